@@ -1,13 +1,13 @@
-/// TODO 
-///     - accept flag at runtime with RPC url 
-///     - Initialise RPC client 
-/// 
+// TODO 
+//     - accept flag at runtime with RPC url 
+//     - Initialise RPC client 
+// 
 
 use std::str::{
     FromStr
 };
-use std::convert::{
-    TryInto
+use std::path::{
+    Path
 };
 
 use solana_sdk::{
@@ -20,8 +20,11 @@ use solana_client::{
 use::pool::{
     structs::PoolState, pool_state::LEGACY_from_client_and_pubkey_via_RPC
 };
+use::mint::{
+    structs::TokenMetadataOverrides
+};
 use::swap::{
-    structs::SwapQuote, structs::SwapParams
+    structs::SwapQuote
 };
 
 use clap::{
@@ -32,6 +35,8 @@ use clap::{
 /// 
 /// Parameters:
 ///     - rpc_url: the RPC url to use for on-chain data fetching
+///     - token_metadata_overrides: optional path to a JSON file of mint symbols and names, 
+///       e.g. token_metadata_overrides.json at the repo root
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -39,6 +44,8 @@ struct Args {
     // - For example, use a custom RPC url from a service like Infura, Alchemy, etc.
     #[arg(long="rpc-url", default_value = "https://api.mainnet-beta.solana.com")]
     rpc_url: String,
+    #[arg(long="token-metadata-overrides")]
+    token_metadata_overrides: Option<String>,
 }
 
 
@@ -47,6 +54,11 @@ fn main(){
     // Parse runtime CLI args 
     let args = Args::parse();
     let rpc_url = args.rpc_url;
+    let overrides: TokenMetadataOverrides = match &args.token_metadata_overrides {
+        Some(path) => TokenMetadataOverrides::from_file(Path::new(path))
+            .expect("Failed to read token metadata overrides"),
+        None => TokenMetadataOverrides::default(),
+    };
 
     // Initialise RPC client
         // *rpc_url converts String -> static str
@@ -55,27 +67,15 @@ fn main(){
 
     let pool_address = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
     let pool_pubkey = Pubkey::from_str(pool_address).expect("Failed to derive pubkey from string");
-    let pool_state: PoolState = LEGACY_from_client_and_pubkey_via_RPC(&rpc_client, &pool_pubkey);
+    let mut pool_state: PoolState = LEGACY_from_client_and_pubkey_via_RPC(&rpc_client, &pool_pubkey)
+        .expect("Failed to populate pool state");
+    pool_state.apply_token_metadata_overrides(&overrides);
     println!("{}", pool_state);
         
-    let params = SwapParams {
-        pool: pool_state,
-        amount: 1_000_000,
-        slippage_bps: 50,
-    };
-
-    // 4MiB stack thread, default is 1MiB which is insufficient.
-    let handle = std::thread::Builder::new()
-        .name("quote".into())
-        .stack_size(4 * 1024 * 1024)        
-        .spawn(move || {
-            let quote: SwapQuote = params.try_into()
-                .expect("failed to build swap quote");
-            println!("Full quote:\n{}", quote);
-        })
-        .expect("failed to spawn thread");
-
-    handle.join().expect("quote thread panicked");
+    // Quote both sides of mint A, quoting borrows the PoolState and runs on the default stack
+    let quote: SwapQuote = SwapQuote::from_pool_state(&pool_state, &pool_state.mint_a_data.pubkey, 1_000_000, 50)
+        .expect("failed to build swap quote");
+    println!("Full quote:\n{}", quote);
 }
 
 
@@ -89,12 +89,14 @@ fn main(){
 ///     - A vector of the populated PoolState structs corresponding to the pool addresses
 /// 
 /// Note: This is a synchronous function, so it will block the main thread.
+#[allow(dead_code, non_snake_case)]
 fn LEGACY_populate_pool_states_via_RPC(
     rpc_client: &RpcClient, pool_addresses: &Vec<Pubkey>
 ) -> Vec<PoolState> {
     let mut pool_states: Vec<_> = Vec::new();
     for pool_address in pool_addresses {
-        let pool_state: PoolState = LEGACY_from_client_and_pubkey_via_RPC(rpc_client, pool_address);
+        let pool_state: PoolState = LEGACY_from_client_and_pubkey_via_RPC(rpc_client, pool_address)
+            .expect("Failed to populate pool state");
         pool_states.push(pool_state);
     }
     pool_states
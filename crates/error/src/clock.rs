@@ -0,0 +1,19 @@
+// Wall clock utils shared by the pool, mint and swap crates ///
+
+use std::time::{
+    SystemTime, UNIX_EPOCH
+};
+
+use crate::error::OrcaQuoterError;
+
+/// Current UNIX timestamp in milliseconds, used to stamp fetches and by the adaptive fee of 
+/// pools with an oracle
+/// 
+/// Returns:
+///     - The milliseconds since UNIX_EPOCH or OrcaQuoterError::SystemClock
+pub fn current_unix_timestamp_ms() -> Result<u64, OrcaQuoterError> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .map_err(|_| OrcaQuoterError::SystemClock)
+}
@@ -0,0 +1,126 @@
+// Shared error type for the pool, mint, swap and liquidity crates ///
+
+use std::fmt;
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind}, rpc_request::RpcError,
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+    }
+};
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::CoreError;
+
+/// Errors returned by every public function of the pool, mint, swap and liquidity crates
+///     - Rpc errors are transport level and safe to retry, RpcRejected errors fail the same way 
+///       on every retry, see is_transient
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrcaQuoterError {
+    /// RPC transport failure, e.g. timeout, rate limit, connection reset or a node still catching up
+    Rpc(String),
+    /// The RPC node rejected the request or returned an unreadable response, e.g. invalid params 
+    /// or an unsupported method
+    RpcRejected(String),
+    /// The account does not exist on chain
+    AccountNotFound(Pubkey),
+    /// The account exists but is owned by an unexpected program, expected lists every accepted owner,
+    /// e.g. both the SPL Token and Token-2022 programs for mints
+    WrongOwner { account: Pubkey, expected: Vec<Pubkey>, found: Pubkey },
+    /// The account data could not be deserialised into the expected struct
+    Deserialisation(String),
+    /// A PDA (tick array, oracle) could not be derived
+    PdaDerivation(String),
+    /// orca_whirlpools_core rejected a quote, carries the core error code
+    Quote(CoreError),
+    /// The system clock is before the UNIX epoch
+    SystemClock,
+    /// Reading or writing local files, e.g. account fixtures or snapshots, failed
+    Io(String),
+    /// A value could not be serialised, e.g. into a snapshot file
+    Serialisation(String),
+    /// A caller supplied argument is out of range, e.g. a tick array window wider than TickArrays holds
+    InvalidArgument(String),
+    /// An account provider call panicked on a worker thread, e.g. a get_multiple_accounts page
+    ProviderPanic(String),
+}
+
+impl OrcaQuoterError {
+    /// Whether retrying the failed call could succeed
+    /// 
+    /// Returns: 
+    ///     - true for RPC transport errors, false for rejected RPC requests and errors in the 
+    ///       on-chain data or the request
+    pub fn is_transient(&self) -> bool {
+        matches!(self, OrcaQuoterError::Rpc(_))
+    }
+}
+
+impl fmt::Display for OrcaQuoterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrcaQuoterError::Rpc(err) => write!(f, "RPC error: {}", err),
+            OrcaQuoterError::RpcRejected(err) => write!(f, "RPC request rejected: {}", err),
+            OrcaQuoterError::AccountNotFound(pubkey) => write!(f, "Account not found: {}", pubkey),
+            OrcaQuoterError::WrongOwner { account, expected, found } => {
+                let expected: Vec<String> = expected.iter().map(|owner| owner.to_string()).collect();
+                match expected.as_slice() {
+                    [owner] => write!(f, "Account {} is owned by {}, expected {}", account, found, owner),
+                    owners => write!(f, "Account {} is owned by {}, expected one of {}", account, found, owners.join(", ")),
+                }
+            },
+            OrcaQuoterError::Deserialisation(err) => write!(f, "Deserialisation error: {}", err),
+            OrcaQuoterError::PdaDerivation(err) => write!(f, "PDA derivation error: {}", err),
+            OrcaQuoterError::Quote(code) => write!(f, "Quote error: {}", code),
+            OrcaQuoterError::SystemClock => write!(f, "System clock is before the UNIX epoch"),
+            OrcaQuoterError::Io(err) => write!(f, "IO error: {}", err),
+            OrcaQuoterError::Serialisation(err) => write!(f, "Serialisation error: {}", err),
+            OrcaQuoterError::InvalidArgument(err) => write!(f, "Invalid argument: {}", err),
+            OrcaQuoterError::ProviderPanic(err) => write!(f, "Provider panicked: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for OrcaQuoterError {}
+
+impl From<ClientError> for OrcaQuoterError {
+    fn from(err: ClientError) -> Self {
+        if is_transient_client_error(&err.kind) {
+            OrcaQuoterError::Rpc(err.to_string())
+        } else {
+            OrcaQuoterError::RpcRejected(err.to_string())
+        }
+    }
+}
+
+
+/// JSON-RPC error codes a healthy node stops returning once it catches up
+const TRANSIENT_RPC_ERROR_CODES: [i64; 4] = [
+    JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET,
+    JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+];
+
+/// Whether retrying the request that failed with a ClientError could succeed
+/// 
+/// Parameters:
+///     - kind: a pointer to the ClientError's kind
+/// 
+/// Returns:
+///     - true for IO errors, HTTP failures without a response, rate limits, 5xx statuses and 
+///       node health JSON-RPC errors, false for everything else, e.g. invalid params (-32602)
+fn is_transient_client_error(kind: &ClientErrorKind) -> bool {
+    match kind {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(err) => match err.status() {
+            Some(status) => status.as_u16() == 429 || status.is_server_error(),
+            // Timeouts and connection failures, an undecodable body fails the same way again
+            None => !err.is_decode() && !err.is_builder(),
+        },
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => TRANSIENT_RPC_ERROR_CODES.contains(code),
+        // Malformed responses, signing and transaction errors
+        _ => false,
+    }
+}
@@ -0,0 +1,61 @@
+// Only ClientErrors that a retry could clear may be reported as transient
+
+use std::io;
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind}, rpc_request::{RpcError, RpcResponseErrorData},
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+};
+
+use error::error::OrcaQuoterError;
+
+/// OrcaQuoterError converted from a JSON-RPC error response with the given code
+fn rpc_response_error(code: i64) -> OrcaQuoterError {
+    OrcaQuoterError::from(ClientError::from(RpcError::RpcResponseError {
+        code,
+        message: "rpc error".to_string(),
+        data: RpcResponseErrorData::Empty,
+    }))
+}
+
+#[test]
+fn io_errors_are_transient() {
+    let err: OrcaQuoterError = OrcaQuoterError::from(ClientError::from(
+        io::Error::new(io::ErrorKind::ConnectionReset, "connection reset by peer")
+    ));
+    assert!(matches!(err, OrcaQuoterError::Rpc(_)));
+    assert!(err.is_transient());
+}
+
+#[test]
+fn node_health_errors_are_transient() {
+    assert!(rpc_response_error(JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY).is_transient());
+}
+
+#[test]
+fn invalid_params_and_unknown_methods_are_not_transient() {
+    // -32602 invalid params, -32601 method not found
+    for code in [-32602, -32601] {
+        let err: OrcaQuoterError = rpc_response_error(code);
+        assert!(matches!(err, OrcaQuoterError::RpcRejected(_)), "{:?}", err);
+        assert!(!err.is_transient());
+    }
+}
+
+#[test]
+fn unreadable_responses_are_not_transient() {
+    let parse_error: OrcaQuoterError = OrcaQuoterError::from(ClientError::from(
+        RpcError::ParseError("RpcResponse".to_string())
+    ));
+    assert!(!parse_error.is_transient());
+
+    let serde_error: OrcaQuoterError = OrcaQuoterError::from(ClientError::from(
+        serde_json::from_str::<u64>("not json").unwrap_err()
+    ));
+    assert!(!serde_error.is_transient());
+
+    let custom_error: OrcaQuoterError = OrcaQuoterError::from(ClientError::from(
+        ClientErrorKind::Custom("unexpected".to_string())
+    ));
+    assert!(!custom_error.is_transient());
+}
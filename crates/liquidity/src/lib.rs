@@ -0,0 +1 @@
+pub mod structs;
\ No newline at end of file
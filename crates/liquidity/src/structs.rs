@@ -0,0 +1,330 @@
+use std::fmt;
+
+use serde::{
+    Deserialize, Serialize
+};
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::{
+    increase_liquidity_quote, increase_liquidity_quote_a, increase_liquidity_quote_b,
+    get_full_range_tick_indexes, get_initializable_tick_index, is_full_range_only, is_tick_initializable,
+    position_status, price_to_sqrt_price, sqrt_price_to_price, sqrt_price_to_tick_index, tick_index_to_price,
+    try_get_token_estimates_from_liquidity,
+    IncreaseLiquidityQuote,
+    PositionStatus,
+    TickRange,
+    TransferFee,
+    MIN_SQRT_PRICE,
+    MAX_SQRT_PRICE
+};
+
+use pool::structs::PoolState;
+use mint::mint::format_ui_amount;
+
+use error::error::OrcaQuoterError;
+
+/// A position's tick range on a whirlpool, both bounds initializable for the pool's tick_spacing
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionRange {
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+}
+
+/// The amount of liquidity to add, either directly or as the budget of one token
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiquidityAmount {
+    // The exact liquidity delta
+    Liquidity(u128),
+    // The most of mint (the pool's mint A or mint B) to deposit, including slippage and transfer fees
+    TokenMax { mint: Pubkey, amount: u64 },
+}
+
+/// Where the pool price lies relative to a PositionRange, which decides the tokens deposited
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RangeStatus {
+    // Price at or below the lower bound, only token A is deposited
+    BelowRange,
+    // Price inside the range, both tokens are deposited
+    InRange,
+    // Price at or above the upper bound, only token B is deposited
+    AboveRange,
+}
+
+/// Serde remote definition of IncreaseLiquidityQuote, orca_whirlpools_core only derives serde behind its wasm feature
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "IncreaseLiquidityQuote")]
+pub struct IncreaseLiquidityQuoteDef {
+    pub liquidity_delta: u128,
+    pub token_est_a: u64,
+    pub token_est_b: u64,
+    pub token_max_a: u64,
+    pub token_max_b: u64,
+}
+
+/// Tokens required to add liquidity to a PositionRange of a whirlpool
+///     - token_est_a/b and token_max_a/b of quote include the Token-2022 transfer fees of the mints,
+///       token_max_a/b add the slippage tolerance on top
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LiquidityIncreaseQuote {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    // Display labels, see MintData::label and PoolState::pair_label
+    pub mint_a_label: String,
+    pub mint_b_label: String,
+    pub pool_label: String,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    pub range: PositionRange,
+    // Prices of token A in UI units of token B, at the range bounds and before the deposit
+    pub price_lower: f64,
+    pub price_upper: f64,
+    pub current_price: f64,
+    pub status: RangeStatus,
+    pub slippage_bps: u16,
+    // Parts of token_est_a and token_est_b withheld by the mints' transfer fees
+    pub transfer_fee_a: u64,
+    pub transfer_fee_b: u64,
+    #[serde(with = "IncreaseLiquidityQuoteDef")]
+    pub quote: IncreaseLiquidityQuote,
+}
+
+/// Parameters of an increase liquidity quote against a borrowed PoolState
+pub struct IncreaseLiquidityParams<'a> {
+    pub pool: &'a PoolState,
+    pub range: PositionRange,
+    pub amount: LiquidityAmount,
+    pub slippage_bps: u16,
+}
+
+impl PositionRange {
+    /// Range between two tick indexes, in either order
+    ///
+    /// Parameters:
+    ///     - pool: a pointer to the PoolState
+    ///     - tick_index_1: one bound, must be a multiple of the pool's tick_spacing
+    ///     - tick_index_2: the other bound, must be a multiple of the pool's tick_spacing
+    ///
+    /// Returns:
+    ///     - The PositionRange or OrcaQuoterError::InvalidArgument if a bound is not initializable,
+    ///       the bounds are equal or the pool only allows full range positions
+    pub fn from_tick_indexes(pool: &PoolState, tick_index_1: i32, tick_index_2: i32) -> Result<PositionRange, OrcaQuoterError> {
+        let tick_spacing: u16 = pool.whirlpool.tick_spacing;
+        let full_range: TickRange = get_full_range_tick_indexes(tick_spacing);
+        let range: PositionRange = PositionRange {
+            tick_lower_index: tick_index_1.min(tick_index_2),
+            tick_upper_index: tick_index_1.max(tick_index_2),
+        };
+
+        for tick_index in [range.tick_lower_index, range.tick_upper_index] {
+            if !is_tick_initializable(tick_index, tick_spacing)
+                || !(full_range.tick_lower_index..=full_range.tick_upper_index).contains(&tick_index) {
+                return Err(OrcaQuoterError::InvalidArgument(format!(
+                    "Tick index {} is not an initializable tick for tick spacing {}", tick_index, tick_spacing
+                )));
+            }
+        }
+        if range.tick_lower_index == range.tick_upper_index {
+            return Err(OrcaQuoterError::InvalidArgument(format!(
+                "Position range is empty, both bounds are tick {}", range.tick_lower_index
+            )));
+        }
+        if is_full_range_only(tick_spacing) && range != PositionRange::full_range(pool) {
+            return Err(OrcaQuoterError::InvalidArgument(format!(
+                "Pools with tick spacing {} only allow full range positions", tick_spacing
+            )));
+        }
+        Ok(range)
+    }
+
+    /// Range between two prices, widened outwards to the nearest initializable ticks
+    ///
+    /// Parameters:
+    ///     - pool: a pointer to the PoolState
+    ///     - price_1: one bound, the price of token A in UI units of token B
+    ///     - price_2: the other bound, same units as price_1
+    ///
+    /// Returns:
+    ///     - The PositionRange or OrcaQuoterError::InvalidArgument if a price is not positive or
+    ///       the snapped range is invalid, see from_tick_indexes
+    pub fn from_prices(pool: &PoolState, price_1: f64, price_2: f64) -> Result<PositionRange, OrcaQuoterError> {
+        if !(price_1.is_finite() && price_2.is_finite() && price_1 > 0.0 && price_2 > 0.0) {
+            return Err(OrcaQuoterError::InvalidArgument(format!(
+                "Position prices must be positive, got {} and {}", price_1, price_2
+            )));
+        }
+        let tick_spacing: u16 = pool.whirlpool.tick_spacing;
+        let full_range: TickRange = get_full_range_tick_indexes(tick_spacing);
+        let (decimals_a, decimals_b): (u8, u8) = (pool.mint_a_data.decimals, pool.mint_b_data.decimals);
+
+        // Prices beyond the tick range have no tick index, their sqrt prices are clamped to it first
+        let tick_index = |price: f64| -> i32 {
+            sqrt_price_to_tick_index(price_to_sqrt_price(price, decimals_a, decimals_b).clamp(MIN_SQRT_PRICE, MAX_SQRT_PRICE))
+        };
+
+        // The lower bound rounds down and the upper bound rounds up, so the range covers both prices
+        let tick_lower_index: i32 = get_initializable_tick_index(tick_index(price_1.min(price_2)), tick_spacing, Some(false));
+        let tick_upper_index: i32 = get_initializable_tick_index(tick_index(price_1.max(price_2)), tick_spacing, Some(true));
+        PositionRange::from_tick_indexes(
+            pool,
+            tick_lower_index.max(full_range.tick_lower_index),
+            tick_upper_index.min(full_range.tick_upper_index)
+        )
+    }
+
+    /// The widest range of the pool's tick_spacing
+    ///
+    /// Parameters:
+    ///     - pool: a pointer to the PoolState
+    ///
+    /// Returns:
+    ///     - The full range PositionRange
+    pub fn full_range(pool: &PoolState) -> PositionRange {
+        let full_range: TickRange = get_full_range_tick_indexes(pool.whirlpool.tick_spacing);
+        PositionRange {
+            tick_lower_index: full_range.tick_lower_index,
+            tick_upper_index: full_range.tick_upper_index,
+        }
+    }
+}
+
+impl LiquidityIncreaseQuote {
+    /// Quote the tokens required to add liquidity to a range of a borrowed PoolState
+    ///     - Slippage bounds the token amounts at the current price, the price range of the
+    ///       instruction itself is not modelled
+    ///
+    /// Parameters:
+    ///     - pool: a pointer to the PoolState
+    ///     - range: the position's tick range, see PositionRange
+    ///     - amount: the liquidity delta or one token's budget, see LiquidityAmount
+    ///     - slippage_bps: the slippage tolerance in basis points
+    ///
+    /// Returns:
+    ///     - The LiquidityIncreaseQuote, OrcaQuoterError::InvalidArgument if the budget's mint is not
+    ///       one of the pool's mints or buys no liquidity in the range, or OrcaQuoterError::Quote
+    pub fn from_pool_state(
+        pool: &PoolState, range: PositionRange, amount: LiquidityAmount, slippage_bps: u16
+    ) -> Result<LiquidityIncreaseQuote, OrcaQuoterError> {
+        let sqrt_price: u128 = pool.whirlpool.sqrt_price;
+        let (tick_lower_index, tick_upper_index): (i32, i32) = (range.tick_lower_index, range.tick_upper_index);
+        let transfer_fee_a: Option<TransferFee> = pool.mint_a_data.core_transfer_fee();
+        let transfer_fee_b: Option<TransferFee> = pool.mint_b_data.core_transfer_fee();
+
+        // 1) The price relative to the range decides which tokens are deposited
+        let status: RangeStatus = match position_status(sqrt_price, tick_lower_index, tick_upper_index) {
+            PositionStatus::PriceBelowRange => RangeStatus::BelowRange,
+            PositionStatus::PriceInRange => RangeStatus::InRange,
+            PositionStatus::PriceAboveRange => RangeStatus::AboveRange,
+            PositionStatus::Invalid => return Err(OrcaQuoterError::InvalidArgument(format!(
+                "Position range [{}, {}] is empty", tick_lower_index, tick_upper_index
+            ))),
+        };
+
+        // 2) Quote the liquidity delta, a token budget is scaled down so that its slippage bound
+        // stays within the budget
+        let quote: IncreaseLiquidityQuote = match amount {
+            LiquidityAmount::Liquidity(liquidity_delta) => increase_liquidity_quote(
+                liquidity_delta, slippage_bps, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b
+            ),
+            LiquidityAmount::TokenMax { mint, amount } => {
+                let budget: u64 = (amount as u128 * 10_000 / (10_000 + slippage_bps as u128)) as u64;
+                if mint == pool.mint_a_data.pubkey {
+                    increase_liquidity_quote_a(
+                        budget, slippage_bps, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b
+                    ).map(|quote| IncreaseLiquidityQuote { token_max_a: quote.token_max_a.min(amount), ..quote })
+                } else if mint == pool.mint_b_data.pubkey {
+                    increase_liquidity_quote_b(
+                        budget, slippage_bps, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b
+                    ).map(|quote| IncreaseLiquidityQuote { token_max_b: quote.token_max_b.min(amount), ..quote })
+                } else {
+                    return Err(OrcaQuoterError::InvalidArgument(format!(
+                        "Mint {} is not one of the pool's mints {} and {}",
+                        mint, pool.mint_a_data.pubkey, pool.mint_b_data.pubkey
+                    )));
+                }
+            }
+        }.map_err(OrcaQuoterError::Quote)?;
+
+        // 3) A budget of the token the range does not take at the current price buys no liquidity
+        if let LiquidityAmount::TokenMax { mint, amount } = amount && quote.liquidity_delta == 0 {
+            return Err(OrcaQuoterError::InvalidArgument(format!(
+                "A budget of {} of mint {} adds no liquidity to range [{}, {}] while the price is {}",
+                amount, mint, tick_lower_index, tick_upper_index, status
+            )));
+        }
+
+        // 4) The transfer fees are the difference to the amounts the pool receives
+        let (pool_est_a, pool_est_b): (u64, u64) = try_get_token_estimates_from_liquidity(
+            quote.liquidity_delta, sqrt_price, tick_lower_index, tick_upper_index, true
+        ).map_err(OrcaQuoterError::Quote)?;
+
+        let (decimals_a, decimals_b): (u8, u8) = (pool.mint_a_data.decimals, pool.mint_b_data.decimals);
+        Ok(LiquidityIncreaseQuote {
+            mint_a: pool.mint_a_data.pubkey,
+            mint_b: pool.mint_b_data.pubkey,
+            mint_a_label: pool.mint_a_data.label(),
+            mint_b_label: pool.mint_b_data.label(),
+            pool_label: pool.pair_label(),
+            decimals_a,
+            decimals_b,
+            range,
+            price_lower: tick_index_to_price(tick_lower_index, decimals_a, decimals_b),
+            price_upper: tick_index_to_price(tick_upper_index, decimals_a, decimals_b),
+            current_price: sqrt_price_to_price(sqrt_price, decimals_a, decimals_b),
+            status,
+            slippage_bps,
+            transfer_fee_a: quote.token_est_a.saturating_sub(pool_est_a),
+            transfer_fee_b: quote.token_est_b.saturating_sub(pool_est_b),
+            quote,
+        })
+    }
+}
+
+/// TryFrom implementation for IncreaseLiquidityParams to LiquidityIncreaseQuote
+impl TryFrom<IncreaseLiquidityParams<'_>> for LiquidityIncreaseQuote {
+    type Error = OrcaQuoterError;
+
+    fn try_from(params: IncreaseLiquidityParams<'_>) -> Result<Self, Self::Error> {
+        LiquidityIncreaseQuote::from_pool_state(params.pool, params.range, params.amount, params.slippage_bps)
+    }
+}
+
+/// Pretty printing for RangeStatus, e.g. "in range"
+impl fmt::Display for RangeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeStatus::BelowRange => write!(f, "below range"),
+            RangeStatus::InRange => write!(f, "in range"),
+            RangeStatus::AboveRange => write!(f, "above range"),
+        }
+    }
+}
+
+/// Pretty printing for LiquidityIncreaseQuote
+impl fmt::Display for LiquidityIncreaseQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let a_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, self.decimals_a), self.mint_a_label);
+        let b_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, self.decimals_b), self.mint_b_label);
+
+        writeln!(f, "LiquidityIncreaseQuote {} {{", self.pool_label)?;
+        writeln!(f, "  mint_a:       {} ({})", self.mint_a_label, self.mint_a)?;
+        writeln!(f, "  mint_b:       {} ({})", self.mint_b_label, self.mint_b)?;
+        writeln!(
+            f, "  range:        ticks [{}, {}], prices {:.6} - {:.6} {} per {}",
+            self.range.tick_lower_index, self.range.tick_upper_index, self.price_lower, self.price_upper,
+            self.mint_b_label, self.mint_a_label
+        )?;
+        writeln!(f, "  price:        {:.6} ({})", self.current_price, self.status)?;
+        writeln!(f, "  liquidity:    {}", self.quote.liquidity_delta)?;
+        writeln!(f, "  slippage_bps: {}", self.slippage_bps)?;
+        writeln!(
+            f, "  deposit a:    {} (max {}), transfer fee {}",
+            a_ui(self.quote.token_est_a), a_ui(self.quote.token_max_a), a_ui(self.transfer_fee_a)
+        )?;
+        writeln!(
+            f, "  deposit b:    {} (max {}), transfer fee {}",
+            b_ui(self.quote.token_est_b), b_ui(self.quote.token_max_b), b_ui(self.transfer_fee_b)
+        )?;
+        writeln!(f, "  raw quote:    {:?}", self.quote)?;
+        writeln!(f, "}}")
+    }
+}
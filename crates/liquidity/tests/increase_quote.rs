@@ -0,0 +1,200 @@
+// LiquidityIncreaseQuote::from_pool_state against orca_whirlpools_core's increase liquidity quotes,
+// below, in and above the range, with token budgets scaled for slippage and capped at the budget
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey
+};
+
+use orca_whirlpools_core::{
+    increase_liquidity_quote, increase_liquidity_quote_a, increase_liquidity_quote_b, tick_index_to_price,
+    IncreaseLiquidityQuote, TickArrayFacade, TickArrays, TickFacade, TransferFee, WhirlpoolFacade, TICK_ARRAY_SIZE
+};
+
+use error::error::OrcaQuoterError;
+use liquidity::structs::{
+    LiquidityAmount, LiquidityIncreaseQuote, PositionRange, RangeStatus
+};
+use mint::structs::{
+    MintData, TransferFeeData
+};
+use pool::structs::{
+    ComponentFetch, FetchMetadata, PoolState, TickArrayWindow
+};
+
+const TICK_SPACING: u16 = 64;
+const LIQUIDITY_DELTA: u128 = 1_000_000_000;
+const SLIPPAGE_BPS: u16 = 100;
+const BUDGET: u64 = 1_000_000;
+
+/// Initialised 6 decimal mint with a fresh pubkey and no transfer fee
+fn mint_data() -> MintData {
+    MintData {
+        decimals: 6,
+        is_initialized: true,
+        ..MintData::unfetched(Pubkey::new_unique())
+    }
+}
+
+/// PoolState at tick 0, i.e. a price of 1.0, the quotes only read the whirlpool and the mints
+fn pool_state() -> PoolState {
+    let fetch: ComponentFetch = ComponentFetch::from_missing_account(0, 0, "test");
+    PoolState {
+        whirlpool: WhirlpoolFacade {
+            tick_spacing: TICK_SPACING,
+            fee_tier_index_seed: TICK_SPACING.to_le_bytes(),
+            fee_rate: 3000,
+            liquidity: 1_000_000_000_000,
+            // sqrt(1.0) in Q64.64
+            sqrt_price: 1 << 64,
+            tick_current_index: 0,
+            ..WhirlpoolFacade::default()
+        },
+        current_tick_array: TickArrays::One(TickArrayFacade {
+            start_tick_index: 0,
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        }),
+        oracle: None,
+        mint_a_data: mint_data(),
+        mint_b_data: mint_data(),
+        metadata: FetchMetadata {
+            commitment: CommitmentConfig::confirmed(),
+            tick_array_window: TickArrayWindow::new(0, 0).unwrap(),
+            whirlpool: fetch.clone(),
+            tick_arrays: vec![fetch],
+            oracle: None,
+            mint_a: None,
+            mint_b: None,
+        },
+    }
+}
+
+fn range(pool: &PoolState, tick_lower_index: i32, tick_upper_index: i32) -> PositionRange {
+    PositionRange::from_tick_indexes(pool, tick_lower_index, tick_upper_index).unwrap()
+}
+
+/// Core quote of a budget of token A, with the pool's transfer fees
+fn core_quote_a(pool: &PoolState, range: PositionRange, budget: u64) -> IncreaseLiquidityQuote {
+    increase_liquidity_quote_a(
+        budget, SLIPPAGE_BPS, pool.whirlpool.sqrt_price, range.tick_lower_index, range.tick_upper_index,
+        pool.mint_a_data.core_transfer_fee(), pool.mint_b_data.core_transfer_fee()
+    ).unwrap()
+}
+
+#[test]
+fn the_range_status_decides_the_tokens_deposited() {
+    let pool: PoolState = pool_state();
+    // The price of 1.0 at tick 0 lies below, inside and above these ranges
+    for ((tick_lower_index, tick_upper_index), status) in [
+        ((64, 640), RangeStatus::BelowRange), ((-640, 640), RangeStatus::InRange), ((-640, -64), RangeStatus::AboveRange),
+    ] {
+        let range: PositionRange = range(&pool, tick_lower_index, tick_upper_index);
+        let quote: LiquidityIncreaseQuote = LiquidityIncreaseQuote::from_pool_state(
+            &pool, range, LiquidityAmount::Liquidity(LIQUIDITY_DELTA), SLIPPAGE_BPS
+        ).unwrap();
+        let expected: IncreaseLiquidityQuote = increase_liquidity_quote(
+            LIQUIDITY_DELTA, SLIPPAGE_BPS, pool.whirlpool.sqrt_price, tick_lower_index, tick_upper_index, None, None
+        ).unwrap();
+
+        assert_eq!(quote.status, status);
+        assert_eq!(quote.quote, expected);
+        assert_eq!(quote.quote.token_est_a > 0, status != RangeStatus::AboveRange, "{}", status);
+        assert_eq!(quote.quote.token_est_b > 0, status != RangeStatus::BelowRange, "{}", status);
+        assert_eq!(quote.price_lower, tick_index_to_price(tick_lower_index, 6, 6));
+        assert_eq!((quote.transfer_fee_a, quote.transfer_fee_b), (0, 0));
+    }
+}
+
+#[test]
+fn a_token_budget_is_scaled_down_by_the_slippage_tolerance() {
+    let pool: PoolState = pool_state();
+    let range: PositionRange = range(&pool, -640, 640);
+    let budget_b: LiquidityAmount = LiquidityAmount::TokenMax { mint: pool.mint_b_data.pubkey, amount: BUDGET };
+    let quote: LiquidityIncreaseQuote = LiquidityIncreaseQuote::from_pool_state(&pool, range, budget_b, SLIPPAGE_BPS).unwrap();
+
+    // 1_000_000 * 10_000 / 10_100, so the 1% slippage bound lands back on the budget
+    let scaled_budget: u64 = 990_099;
+    assert_eq!(BUDGET as u128 * 10_000 / (10_000 + SLIPPAGE_BPS as u128), scaled_budget as u128);
+    let expected: IncreaseLiquidityQuote = increase_liquidity_quote_b(
+        scaled_budget, SLIPPAGE_BPS, pool.whirlpool.sqrt_price, -640, 640, None, None
+    ).unwrap();
+    assert_eq!(quote.quote.liquidity_delta, expected.liquidity_delta);
+    assert!(quote.quote.token_est_b <= scaled_budget + 1);
+    assert!(quote.quote.token_max_b <= BUDGET);
+}
+
+#[test]
+fn the_slippage_bound_never_exceeds_the_budget() {
+    // The scaled budget's slippage bound lands on the budget at most, token_max_a is capped at the
+    // budget in case the transfer fee's rounding ever pushes it over
+    let mut pool: PoolState = pool_state();
+    for (transfer_fee_bps, maximum_fee) in [(0, 0), (137, u64::MAX), (500, 3)] {
+        pool.mint_a_data.transfer_fee = Some(TransferFeeData { epoch: 0, maximum_fee, transfer_fee_basis_points: transfer_fee_bps });
+        for (tick_lower_index, tick_upper_index) in [(-640, 640), (64, 640)] {
+            let range: PositionRange = range(&pool, tick_lower_index, tick_upper_index);
+            for amount in (1_000..1_200).chain(BUDGET..BUDGET + 200) {
+                let budget_a: LiquidityAmount = LiquidityAmount::TokenMax { mint: pool.mint_a_data.pubkey, amount };
+                let quote: LiquidityIncreaseQuote = LiquidityIncreaseQuote::from_pool_state(&pool, range, budget_a, SLIPPAGE_BPS).unwrap();
+                let scaled_budget: u64 = (amount as u128 * 10_000 / (10_000 + SLIPPAGE_BPS as u128)) as u64;
+                let core: IncreaseLiquidityQuote = core_quote_a(&pool, range, scaled_budget);
+
+                assert_eq!(quote.quote, IncreaseLiquidityQuote { token_max_a: core.token_max_a.min(amount), ..core });
+                assert!(quote.quote.token_max_a <= amount);
+            }
+        }
+    }
+}
+
+#[test]
+fn transfer_fees_are_reported_on_top_of_the_pool_amounts() {
+    let mut pool: PoolState = pool_state();
+    pool.mint_a_data.transfer_fee = Some(TransferFeeData { epoch: 0, maximum_fee: u64::MAX, transfer_fee_basis_points: 100 });
+    assert_eq!(pool.mint_a_data.core_transfer_fee(), Some(TransferFee::new_with_max(100, u64::MAX)));
+    assert_eq!(pool.mint_b_data.core_transfer_fee(), None);
+
+    let quote: LiquidityIncreaseQuote = LiquidityIncreaseQuote::from_pool_state(
+        &pool, range(&pool, -640, 640), LiquidityAmount::Liquidity(LIQUIDITY_DELTA), SLIPPAGE_BPS
+    ).unwrap();
+    let without_fee: LiquidityIncreaseQuote = LiquidityIncreaseQuote::from_pool_state(
+        &pool_state(), range(&pool, -640, 640), LiquidityAmount::Liquidity(LIQUIDITY_DELTA), SLIPPAGE_BPS
+    ).unwrap();
+    assert_eq!(quote.quote.token_est_a - quote.transfer_fee_a, without_fee.quote.token_est_a);
+    assert!(quote.transfer_fee_a > 0);
+    assert_eq!(quote.transfer_fee_b, 0);
+}
+
+#[test]
+fn budgets_that_buy_no_liquidity_are_rejected() {
+    let pool: PoolState = pool_state();
+    let below: PositionRange = range(&pool, 64, 640);
+    let quote = |mint: Pubkey| LiquidityIncreaseQuote::from_pool_state(
+        &pool, below, LiquidityAmount::TokenMax { mint, amount: BUDGET }, SLIPPAGE_BPS
+    );
+    // Below the range only token A is deposited
+    assert!(quote(pool.mint_a_data.pubkey).is_ok());
+    assert!(matches!(quote(pool.mint_b_data.pubkey), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(quote(Pubkey::new_unique()), Err(OrcaQuoterError::InvalidArgument(_))));
+}
+
+#[test]
+fn price_ranges_widen_outwards_to_initializable_ticks() {
+    let pool: PoolState = pool_state();
+    // Ticks 100 and 1_000 snap out to 64 and 1_024, in either order
+    let (price_100, price_1000): (f64, f64) = (tick_index_to_price(100, 6, 6), tick_index_to_price(1_000, 6, 6));
+    let expected: PositionRange = PositionRange { tick_lower_index: 64, tick_upper_index: 1_024 };
+    assert_eq!(PositionRange::from_prices(&pool, price_100, price_1000).unwrap(), expected);
+    assert_eq!(PositionRange::from_prices(&pool, price_1000, price_100).unwrap(), expected);
+
+    // Negative ticks round away from zero on the lower bound and towards it on the upper bound
+    let (price_minus_100, price_minus_30): (f64, f64) = (tick_index_to_price(-100, 6, 6), tick_index_to_price(-30, 6, 6));
+    assert_eq!(
+        PositionRange::from_prices(&pool, price_minus_100, price_minus_30).unwrap(),
+        PositionRange { tick_lower_index: -128, tick_upper_index: 0 }
+    );
+
+    // Prices beyond the tick range clamp to the full range
+    assert_eq!(PositionRange::from_prices(&pool, 1e-40, 1e40).unwrap(), PositionRange::full_range(&pool));
+
+    for (price_1, price_2) in [(0.0, 1.0), (-1.0, 1.0), (f64::NAN, 1.0), (1.0, f64::INFINITY)] {
+        assert!(matches!(PositionRange::from_prices(&pool, price_1, price_2), Err(OrcaQuoterError::InvalidArgument(_))));
+    }
+}
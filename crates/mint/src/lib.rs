@@ -1,2 +1,3 @@
 pub mod mint; 
+pub mod metadata;
 pub mod structs;
\ No newline at end of file
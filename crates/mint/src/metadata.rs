@@ -0,0 +1,111 @@
+/// Token metadata (symbol, name) resolution ///
+///
+use solana_sdk::{
+    pubkey, pubkey::Pubkey, account::Account
+};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::structs::{
+    MintData, TokenMetadataSource
+};
+
+/// The Metaplex Token Metadata program, owner of the metadata PDAs of SPL Token mints
+pub const METAPLEX_TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+// Offset of the borsh encoded name in a Metaplex metadata account: key (1), update authority (32), mint (32)
+const METAPLEX_MINT_OFFSET: usize = 1 + 32;
+const METAPLEX_NAME_OFFSET: usize = METAPLEX_MINT_OFFSET + 32;
+
+
+/// Derive the Metaplex Token Metadata PDA of a mint
+///
+/// Parameters:
+///     - mint_pubkey: a pointer to the mint's pubkey
+///
+/// Returns:
+///     - The metadata account's pubkey
+pub fn metaplex_metadata_address(mint_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", METAPLEX_TOKEN_METADATA_PROGRAM_ID.as_ref(), mint_pubkey.as_ref()],
+        &METAPLEX_TOKEN_METADATA_PROGRAM_ID
+    ).0
+}
+
+
+/// Fill a mint's symbol and name from its Metaplex metadata account
+///     - Metadata already resolved from the Token-2022 extension is kept, the extension is
+///       authoritative for Token-2022 mints
+///
+/// Parameters:
+///     - mint_data: a mutable pointer to the mint's MintData
+///     - metadata_account: the mint's Metaplex metadata account, None if it does not exist
+pub fn apply_metaplex_metadata(mint_data: &mut MintData, metadata_account: Option<&Account>) {
+    if mint_data.metadata_source.is_some() {
+        return;
+    }
+    let Some(account) = metadata_account else {
+        return;
+    };
+    if account.owner != METAPLEX_TOKEN_METADATA_PROGRAM_ID {
+        return;
+    }
+    if let Some((name, symbol)) = metaplex_name_and_symbol(&mint_data.pubkey, &account.data) {
+        mint_data.symbol = Some(symbol);
+        mint_data.name = Some(name);
+        mint_data.metadata_source = Some(TokenMetadataSource::Metaplex);
+    }
+}
+
+
+/// Read the name and symbol of the Token-2022 TokenMetadata extension stored on the mint
+///
+/// Parameters:
+///     - mint_pubkey: a pointer to the mint's pubkey, the extension must name the same mint
+///     - state: a pointer to the unpacked mint with its TLV data
+///
+/// Returns:
+///     - The (name, symbol) pair, None if the extension is absent, malformed or has an empty symbol
+pub(crate) fn token_2022_name_and_symbol(
+    mint_pubkey: &Pubkey, state: &StateWithExtensions<Mint>
+) -> Option<(String, String)> {
+    let token_metadata: TokenMetadata = state.get_variable_len_extension::<TokenMetadata>().ok()?;
+    if token_metadata.mint != *mint_pubkey {
+        return None;
+    }
+    non_empty_name_and_symbol(&token_metadata.name, &token_metadata.symbol)
+}
+
+
+// Decode the name and symbol of a Metaplex metadata account, rejecting metadata of another mint
+fn metaplex_name_and_symbol(mint_pubkey: &Pubkey, data: &[u8]) -> Option<(String, String)> {
+    let mint: &[u8] = data.get(METAPLEX_MINT_OFFSET..METAPLEX_NAME_OFFSET)?;
+    if mint != mint_pubkey.as_ref() {
+        return None;
+    }
+    let (name, symbol_offset): (String, usize) = borsh_string_at(data, METAPLEX_NAME_OFFSET)?;
+    let (symbol, _): (String, usize) = borsh_string_at(data, symbol_offset)?;
+    non_empty_name_and_symbol(&name, &symbol)
+}
+
+// Read a borsh string (u32 little endian length, utf8 bytes) at offset, returning it with the offset after it
+fn borsh_string_at(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let len_bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+    let start: usize = offset + 4;
+    let end: usize = start.checked_add(u32::from_le_bytes(len_bytes) as usize)?;
+    let value: String = String::from_utf8_lossy(data.get(start..end)?).into_owned();
+    Some((value, end))
+}
+
+// Metaplex pads names and symbols with NUL bytes to a fixed width, so both are trimmed
+fn non_empty_name_and_symbol(name: &str, symbol: &str) -> Option<(String, String)> {
+    let name: &str = name.trim_matches(char::from(0)).trim();
+    let symbol: &str = symbol.trim_matches(char::from(0)).trim();
+    match symbol.is_empty() {
+        true => None,
+        false => Some((name.to_string(), symbol.to_string())),
+    }
+}
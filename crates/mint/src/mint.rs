@@ -1,49 +1,400 @@
 /// Mint related utils ///
-/// 
-use std::fmt;
-
-use solana_client::{
-    rpc_client::RpcClient
+///
+use std::collections::{
+    HashMap, HashSet
 };
+
+use futures::executor::block_on;
 use solana_sdk::{
-    pubkey::Pubkey, account::Account, program_pack::Pack, program_option::COption
+    pubkey::Pubkey, account::{Account, from_account}, program_option::COption, hash::hash,
+    sysvar::{self, clock::Clock}
+};
+use spl_token_2022::{
+    extension::{
+        BaseStateWithExtensions, StateWithExtensions, ExtensionType, transfer_fee::{TransferFee, TransferFeeConfig},
+        transfer_hook::TransferHook, permanent_delegate::PermanentDelegate, non_transferable::NonTransferable,
+        interest_bearing_mint::InterestBearingConfig, default_account_state::DefaultAccountState,
+        metadata_pointer::MetadataPointer, mint_close_authority::MintCloseAuthority
+    },
+    state::{Mint, AccountState}
 };
-use spl_token::state::{
-    Mint
-}; 
 
-use crate::structs::MintData;
+use crate::structs::{
+    MintData, TransferFeeConfigData, TransferFeeData, MintExtensions, InterestBearingData, MetadataPointerData,
+    MintCache, TokenMetadataSource, FetchedMint
+};
+use crate::metadata::{
+    metaplex_metadata_address, apply_metaplex_metadata, token_2022_name_and_symbol
+};
 
-///  Populates, via given RPC client, and serialises MintData struct from the mint's pubkey
-/// 
-/// Parameters: 
-///     - client: The RPC client used for population 
+use error::{
+    error::OrcaQuoterError, clock::current_unix_timestamp_ms
+};
+use provider::{
+    provider::AccountProvider, nonblocking::{AsyncAccountProvider, BlockingAccountProvider}, structs::AtSlot
+};
+
+///  Populates, via given account provider, and serialises MintData struct from the mint's pubkey
+///     - Fetched through mint_data_from_pubkeys_async, alongside the mint's Metaplex metadata PDA 
+///       and the Clock sysvar
+///
+/// Parameters:
+///     - provider: The account provider (e.g. RpcClient) used for population
 ///     - mint_pubkey: The pubkey of the mint used
-/// 
-/// Returns: 
-///     - A populated MintData struct
-pub fn mint_data_from_client_and_pubkey(client: &RpcClient, mint_pubkey: &Pubkey) -> MintData{
-    // Fetch account with client & serialise into Mint struct
-    let account: Account = client.get_account(&mint_pubkey).unwrap(); 
-    let state: Mint = Mint::unpack(&account.data)
-        .expect("Failed to deserialize SPL‐Token Mint account");
+///
+/// Returns:
+///     - A populated MintData struct, at the slot the mint was read at, or an OrcaQuoterError
+pub fn mint_data_from_client_and_pubkey<P: AccountProvider + Sync + ?Sized>(
+    provider: &P, mint_pubkey: &Pubkey
+) -> Result<AtSlot<MintData>, OrcaQuoterError> {
+    let mut fetched_mints: HashMap<Pubkey, Result<FetchedMint, OrcaQuoterError>> = block_on(
+        mint_data_from_pubkeys_async(&BlockingAccountProvider(provider), &[*mint_pubkey], None, None)
+    )?;
+    let fetched_mint: FetchedMint = fetched_mints.remove(mint_pubkey)
+        .unwrap_or(Err(OrcaQuoterError::AccountNotFound(*mint_pubkey)))?;
+    Ok(AtSlot {
+        slot: fetched_mint.slot,
+        value: fetched_mint.mint_data
+    })
+}
+
+
+/// Populates, via given account provider, MintData structs for several mints in as few requests as possible
+///     - Blocking wrapper over mint_data_from_pubkeys_async without a cache
+///
+/// Parameters:
+///     - provider: The account provider (e.g. RpcClient) used for population
+///     - mint_pubkeys: The pubkeys of the mints, duplicates allowed
+///
+/// Returns:
+///     - A vector of MintData or the mint's OrcaQuoterError, in the order of mint_pubkeys
+pub fn mint_data_from_pubkeys<P: AccountProvider + Sync + ?Sized>(
+    provider: &P, mint_pubkeys: &[Pubkey]
+) -> Vec<Result<MintData, OrcaQuoterError>> {
+    mint_data_from_pubkeys_with_cache(provider, mint_pubkeys, None)
+}
+
+
+/// Populates MintData structs like mint_data_from_pubkeys, serving and storing mints via a MintCache
+///     - Blocking wrapper over mint_data_from_pubkeys_async
+///
+/// Parameters:
+///     - provider: The account provider (e.g. RpcClient) used for population
+///     - mint_pubkeys: The pubkeys of the mints, duplicates allowed
+///     - cache: The MintCache to read from and populate, e.g. MintCache::global(), None to always fetch
+///
+/// Returns:
+///     - A vector of MintData or the mint's OrcaQuoterError, in the order of mint_pubkeys
+pub fn mint_data_from_pubkeys_with_cache<P: AccountProvider + Sync + ?Sized>(
+    provider: &P, mint_pubkeys: &[Pubkey], cache: Option<&MintCache>
+) -> Vec<Result<MintData, OrcaQuoterError>> {
+    match block_on(mint_data_from_pubkeys_async(&BlockingAccountProvider(provider), mint_pubkeys, cache, None)) {
+        Ok(fetched_mints) => mint_pubkeys.iter()
+            .map(|mint_pubkey| match fetched_mints.get(mint_pubkey) {
+                Some(fetched_mint) => fetched_mint.clone().map(|fetched_mint| fetched_mint.mint_data),
+                None => Err(OrcaQuoterError::AccountNotFound(*mint_pubkey)),
+            })
+            .collect(),
+        // A failed request fails every mint that cannot be served from the cache
+        Err(err) => mint_pubkeys.iter()
+            .map(|mint_pubkey| cache.and_then(|cache| cache.get(mint_pubkey)).ok_or_else(|| err.clone()))
+            .collect(),
+    }
+}
+
+
+/// Populates, via given async account provider, the MintData of several mints with their fetches
+///     - The single batch fetch behind every mint loader, including populate_pool_states_async
+///     - Pubkeys are deduplicated, cached mints are served from the cache and the rest fetched with 
+///       get_multiple_accounts_paged, alongside their Metaplex metadata PDAs and the Clock sysvar
+///     - Mint accounts not owned by the SPL Token or Token-2022 program fail with WrongOwner
+///
+/// Parameters:
+///     - provider: The async account provider (e.g. a nonblocking RpcClient) used for population
+///     - mint_pubkeys: The pubkeys of the mints, duplicates allowed
+///     - cache: The MintCache to read from and populate, e.g. MintCache::global(), None to always fetch
+///     - max_concurrency: the maximum number of pages in flight at once, None awaits pages one after another
+///
+/// Returns:
+///     - A HashMap from every distinct mint pubkey to its FetchedMint or the mint's OrcaQuoterError, 
+///       or a batch level OrcaQuoterError if the fetch itself fails
+pub async fn mint_data_from_pubkeys_async<P: AsyncAccountProvider + ?Sized>(
+    provider: &P, mint_pubkeys: &[Pubkey], cache: Option<&MintCache>, max_concurrency: Option<usize>
+) -> Result<HashMap<Pubkey, Result<FetchedMint, OrcaQuoterError>>, OrcaQuoterError> {
+    // Phase 1: Deduplicate the pubkeys and split off the cached mints
+    let mut seen: HashSet<Pubkey> = HashSet::new();
+    let mut fetched_mints: HashMap<Pubkey, Result<FetchedMint, OrcaQuoterError>> = HashMap::new();
+    let mut pubkeys_to_fetch: Vec<Pubkey> = Vec::new();
+    for mint_pubkey in mint_pubkeys.iter().filter(|mint_pubkey| seen.insert(**mint_pubkey)) {
+        match cache.and_then(|cache| cache.get_fetched(mint_pubkey)) {
+            Some(cached) => { fetched_mints.insert(*mint_pubkey, Ok(cached)); },
+            None => pubkeys_to_fetch.push(*mint_pubkey),
+        }
+    }
+    if pubkeys_to_fetch.is_empty() {
+        return Ok(fetched_mints);
+    }
+
+    // Phase 2: Fetch the remaining mints with account provider (order preserving)
+        // Laid out as [mints.., Metaplex metadata PDAs.., clock], the Clock sysvar is appended so 
+        // Token-2022 transfer fees resolve at the fetched epoch, providers without it (e.g. fixtures) 
+        // fall back to the newer fee
+    let endpoint: String = provider.endpoint();
+    let mut pubkeys_with_metadata: Vec<Pubkey> = pubkeys_to_fetch.clone();
+    pubkeys_with_metadata.extend(pubkeys_to_fetch.iter().map(metaplex_metadata_address));
+    pubkeys_with_metadata.push(sysvar::clock::id());
+    let mut accounts: Vec<AtSlot<Option<Account>>> = provider.get_multiple_accounts_paged(
+        &pubkeys_with_metadata, max_concurrency
+    ).await?;
+    let fetched_at_ms: u64 = current_unix_timestamp_ms()?;
+    let epoch: Option<u64> = accounts.pop()
+        .and_then(|clock_account| clock_account.value)
+        .and_then(|clock_account| epoch_from_clock_account(&clock_account));
+    let (mint_accounts, metadata_accounts) = accounts.split_at(pubkeys_to_fetch.len());
+
+    // Phase 3: Deserialise into MintData, verifying the token program owner, and cache the successes
+        // A missing metadata account only leaves the symbol unset
+    for ((mint_pubkey, account), metadata_account) in pubkeys_to_fetch.iter()
+        .zip(mint_accounts.iter())
+        .zip(metadata_accounts.iter())
+    {
+        let fetched_mint: Result<FetchedMint, OrcaQuoterError> = match &account.value {
+            Some(mint_account) => mint_data_from_pubkey_and_account(mint_pubkey, mint_account, epoch)
+                .map(|mut mint_data| {
+                    apply_metaplex_metadata(&mut mint_data, metadata_account.value.as_ref());
+                    FetchedMint {
+                        mint_data,
+                        slot: account.slot,
+                        fetched_at_ms,
+                        endpoint: endpoint.clone(),
+                        data_hash: hash(&mint_account.data),
+                    }
+                }),
+            None => Err(OrcaQuoterError::AccountNotFound(*mint_pubkey)),
+        };
+        if let (Some(cache), Ok(fetched_mint)) = (cache, &fetched_mint) {
+            cache.insert(fetched_mint.clone());
+        }
+        fetched_mints.insert(*mint_pubkey, fetched_mint);
+    }
+
+    Ok(fetched_mints)
+}
+
+
+/// Serialises MintData struct from an already fetched mint account
+///     - Both SPL Token and Token-2022 mints are supported, for Token-2022 mints the
+///       TransferFeeConfig and the other extensions of MintExtensions are decoded
+///     - Symbol and name are only set from the Token-2022 TokenMetadata extension, see 
+///       apply_metaplex_metadata for SPL Token mints
+///
+/// Parameters:
+///     - mint_pubkey: The pubkey of the mint
+///     - account: The mint's account, e.g. obtained via get_multiple_accounts
+///     - epoch: The epoch the account was read at, used to pick the active transfer fee,
+///       None if unknown in which case the newer fee is used
+///
+/// Returns:
+///     - A populated MintData struct or an OrcaQuoterError
+pub fn mint_data_from_pubkey_and_account(
+    mint_pubkey: &Pubkey, account: &Account, epoch: Option<u64>
+) -> Result<MintData, OrcaQuoterError> {
+    // Verify the account is owned by the SPL Token or Token-2022 program before unpacking
+    if account.owner != spl_token::id() && account.owner != spl_token_2022::id() {
+        return Err(OrcaQuoterError::WrongOwner {
+            account: *mint_pubkey,
+            expected: vec![spl_token::id(), spl_token_2022::id()],
+            found: account.owner,
+        });
+    }
+    // Token-2022 mints share the SPL Token base layout, SPL Token mints simply carry no extensions
+    let state: StateWithExtensions<Mint> = match StateWithExtensions::<Mint>::unpack(&account.data) {
+        Ok(state) => state,
+        Err(err) => {
+            return Err(OrcaQuoterError::Deserialisation(format!(
+                "Failed to deserialize mint account with address {}: {}",
+                mint_pubkey, err
+            )));
+        }
+    };
 
     // Convert authorities to Option<String> structs
-    let authority: Option<String> = match state.mint_authority {
+    let authority: Option<String> = match state.base.mint_authority {
         COption::Some(pk) => Some(pk.to_string()),
         COption::None     => None,
     };
-    let freeze_authority: Option<String> = match state.freeze_authority {
+    let freeze_authority: Option<String> = match state.base.freeze_authority {
         COption::Some(pk) => Some(pk.to_string()),
         COption::None     => None,
     };
 
-    MintData {
-        pubkey: mint_pubkey.clone(),
-        authority: authority,
-        supply: state.supply,
-        decimals: state.decimals,
-        is_initialized: state.is_initialized,
-        freeze_authority: freeze_authority,
+    // Decode the transfer fee extension, absent on SPL Token mints
+    let transfer_fee_config: Option<TransferFeeConfigData> = state.get_extension::<TransferFeeConfig>()
+        .ok()
+        .map(|config| TransferFeeConfigData {
+            older_transfer_fee: transfer_fee_data(&config.older_transfer_fee),
+            newer_transfer_fee: transfer_fee_data(&config.newer_transfer_fee),
+        });
+
+    // Token metadata stored on the mint itself, Metaplex metadata lives in a separate account
+    let token_metadata: Option<(String, String)> = token_2022_name_and_symbol(mint_pubkey, &state);
+
+    Ok(MintData {
+        pubkey: *mint_pubkey,
+        authority,
+        supply: state.base.supply,
+        decimals: state.base.decimals,
+        is_initialized: state.base.is_initialized,
+        freeze_authority,
+        transfer_fee: transfer_fee_config.map(|config| config.fee_at(epoch)),
+        transfer_fee_config,
+        token_program: account.owner,
+        extensions: mint_extensions(&state),
+        metadata_source: token_metadata.as_ref().map(|_| TokenMetadataSource::Token2022Extension),
+        symbol: token_metadata.as_ref().map(|(_, symbol)| symbol.clone()),
+        name: token_metadata.map(|(name, _)| name),
+    })
+}
+
+
+/// Decode the Token-2022 extensions of an unpacked mint
+///
+/// Parameters:
+///     - state: a pointer to the unpacked mint with its TLV data
+///
+/// Returns:
+///     - The MintExtensions, empty if the mint has none
+fn mint_extensions(state: &StateWithExtensions<Mint>) -> MintExtensions {
+    // Extensions are optional, a missing or malformed extension is left out rather than failing the mint
+    let extension_types: Vec<String> = state.get_extension_types()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|extension_type| extension_type_name(extension_type).to_string())
+        .collect();
+    MintExtensions {
+        extension_types,
+        transfer_hook_program_id: state.get_extension::<TransferHook>()
+            .ok()
+            .and_then(|hook| Option::<Pubkey>::from(hook.program_id)),
+        permanent_delegate: state.get_extension::<PermanentDelegate>()
+            .ok()
+            .and_then(|delegate| Option::<Pubkey>::from(delegate.delegate)),
+        non_transferable: state.get_extension::<NonTransferable>().is_ok(),
+        interest_bearing: state.get_extension::<InterestBearingConfig>()
+            .ok()
+            .map(|config| InterestBearingData {
+                rate_authority: Option::<Pubkey>::from(config.rate_authority),
+                initialization_timestamp: i64::from(config.initialization_timestamp),
+                pre_update_average_rate: i16::from(config.pre_update_average_rate),
+                last_update_timestamp: i64::from(config.last_update_timestamp),
+                current_rate: i16::from(config.current_rate),
+            }),
+        default_account_state_frozen: state.get_extension::<DefaultAccountState>()
+            .is_ok_and(|default_state| default_state.state == AccountState::Frozen as u8),
+        metadata_pointer: state.get_extension::<MetadataPointer>()
+            .ok()
+            .map(|pointer| MetadataPointerData {
+                authority: Option::<Pubkey>::from(pointer.authority),
+                metadata_address: Option::<Pubkey>::from(pointer.metadata_address),
+            }),
+        mint_close_authority: state.get_extension::<MintCloseAuthority>()
+            .ok()
+            .and_then(|close_authority| Option::<Pubkey>::from(close_authority.close_authority)),
+    }
+}
+
+
+/// Stable name of a Token-2022 extension type, recorded in MintExtensions::extension_types
+///     - Spelled out rather than taken from Debug, so snapshots and logs keep their names across 
+///       spl-token-2022 upgrades, a new extension type fails to compile here until it is named
+///
+/// Parameters:
+///     - extension_type: the ExtensionType
+///
+/// Returns:
+///     - The extension type's name
+fn extension_type_name(extension_type: ExtensionType) -> &'static str {
+    match extension_type {
+        ExtensionType::Uninitialized => "Uninitialized",
+        ExtensionType::TransferFeeConfig => "TransferFeeConfig",
+        ExtensionType::TransferFeeAmount => "TransferFeeAmount",
+        ExtensionType::MintCloseAuthority => "MintCloseAuthority",
+        ExtensionType::ConfidentialTransferMint => "ConfidentialTransferMint",
+        ExtensionType::ConfidentialTransferAccount => "ConfidentialTransferAccount",
+        ExtensionType::DefaultAccountState => "DefaultAccountState",
+        ExtensionType::ImmutableOwner => "ImmutableOwner",
+        ExtensionType::MemoTransfer => "MemoTransfer",
+        ExtensionType::NonTransferable => "NonTransferable",
+        ExtensionType::InterestBearingConfig => "InterestBearingConfig",
+        ExtensionType::CpiGuard => "CpiGuard",
+        ExtensionType::PermanentDelegate => "PermanentDelegate",
+        ExtensionType::NonTransferableAccount => "NonTransferableAccount",
+        ExtensionType::TransferHook => "TransferHook",
+        ExtensionType::TransferHookAccount => "TransferHookAccount",
+        ExtensionType::ConfidentialTransferFeeConfig => "ConfidentialTransferFeeConfig",
+        ExtensionType::ConfidentialTransferFeeAmount => "ConfidentialTransferFeeAmount",
+        ExtensionType::MetadataPointer => "MetadataPointer",
+        ExtensionType::TokenMetadata => "TokenMetadata",
+    }
+}
+
+
+/// Read the epoch from a Clock sysvar account
+///
+/// Parameters:
+///     - account: The Clock sysvar account
+///
+/// Returns:
+///     - The epoch, None if the account is not a valid Clock
+pub fn epoch_from_clock_account(account: &Account) -> Option<u64> {
+    from_account::<Clock, Account>(account).map(|clock| clock.epoch)
+}
+
+
+// Convert a Token-2022 Pod TransferFee into TransferFeeData
+fn transfer_fee_data(transfer_fee: &TransferFee) -> TransferFeeData {
+    TransferFeeData {
+        epoch: u64::from(transfer_fee.epoch),
+        maximum_fee: u64::from(transfer_fee.maximum_fee),
+        transfer_fee_basis_points: u16::from(transfer_fee.transfer_fee_basis_points),
+    }
+}
+
+
+/// Convert a raw token amount into UI units
+///
+/// Parameters:
+///     - raw_amount: the amount in base units
+///     - decimals: the mint's decimals
+///
+/// Returns:
+///     - The amount in UI units, e.g. 1_500_000 with 6 decimals is 1.5
+pub fn ui_amount(raw_amount: u64, decimals: u8) -> f64 {
+    raw_amount as f64 / 10f64.powi(decimals as i32)
+}
+
+
+/// Format a raw token amount in UI units without floating point rounding
+///     - Trailing zeros are trimmed, keeping at least one fractional digit for mints with decimals
+///
+/// Parameters:
+///     - raw_amount: the amount in base units
+///     - decimals: the mint's decimals
+///
+/// Returns:
+///     - The formatted amount, e.g. "1.0" for 1_000_000 or "142.31" for 142_310_000 with 6 decimals
+pub fn format_ui_amount(raw_amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return raw_amount.to_string();
+    }
+    // decimals is an arbitrary on-chain u8 and 10^decimals overflows u128 beyond 38 decimals, so the 
+    // decimal point is inserted into the digits, left padded to keep at least one whole digit
+    let decimals: usize = decimals as usize;
+    let digits: String = format!("{:0>width$}", raw_amount, width = decimals + 1);
+    let (whole, fraction): (&str, &str) = digits.split_at(digits.len() - decimals);
+    let fraction: &str = fraction.trim_end_matches('0');
+    match fraction.is_empty() {
+        true => format!("{}.0", whole),
+        false => format!("{}.{}", whole, fraction),
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,31 @@
 use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::collections::HashMap;
+use std::sync::{
+    OnceLock, RwLock
+};
+use std::time::{
+    Duration, Instant
+};
 
-use solana_sdk::pubkey::Pubkey;
+use serde::{
+    Deserialize, Serialize
+};
+use solana_sdk::{
+    hash::Hash, pubkey::Pubkey
+};
 
+use orca_whirlpools_core::TransferFee;
+
+use error::error::OrcaQuoterError;
+
+use crate::mint::{
+    ui_amount, format_ui_amount
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MintData {
     pub pubkey: Pubkey,
     pub authority:   Option<String>,
@@ -9,6 +33,239 @@ pub struct MintData {
     pub decimals:         u8,
     pub is_initialized:   bool,
     pub freeze_authority: Option<String>,
+    // Token-2022 TransferFeeConfig extension, None for SPL Token mints and mints without the extension
+    pub transfer_fee_config: Option<TransferFeeConfigData>,
+    // The transfer fee active at the epoch the mint was read at
+    pub transfer_fee: Option<TransferFeeData>,
+    // The owning token program, SPL Token or Token-2022, the default Pubkey for unfetched placeholders
+    pub token_program: Pubkey,
+    // Token-2022 extensions decoded from the TLV area, empty for SPL Token mints
+    pub extensions: MintExtensions,
+    // Token metadata, None if the mint has no metadata or it could not be resolved
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub metadata_source: Option<TokenMetadataSource>,
+}
+
+/// Where a mint's symbol and name were resolved from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenMetadataSource {
+    /// The Token-2022 TokenMetadata extension stored on the mint itself
+    Token2022Extension,
+    /// The Metaplex Token Metadata PDA of the mint
+    Metaplex,
+    /// A local TokenMetadataOverrides file
+    Override,
+}
+
+/// Token-2022 mint extensions, decoded from the TLV area following the base mint
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintExtensions {
+    // Names of every extension present, including ones not decoded below
+    pub extension_types: Vec<String>,
+    // Program invoked on every transfer, None if absent or unset
+    pub transfer_hook_program_id: Option<Pubkey>,
+    // Delegate able to transfer or burn from any token account of the mint
+    pub permanent_delegate: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub interest_bearing: Option<InterestBearingData>,
+    // New token accounts of the mint start frozen
+    pub default_account_state_frozen: bool,
+    pub metadata_pointer: Option<MetadataPointerData>,
+    pub mint_close_authority: Option<Pubkey>,
+}
+
+/// Token-2022 InterestBearingConfig extension, rates are in basis points per year
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterestBearingData {
+    pub rate_authority: Option<Pubkey>,
+    pub initialization_timestamp: i64,
+    pub pre_update_average_rate: i16,
+    pub last_update_timestamp: i64,
+    pub current_rate: i16,
+}
+
+/// Token-2022 MetadataPointer extension, the account holding the mint's token metadata
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataPointerData {
+    pub authority: Option<Pubkey>,
+    pub metadata_address: Option<Pubkey>,
+}
+
+/// Properties of a mint that can make its pools untradeable or mis-quoted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MintRisk {
+    TransferHook,
+    PermanentDelegate,
+    NonTransferable,
+    InterestBearing,
+    DefaultAccountStateFrozen,
+    TransferFee,
+}
+
+impl MintRisk {
+    /// Whether the risk rules the mint out for trading, rather than just needing care when quoting
+    /// 
+    /// Returns: 
+    ///     - true for transfer hooks, permanent delegates, non transferable mints and frozen 
+    ///       default account states
+    pub fn is_blocking(&self) -> bool {
+        !matches!(self, MintRisk::InterestBearing | MintRisk::TransferFee)
+    }
+}
+
+impl fmt::Display for MintRisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label: &str = match self {
+            MintRisk::TransferHook => "transfer_hook",
+            MintRisk::PermanentDelegate => "permanent_delegate",
+            MintRisk::NonTransferable => "non_transferable",
+            MintRisk::InterestBearing => "interest_bearing",
+            MintRisk::DefaultAccountStateFrozen => "default_account_state_frozen",
+            MintRisk::TransferFee => "transfer_fee",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A Token-2022 transfer fee, taking effect from epoch
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFeeData {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+/// The older and newer transfer fees of a Token-2022 TransferFeeConfig extension
+///     - A fee update only takes effect from the epoch after it is set, until then the older fee applies
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFeeConfigData {
+    pub older_transfer_fee: TransferFeeData,
+    pub newer_transfer_fee: TransferFeeData,
+}
+
+impl TransferFeeConfigData {
+    /// The transfer fee in effect at an epoch
+    /// 
+    /// Parameters: 
+    ///     - epoch: the epoch, None if unknown
+    /// 
+    /// Returns: 
+    ///     - The newer fee from its epoch onwards or if the epoch is unknown, the older fee otherwise
+    pub fn fee_at(&self, epoch: Option<u64>) -> TransferFeeData {
+        match epoch {
+            Some(epoch) if epoch < self.newer_transfer_fee.epoch => self.older_transfer_fee,
+            _ => self.newer_transfer_fee,
+        }
+    }
+}
+
+impl MintData {
+    /// Placeholder MintData for a mint whose account has not been fetched
+    ///     - Only the pubkey is meaningful, is_initialized = false flags the placeholder
+    /// 
+    /// Parameters: 
+    ///     - pubkey: The pubkey of the mint
+    /// 
+    /// Returns: 
+    ///     - An unfetched MintData struct
+    pub fn unfetched(pubkey: Pubkey) -> MintData {
+        MintData {
+            pubkey,
+            authority: None,
+            supply: 0,
+            decimals: 0,
+            is_initialized: false,
+            freeze_authority: None,
+            transfer_fee_config: None,
+            transfer_fee: None,
+            token_program: Pubkey::default(),
+            extensions: MintExtensions::default(),
+            symbol: None,
+            name: None,
+            metadata_source: None,
+        }
+    }
+
+    /// Convert a raw amount of the mint into UI units, see mint::ui_amount
+    /// 
+    /// Parameters: 
+    ///     - raw_amount: the amount in base units
+    /// 
+    /// Returns: 
+    ///     - The amount in UI units
+    pub fn ui_amount(&self, raw_amount: u64) -> f64 {
+        ui_amount(raw_amount, self.decimals)
+    }
+
+    /// Format a raw amount of the mint in UI units, see mint::format_ui_amount
+    /// 
+    /// Parameters: 
+    ///     - raw_amount: the amount in base units
+    /// 
+    /// Returns: 
+    ///     - The formatted amount, e.g. "1.5"
+    pub fn format_ui_amount(&self, raw_amount: u64) -> String {
+        format_ui_amount(raw_amount, self.decimals)
+    }
+
+    /// Token-2022 transfer fee of the mint in orca_whirlpools_core form
+    /// 
+    /// Returns: 
+    ///     - The transfer fee active when the mint was read, None for mints without a transfer fee
+    pub fn core_transfer_fee(&self) -> Option<TransferFee> {
+        self.transfer_fee.map(|fee| TransferFee::new_with_max(fee.transfer_fee_basis_points, fee.maximum_fee))
+    }
+
+    /// Short human readable label of the mint for display
+    /// 
+    /// Returns: 
+    ///     - The symbol, or the first and last four characters of the pubkey if it has none
+    pub fn label(&self) -> String {
+        match &self.symbol {
+            Some(symbol) => symbol.clone(),
+            None => {
+                let pubkey: String = self.pubkey.to_string();
+                format!("{}..{}", &pubkey[..4], &pubkey[pubkey.len() - 4..])
+            },
+        }
+    }
+
+    /// The MintRisks of the mint's extensions
+    ///     - A zero transfer fee or an unset transfer hook program are not risks
+    /// 
+    /// Returns: 
+    ///     - The MintRisks, empty for plain SPL Token mints
+    pub fn risks(&self) -> Vec<MintRisk> {
+        let mut risks: Vec<MintRisk> = Vec::new();
+        if self.extensions.transfer_hook_program_id.is_some() {
+            risks.push(MintRisk::TransferHook);
+        }
+        if self.extensions.permanent_delegate.is_some() {
+            risks.push(MintRisk::PermanentDelegate);
+        }
+        if self.extensions.non_transferable {
+            risks.push(MintRisk::NonTransferable);
+        }
+        if self.extensions.interest_bearing.is_some() {
+            risks.push(MintRisk::InterestBearing);
+        }
+        if self.extensions.default_account_state_frozen {
+            risks.push(MintRisk::DefaultAccountStateFrozen);
+        }
+        if self.transfer_fee.is_some_and(|fee| fee.transfer_fee_basis_points > 0) {
+            risks.push(MintRisk::TransferFee);
+        }
+        risks
+    }
+
+    /// Whether any of the mint's risks rules it out for trading, see MintRisk::is_blocking
+    /// 
+    /// Returns: 
+    ///     - true if the mint has a blocking MintRisk
+    pub fn is_untradeable(&self) -> bool {
+        self.risks().iter().any(|risk| risk.is_blocking())
+    }
 }
 
 /// Pretty printing implementation for MintData
@@ -16,6 +273,13 @@ pub struct MintData {
 impl fmt::Display for MintData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "  pubkey:           {}", self.pubkey)?;
+        match (&self.symbol, &self.metadata_source) {
+            (Some(symbol), Some(source)) => writeln!(
+                f, "  symbol:           {} ({}, {:?})", symbol, self.name.as_deref().unwrap_or("unnamed"), source
+            )?,
+            (Some(symbol), None) => writeln!(f, "  symbol:           {} ({})", symbol, self.name.as_deref().unwrap_or("unnamed"))?,
+            (None, _) => writeln!(f, "  symbol:           None")?,
+        }
         writeln!(
             f,
             "  authority:        {}",
@@ -24,10 +288,226 @@ impl fmt::Display for MintData {
         writeln!(f, "  supply:           {}", self.supply)?;
         writeln!(f, "  decimals:         {}", self.decimals)?;
         writeln!(f, "  is_initialized:   {}", self.is_initialized)?;
+        match &self.transfer_fee {
+            Some(fee) => writeln!(
+                f, "  transfer_fee:     {} bps, max {} (from epoch {})", 
+                fee.transfer_fee_basis_points, fee.maximum_fee, fee.epoch
+            )?,
+            None => writeln!(f, "  transfer_fee:     None")?,
+        }
+        writeln!(f, "  token_program:    {}", self.token_program)?;
+        if !self.extensions.extension_types.is_empty() {
+            writeln!(f, "  extensions:       {}", self.extensions.extension_types.join(", "))?;
+        }
+        let risks: Vec<MintRisk> = self.risks();
+        match risks.is_empty() {
+            true => writeln!(f, "  risks:            None")?,
+            false => writeln!(
+                f, "  risks:            {}{}", 
+                risks.iter().map(|risk| risk.to_string()).collect::<Vec<String>>().join(", "),
+                if self.is_untradeable() { " (UNTRADEABLE)" } else { "" }
+            )?,
+        }
         write!(
             f,
             "  freeze_authority: {}",
             self.freeze_authority.as_deref().unwrap_or("None")
         )
     }
-}
\ No newline at end of file
+}
+
+/// MintData with the fetch of the mint account it was decoded from
+///     - Served from a MintCache, the fetch is the one that filled the cache entry
+#[derive(Clone, Debug)]
+pub struct FetchedMint {
+    pub mint_data: MintData,
+    pub slot: u64,
+    // Milliseconds since UNIX_EPOCH
+    pub fetched_at_ms: u64,
+    pub endpoint: String,
+    // SHA-256 of the mint account data
+    pub data_hash: Hash,
+}
+
+/// Default maximum age of MintCache::global entries, decimals never change but supply,
+/// authorities and the active transfer fee occasionally do
+pub const DEFAULT_MINT_CACHE_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// Thread safe cache of fetched MintData, keyed by mint pubkey
+///     - Used by mint_data_from_pubkeys_async and its wrappers to skip refetching known mints
+#[derive(Debug, Default)]
+pub struct MintCache {
+    // Entries older than max_age are refetched, None keeps entries until invalidated
+    max_age: Option<Duration>,
+    entries: RwLock<HashMap<Pubkey, CachedMintData>>,
+}
+
+#[derive(Clone, Debug)]
+struct CachedMintData {
+    fetched_mint: FetchedMint,
+    cached_at: Instant,
+}
+
+impl MintCache {
+    /// Create an empty MintCache
+    /// 
+    /// Parameters: 
+    ///     - max_age: the maximum age of an entry before it is refetched, None to keep entries 
+    ///       until invalidated
+    /// 
+    /// Returns: 
+    ///     - The empty MintCache
+    pub fn new(max_age: Option<Duration>) -> Self {
+        Self { max_age, entries: RwLock::new(HashMap::new()) }
+    }
+
+    /// The process wide MintCache, created on first use with DEFAULT_MINT_CACHE_MAX_AGE
+    /// 
+    /// Returns: 
+    ///     - A static pointer to the process wide MintCache
+    pub fn global() -> &'static MintCache {
+        static GLOBAL_MINT_CACHE: OnceLock<MintCache> = OnceLock::new();
+        GLOBAL_MINT_CACHE.get_or_init(|| MintCache::new(Some(DEFAULT_MINT_CACHE_MAX_AGE)))
+    }
+
+    /// The maximum age of an entry before it is refetched
+    /// 
+    /// Returns: 
+    ///     - The max_age the cache was created with, None if entries are kept until invalidated
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Look up a mint, expired entries are treated as missing
+    /// 
+    /// Parameters: 
+    ///     - mint_pubkey: a pointer to the mint's pubkey
+    /// 
+    /// Returns: 
+    ///     - A clone of the cached MintData, None if absent or expired
+    pub fn get(&self, mint_pubkey: &Pubkey) -> Option<MintData> {
+        self.get_fetched(mint_pubkey).map(|fetched_mint| fetched_mint.mint_data)
+    }
+
+    /// Look up a mint with the fetch that filled its entry, expired entries are treated as missing
+    /// 
+    /// Parameters: 
+    ///     - mint_pubkey: a pointer to the mint's pubkey
+    /// 
+    /// Returns: 
+    ///     - A clone of the cached FetchedMint, None if absent or expired
+    pub fn get_fetched(&self, mint_pubkey: &Pubkey) -> Option<FetchedMint> {
+        // A poisoned lock only means a writer panicked, the map itself is still consistent
+        let entries = self.entries.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.get(mint_pubkey)
+            .filter(|entry| self.max_age.is_none_or(|max_age| entry.cached_at.elapsed() <= max_age))
+            .map(|entry| entry.fetched_mint.clone())
+    }
+
+    /// Insert or replace a mint's entry
+    /// 
+    /// Parameters: 
+    ///     - fetched_mint: the freshly fetched mint
+    pub fn insert(&self, fetched_mint: FetchedMint) {
+        let mut entries = self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.insert(fetched_mint.mint_data.pubkey, CachedMintData { fetched_mint, cached_at: Instant::now() });
+    }
+
+    /// Remove a mint's entry, so its next lookup refetches it
+    /// 
+    /// Parameters: 
+    ///     - mint_pubkey: a pointer to the mint's pubkey
+    pub fn invalidate(&self, mint_pubkey: &Pubkey) {
+        let mut entries = self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.remove(mint_pubkey);
+    }
+
+    /// Remove every entry
+    pub fn clear(&self) {
+        let mut entries = self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.clear();
+    }
+
+    /// Number of entries, including expired ones not yet refetched
+    /// 
+    /// Returns: 
+    ///     - The number of entries
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|poisoned| poisoned.into_inner()).len()
+    }
+
+    /// Whether the cache holds no entries
+    /// 
+    /// Returns: 
+    ///     - true if empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+
+/// Symbol and name of a single mint in a TokenMetadataOverrides file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenMetadataOverride {
+    pub symbol: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Locally configured symbols and names, taking precedence over on-chain metadata
+///     - Read from a JSON object keyed by base58 mint pubkey, 
+///       e.g. {"So11111111111111111111111111111111111111112": {"symbol": "SOL", "name": "Wrapped SOL"}}
+#[derive(Clone, Debug, Default)]
+pub struct TokenMetadataOverrides {
+    pub overrides: HashMap<Pubkey, TokenMetadataOverride>,
+}
+
+impl TokenMetadataOverrides {
+    /// Parse overrides from a JSON string
+    /// 
+    /// Parameters: 
+    ///     - json: the JSON object keyed by base58 mint pubkey
+    /// 
+    /// Returns: 
+    ///     - The TokenMetadataOverrides or OrcaQuoterError::Deserialisation on malformed JSON or pubkeys
+    pub fn from_json_str(json: &str) -> Result<TokenMetadataOverrides, OrcaQuoterError> {
+        // Pubkeys serialise as byte arrays, so the keys are parsed from base58 strings by hand
+        let raw: HashMap<String, TokenMetadataOverride> = serde_json::from_str(json).map_err(|err| 
+            OrcaQuoterError::Deserialisation(format!("Failed to parse token metadata overrides: {}", err))
+        )?;
+        let mut overrides: HashMap<Pubkey, TokenMetadataOverride> = HashMap::with_capacity(raw.len());
+        for (mint, token_metadata_override) in raw {
+            let mint_pubkey: Pubkey = Pubkey::from_str(&mint).map_err(|err| OrcaQuoterError::Deserialisation(
+                format!("Invalid mint pubkey {} in token metadata overrides: {}", mint, err)
+            ))?;
+            overrides.insert(mint_pubkey, token_metadata_override);
+        }
+        Ok(TokenMetadataOverrides { overrides })
+    }
+
+    /// Read overrides from a JSON file, see from_json_str
+    /// 
+    /// Parameters: 
+    ///     - path: a pointer to the path of the overrides file
+    /// 
+    /// Returns: 
+    ///     - The TokenMetadataOverrides or an OrcaQuoterError if the file could not be read or parsed
+    pub fn from_file(path: &Path) -> Result<TokenMetadataOverrides, OrcaQuoterError> {
+        let json: String = fs::read_to_string(path).map_err(|err| OrcaQuoterError::Io(
+            format!("Failed to read token metadata overrides {}: {}", path.display(), err)
+        ))?;
+        TokenMetadataOverrides::from_json_str(&json)
+    }
+
+    /// Overwrite a mint's symbol and name if the mint has an override
+    /// 
+    /// Parameters: 
+    ///     - mint_data: a mutable pointer to the mint's MintData
+    pub fn apply(&self, mint_data: &mut MintData) {
+        if let Some(token_metadata_override) = self.overrides.get(&mint_data.pubkey) {
+            mint_data.symbol = Some(token_metadata_override.symbol.clone());
+            mint_data.name = token_metadata_override.name.clone();
+            mint_data.metadata_source = Some(TokenMetadataSource::Override);
+        }
+    }
+}
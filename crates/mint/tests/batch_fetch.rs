@@ -0,0 +1,213 @@
+// Mints are fetched in one deduplicated batch, verified against the token programs and served from a
+// MintCache until their entries expire
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use futures::executor::block_on;
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig, system_program, sysvar
+};
+
+use error::error::OrcaQuoterError;
+use mint::{
+    metadata::metaplex_metadata_address,
+    mint::{
+        mint_data_from_pubkey_and_account, mint_data_from_pubkeys, mint_data_from_pubkeys_with_cache, 
+        mint_data_from_pubkeys_async
+    },
+    structs::{FetchedMint, MintCache, MintData, DEFAULT_MINT_CACHE_MAX_AGE}
+};
+use provider::{
+    provider::AccountProvider, nonblocking::BlockingAccountProvider, structs::{AtSlot, InMemoryAccountProvider}
+};
+
+use common::{
+    DECIMALS, spl_token_mint_account, token_2022_mint_account
+};
+
+/// In-memory provider recording the pubkeys of every get_multiple_accounts request, failing them
+/// all once fail is set
+struct RecordingProvider {
+    inner: InMemoryAccountProvider,
+    requests: Mutex<Vec<Vec<Pubkey>>>,
+    fail: bool,
+}
+
+impl RecordingProvider {
+    fn new(accounts: HashMap<Pubkey, Account>) -> Self {
+        RecordingProvider { inner: InMemoryAccountProvider::from(accounts), requests: Mutex::new(Vec::new()), fail: false }
+    }
+
+    fn requests(&self) -> Vec<Vec<Pubkey>> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl AccountProvider for RecordingProvider {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        self.inner.get_account(pubkey)
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        self.requests.lock().unwrap().push(pubkeys.to_vec());
+        match self.fail {
+            true => Err(OrcaQuoterError::Rpc("connection refused".to_string())),
+            false => self.inner.get_multiple_accounts(pubkeys),
+        }
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        self.inner.get_slot()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.inner.commitment()
+    }
+
+    fn endpoint(&self) -> String {
+        "recording".to_string()
+    }
+}
+
+/// Provider holding an SPL Token mint and a Token-2022 mint
+fn provider_with_two_mints() -> (RecordingProvider, Pubkey, Pubkey) {
+    let (spl_mint, token_2022_mint): (Pubkey, Pubkey) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts: HashMap<Pubkey, Account> = HashMap::from([
+        (spl_mint, spl_token_mint_account(DECIMALS)),
+        (token_2022_mint, token_2022_mint_account(9, &[], |_| {})),
+    ]);
+    (RecordingProvider::new(accounts), spl_mint, token_2022_mint)
+}
+
+/// Fetch with a cache through the async batch
+fn fetch_with_cache(provider: &RecordingProvider, mint_pubkeys: &[Pubkey], cache: &MintCache) -> HashMap<Pubkey, FetchedMint> {
+    block_on(mint_data_from_pubkeys_async(&BlockingAccountProvider(provider), mint_pubkeys, Some(cache), None))
+        .unwrap()
+        .into_iter()
+        .map(|(mint_pubkey, fetched_mint)| (mint_pubkey, fetched_mint.unwrap()))
+        .collect()
+}
+
+#[test]
+fn duplicates_are_fetched_once_and_returned_in_order() {
+    let (provider, spl_mint, token_2022_mint): (RecordingProvider, Pubkey, Pubkey) = provider_with_two_mints();
+    let mint_data: Vec<Result<MintData, OrcaQuoterError>> = mint_data_from_pubkeys(
+        &provider, &[spl_mint, token_2022_mint, spl_mint, spl_mint]
+    );
+
+    let pubkeys: Vec<Pubkey> = mint_data.iter().map(|mint_data| mint_data.as_ref().unwrap().pubkey).collect();
+    assert_eq!(pubkeys, vec![spl_mint, token_2022_mint, spl_mint, spl_mint]);
+    assert_eq!(mint_data[1].as_ref().unwrap().decimals, 9);
+
+    // One request laid out as [mints.., Metaplex metadata PDAs.., clock]
+    assert_eq!(provider.requests(), vec![vec![
+        spl_mint, token_2022_mint, metaplex_metadata_address(&spl_mint), metaplex_metadata_address(&token_2022_mint),
+        sysvar::clock::id(),
+    ]]);
+}
+
+#[test]
+fn mints_failing_verification_fail_alone() {
+    let (mut provider, spl_mint, _): (RecordingProvider, Pubkey, Pubkey) = provider_with_two_mints();
+    let (system_owned, missing): (Pubkey, Pubkey) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut foreign_account: Account = spl_token_mint_account(DECIMALS);
+    foreign_account.owner = system_program::id();
+    provider.inner.accounts.insert(system_owned, foreign_account);
+
+    let mint_data: Vec<Result<MintData, OrcaQuoterError>> = mint_data_from_pubkeys(&provider, &[system_owned, spl_mint, missing]);
+    assert!(matches!(mint_data[0], Err(OrcaQuoterError::WrongOwner { found, .. }) if found == system_program::id()));
+    assert_eq!(mint_data[1].as_ref().unwrap().pubkey, spl_mint);
+    assert!(matches!(mint_data[2], Err(OrcaQuoterError::AccountNotFound(pubkey)) if pubkey == missing));
+}
+
+#[test]
+fn a_foreign_owned_mint_reports_both_token_programs() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mut account: Account = token_2022_mint_account(DECIMALS, &[], |_| {});
+    account.owner = system_program::id();
+
+    let err: OrcaQuoterError = mint_data_from_pubkey_and_account(&mint_pubkey, &account, None).unwrap_err();
+    assert_eq!(err, OrcaQuoterError::WrongOwner {
+        account: mint_pubkey,
+        expected: vec![spl_token::id(), spl_token_2022::id()],
+        found: system_program::id(),
+    });
+    assert_eq!(err.to_string(), format!(
+        "Account {} is owned by {}, expected one of {}, {}",
+        mint_pubkey, system_program::id(), spl_token::id(), spl_token_2022::id()
+    ));
+
+    // Both token programs are accepted
+    assert!(mint_data_from_pubkey_and_account(&mint_pubkey, &spl_token_mint_account(DECIMALS), None).is_ok());
+    assert!(mint_data_from_pubkey_and_account(&mint_pubkey, &token_2022_mint_account(DECIMALS, &[], |_| {}), None).is_ok());
+}
+
+#[test]
+fn cached_mints_are_not_refetched() {
+    let (provider, spl_mint, token_2022_mint): (RecordingProvider, Pubkey, Pubkey) = provider_with_two_mints();
+    let cache: MintCache = MintCache::new(None);
+
+    let first: HashMap<Pubkey, FetchedMint> = fetch_with_cache(&provider, &[spl_mint], &cache);
+    assert_eq!(cache.len(), 1);
+    let second: HashMap<Pubkey, FetchedMint> = fetch_with_cache(&provider, &[spl_mint, token_2022_mint], &cache);
+
+    // The second batch only fetched the uncached mint, the cached one keeps its original fetch
+    let requests: Vec<Vec<Pubkey>> = provider.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1][0], token_2022_mint);
+    assert!(!requests[1].contains(&spl_mint));
+    assert_eq!(second[&spl_mint].fetched_at_ms, first[&spl_mint].fetched_at_ms);
+    assert_eq!(second[&spl_mint].data_hash, first[&spl_mint].data_hash);
+    assert_eq!(second[&spl_mint].endpoint, "recording");
+
+    // A fully cached batch sends no request at all
+    fetch_with_cache(&provider, &[token_2022_mint, spl_mint], &cache);
+    assert_eq!(provider.requests().len(), 2);
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn expired_entries_are_refetched() {
+    let (provider, spl_mint, _): (RecordingProvider, Pubkey, Pubkey) = provider_with_two_mints();
+    let cache: MintCache = MintCache::new(Some(Duration::from_millis(20)));
+
+    fetch_with_cache(&provider, &[spl_mint], &cache);
+    assert!(cache.get(&spl_mint).is_some());
+    thread::sleep(Duration::from_millis(40));
+    assert!(cache.get(&spl_mint).is_none());
+
+    fetch_with_cache(&provider, &[spl_mint], &cache);
+    assert_eq!(provider.requests().len(), 2);
+    assert!(cache.get(&spl_mint).is_some());
+}
+
+#[test]
+fn the_global_cache_keeps_entries_for_five_minutes() {
+    assert_eq!(DEFAULT_MINT_CACHE_MAX_AGE, Duration::from_secs(300));
+    assert_eq!(MintCache::global().max_age(), Some(DEFAULT_MINT_CACHE_MAX_AGE));
+    assert_eq!(MintCache::default().max_age(), None);
+}
+
+#[test]
+fn a_failed_request_fails_only_the_uncached_mints() {
+    let (mut provider, spl_mint, token_2022_mint): (RecordingProvider, Pubkey, Pubkey) = provider_with_two_mints();
+    let cache: MintCache = MintCache::new(None);
+    fetch_with_cache(&provider, &[spl_mint], &cache);
+
+    provider.fail = true;
+    let batch: Result<HashMap<Pubkey, Result<FetchedMint, OrcaQuoterError>>, OrcaQuoterError> = block_on(
+        mint_data_from_pubkeys_async(&BlockingAccountProvider(&provider), &[spl_mint, token_2022_mint], Some(&cache), None)
+    );
+    assert!(matches!(batch, Err(OrcaQuoterError::Rpc(_))));
+
+    let mint_data: Vec<Result<MintData, OrcaQuoterError>> = mint_data_from_pubkeys_with_cache(
+        &provider, &[spl_mint, token_2022_mint], Some(&cache)
+    );
+    assert_eq!(mint_data[0].as_ref().unwrap().pubkey, spl_mint);
+    assert!(matches!(mint_data[1], Err(OrcaQuoterError::Rpc(_))));
+}
@@ -0,0 +1,65 @@
+// Shared builders for the mint integration tests, mint accounts are packed byte for byte as the SPL Token
+// and Token-2022 programs store them on chain
+#![allow(dead_code)]
+
+use solana_sdk::{
+    account::Account, program_option::COption, program_pack::Pack
+};
+use spl_token_2022::{
+    extension::{ExtensionType, StateWithExtensionsMut},
+    state::Mint
+};
+
+pub const DECIMALS: u8 = 6;
+pub const SUPPLY: u64 = 1_000_000_000;
+
+
+/// Initialised mint base state without authorities
+fn mint(decimals: u8) -> Mint {
+    Mint {
+        mint_authority: COption::None,
+        supply: SUPPLY,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+}
+
+
+/// Initialised SPL Token mint account, Token-2022 packs its base mint in the same 82 byte layout
+pub fn spl_token_mint_account(decimals: u8) -> Account {
+    let mut data: Vec<u8> = vec![0; Mint::LEN];
+    Mint::pack(mint(decimals), &mut data).unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+
+/// Initialised Token-2022 mint account holding the given fixed length extensions
+/// 
+/// Parameters:
+///     - decimals: the mint's decimals
+///     - extension_types: every extension init_extensions initialises, sizes the TLV area
+///     - init_extensions: initialises the extensions, e.g. via StateWithExtensionsMut::init_extension
+pub fn token_2022_mint_account(
+    decimals: u8, extension_types: &[ExtensionType], init_extensions: impl FnOnce(&mut StateWithExtensionsMut<Mint>)
+) -> Account {
+    let mut data: Vec<u8> = vec![0; ExtensionType::try_calculate_account_len::<Mint>(extension_types).unwrap()];
+    let mut state: StateWithExtensionsMut<Mint> = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    init_extensions(&mut state);
+    state.base = mint(decimals);
+    state.pack_base();
+    state.init_account_type().unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token_2022::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,143 @@
+// Token-2022 extensions are decoded from the TLV area of hand built mints, named by their stable
+// extension type names and summarised as MintRisks
+
+mod common;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+use spl_token_2022::{
+    extension::{
+        ExtensionType, transfer_hook::TransferHook, permanent_delegate::PermanentDelegate,
+        non_transferable::NonTransferable, interest_bearing_mint::InterestBearingConfig,
+        default_account_state::DefaultAccountState, metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority
+    },
+    state::AccountState
+};
+
+use mint::{
+    mint::mint_data_from_pubkey_and_account,
+    structs::{InterestBearingData, MetadataPointerData, MintData, MintExtensions, MintRisk}
+};
+
+use common::{
+    DECIMALS, spl_token_mint_account, token_2022_mint_account
+};
+
+/// Pubkeys set on the extensions, so each decoded field can be told apart
+struct ExtensionKeys {
+    hook_program: Pubkey,
+    delegate: Pubkey,
+    rate_authority: Pubkey,
+    metadata_address: Pubkey,
+    close_authority: Pubkey,
+}
+
+/// Token-2022 mint holding every fixed length extension MintExtensions decodes
+fn mint_with_every_extension(keys: &ExtensionKeys) -> MintData {
+    let extension_types: [ExtensionType; 7] = [
+        ExtensionType::TransferHook, ExtensionType::PermanentDelegate, ExtensionType::NonTransferable,
+        ExtensionType::InterestBearingConfig, ExtensionType::DefaultAccountState, ExtensionType::MetadataPointer,
+        ExtensionType::MintCloseAuthority,
+    ];
+    let account: Account = token_2022_mint_account(DECIMALS, &extension_types, |state| {
+        let hook: &mut TransferHook = state.init_extension::<TransferHook>(true).unwrap();
+        hook.program_id = Some(keys.hook_program).try_into().unwrap();
+        let delegate: &mut PermanentDelegate = state.init_extension::<PermanentDelegate>(true).unwrap();
+        delegate.delegate = Some(keys.delegate).try_into().unwrap();
+        state.init_extension::<NonTransferable>(true).unwrap();
+        let interest: &mut InterestBearingConfig = state.init_extension::<InterestBearingConfig>(true).unwrap();
+        interest.rate_authority = Some(keys.rate_authority).try_into().unwrap();
+        interest.initialization_timestamp = 1_700_000_000.into();
+        interest.pre_update_average_rate = 150.into();
+        interest.last_update_timestamp = 1_700_086_400.into();
+        interest.current_rate = (-25).into();
+        let default_state: &mut DefaultAccountState = state.init_extension::<DefaultAccountState>(true).unwrap();
+        default_state.state = AccountState::Frozen as u8;
+        let pointer: &mut MetadataPointer = state.init_extension::<MetadataPointer>(true).unwrap();
+        pointer.metadata_address = Some(keys.metadata_address).try_into().unwrap();
+        let close: &mut MintCloseAuthority = state.init_extension::<MintCloseAuthority>(true).unwrap();
+        close.close_authority = Some(keys.close_authority).try_into().unwrap();
+    });
+    mint_data_from_pubkey_and_account(&Pubkey::new_unique(), &account, None).unwrap()
+}
+
+#[test]
+fn every_extension_is_decoded() {
+    let keys: ExtensionKeys = ExtensionKeys {
+        hook_program: Pubkey::new_unique(),
+        delegate: Pubkey::new_unique(),
+        rate_authority: Pubkey::new_unique(),
+        metadata_address: Pubkey::new_unique(),
+        close_authority: Pubkey::new_unique(),
+    };
+    let mint_data: MintData = mint_with_every_extension(&keys);
+
+    assert_eq!(mint_data.extensions, MintExtensions {
+        extension_types: [
+            "TransferHook", "PermanentDelegate", "NonTransferable", "InterestBearingConfig", "DefaultAccountState",
+            "MetadataPointer", "MintCloseAuthority",
+        ].map(String::from).to_vec(),
+        transfer_hook_program_id: Some(keys.hook_program),
+        permanent_delegate: Some(keys.delegate),
+        non_transferable: true,
+        interest_bearing: Some(InterestBearingData {
+            rate_authority: Some(keys.rate_authority),
+            initialization_timestamp: 1_700_000_000,
+            pre_update_average_rate: 150,
+            last_update_timestamp: 1_700_086_400,
+            current_rate: -25,
+        }),
+        default_account_state_frozen: true,
+        metadata_pointer: Some(MetadataPointerData { authority: None, metadata_address: Some(keys.metadata_address) }),
+        mint_close_authority: Some(keys.close_authority),
+    });
+}
+
+#[test]
+fn extensions_are_summarised_as_risks() {
+    let mint_data: MintData = mint_with_every_extension(&ExtensionKeys {
+        hook_program: Pubkey::new_unique(),
+        delegate: Pubkey::new_unique(),
+        rate_authority: Pubkey::new_unique(),
+        metadata_address: Pubkey::new_unique(),
+        close_authority: Pubkey::new_unique(),
+    });
+    assert_eq!(mint_data.risks(), vec![
+        MintRisk::TransferHook, MintRisk::PermanentDelegate, MintRisk::NonTransferable, MintRisk::InterestBearing,
+        MintRisk::DefaultAccountStateFrozen,
+    ]);
+    assert!(mint_data.is_untradeable());
+
+    let display: String = mint_data.to_string();
+    assert!(display.contains("extensions:       TransferHook, PermanentDelegate, NonTransferable"), "{}", display);
+    assert!(display.contains("default_account_state_frozen (UNTRADEABLE)"), "{}", display);
+}
+
+#[test]
+fn unset_extensions_are_not_risks() {
+    // A transfer hook without a program and an initialised default account state are harmless
+    let account: Account = token_2022_mint_account(
+        DECIMALS, &[ExtensionType::TransferHook, ExtensionType::DefaultAccountState], |state| {
+            state.init_extension::<TransferHook>(true).unwrap();
+            let default_state: &mut DefaultAccountState = state.init_extension::<DefaultAccountState>(true).unwrap();
+            default_state.state = AccountState::Initialized as u8;
+        }
+    );
+    let mint_data: MintData = mint_data_from_pubkey_and_account(&Pubkey::new_unique(), &account, None).unwrap();
+    assert_eq!(mint_data.extensions.extension_types, vec!["TransferHook", "DefaultAccountState"]);
+    assert_eq!(mint_data.extensions.transfer_hook_program_id, None);
+    assert!(!mint_data.extensions.default_account_state_frozen);
+    assert!(mint_data.risks().is_empty());
+    assert!(mint_data.to_string().contains("risks:            None"));
+}
+
+#[test]
+fn spl_token_mints_have_no_extensions() {
+    let mint_data: MintData = mint_data_from_pubkey_and_account(
+        &Pubkey::new_unique(), &spl_token_mint_account(DECIMALS), None
+    ).unwrap();
+    assert_eq!(mint_data.extensions, MintExtensions::default());
+    assert!(!mint_data.to_string().contains("extensions:"));
+}
@@ -0,0 +1,247 @@
+// Symbols and names are decoded from hand built Metaplex metadata accounts and Token-2022 TokenMetadata
+// extensions, metadata of another mint or a malformed buffer is ignored and local overrides win
+
+mod common;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+use spl_token_2022::{
+    extension::{ExtensionType, StateWithExtensionsMut, metadata_pointer::MetadataPointer},
+    state::Mint
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use error::error::OrcaQuoterError;
+use mint::{
+    metadata::{METAPLEX_TOKEN_METADATA_PROGRAM_ID, apply_metaplex_metadata},
+    mint::mint_data_from_pubkey_and_account,
+    structs::{MintData, TokenMetadataOverride, TokenMetadataOverrides, TokenMetadataSource}
+};
+
+use common::{
+    DECIMALS, spl_token_mint_account, token_2022_mint_account
+};
+
+// Metaplex pads names to 32, symbols to 10 and uris to 200 bytes
+const METAPLEX_NAME_LEN: usize = 32;
+const METAPLEX_SYMBOL_LEN: usize = 10;
+const METAPLEX_URI_LEN: usize = 200;
+// Offset of the name's length prefix: key (1), update authority (32), mint (32)
+const METAPLEX_NAME_OFFSET: usize = 1 + 32 + 32;
+
+
+/// Borsh string NUL padded to width bytes, as Metaplex stores names, symbols and uris
+fn padded_borsh_string(value: &str, width: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = (width as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(value.as_bytes());
+    bytes.resize(4 + width, 0);
+    bytes
+}
+
+/// Metaplex metadata account of mint_pubkey, truncated after the uri
+fn metaplex_metadata_account(mint_pubkey: &Pubkey, name: &str, symbol: &str) -> Account {
+    // Key::MetadataV1, then the update authority and the mint
+    let mut data: Vec<u8> = vec![4];
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(mint_pubkey.as_ref());
+    data.extend(padded_borsh_string(name, METAPLEX_NAME_LEN));
+    data.extend(padded_borsh_string(symbol, METAPLEX_SYMBOL_LEN));
+    data.extend(padded_borsh_string("https://example.com/token.json", METAPLEX_URI_LEN));
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: METAPLEX_TOKEN_METADATA_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// SPL Token mint without metadata
+fn spl_token_mint(mint_pubkey: &Pubkey) -> MintData {
+    mint_data_from_pubkey_and_account(mint_pubkey, &spl_token_mint_account(DECIMALS), None).unwrap()
+}
+
+/// SPL Token mint after applying a Metaplex metadata account
+fn spl_token_mint_with_metaplex(mint_pubkey: &Pubkey, metadata_account: &Account) -> MintData {
+    let mut mint_data: MintData = spl_token_mint(mint_pubkey);
+    apply_metaplex_metadata(&mut mint_data, Some(metadata_account));
+    mint_data
+}
+
+/// Token-2022 mint whose MetadataPointer points at itself and whose TokenMetadata extension names
+/// metadata_mint, the mint the metadata claims to describe
+fn token_2022_mint_with_metadata(metadata_mint: &Pubkey, name: &str, symbol: &str) -> Account {
+    let token_metadata: TokenMetadata = TokenMetadata {
+        mint: *metadata_mint,
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: "https://example.com/token.json".to_string(),
+        ..TokenMetadata::default()
+    };
+    let mut account: Account = token_2022_mint_account(DECIMALS, &[ExtensionType::MetadataPointer], |state| {
+        let pointer: &mut MetadataPointer = state.init_extension::<MetadataPointer>(true).unwrap();
+        pointer.metadata_address = Some(*metadata_mint).try_into().unwrap();
+    });
+    // The variable length TokenMetadata entry is appended after the fixed length extensions
+    let base_len: usize = account.data.len();
+    account.data.resize(base_len + token_metadata.tlv_size_of().unwrap(), 0);
+    let mut state: StateWithExtensionsMut<Mint> = StateWithExtensionsMut::<Mint>::unpack(&mut account.data).unwrap();
+    state.init_variable_len_extension(&token_metadata, false).unwrap();
+    account
+}
+
+#[test]
+fn metaplex_names_and_symbols_are_trimmed_of_their_padding() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mint_data: MintData = spl_token_mint_with_metaplex(
+        &mint_pubkey, &metaplex_metadata_account(&mint_pubkey, "Wrapped SOL", "SOL")
+    );
+
+    assert_eq!(mint_data.symbol.as_deref(), Some("SOL"));
+    assert_eq!(mint_data.name.as_deref(), Some("Wrapped SOL"));
+    assert_eq!(mint_data.metadata_source, Some(TokenMetadataSource::Metaplex));
+    assert_eq!(mint_data.label(), "SOL");
+}
+
+#[test]
+fn metaplex_metadata_of_another_mint_is_ignored() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mint_data: MintData = spl_token_mint_with_metaplex(
+        &mint_pubkey, &metaplex_metadata_account(&Pubkey::new_unique(), "Wrapped SOL", "SOL")
+    );
+
+    assert_eq!(mint_data.metadata_source, None);
+    assert_eq!(mint_data.symbol, None);
+    assert_eq!(mint_data.name, None);
+}
+
+#[test]
+fn metaplex_metadata_not_owned_by_the_metadata_program_is_ignored() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mut metadata_account: Account = metaplex_metadata_account(&mint_pubkey, "Wrapped SOL", "SOL");
+    metadata_account.owner = Pubkey::new_unique();
+
+    assert_eq!(spl_token_mint_with_metaplex(&mint_pubkey, &metadata_account).metadata_source, None);
+}
+
+#[test]
+fn truncated_metaplex_metadata_is_ignored() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let metadata_account: Account = metaplex_metadata_account(&mint_pubkey, "Wrapped SOL", "SOL");
+    let symbol_offset: usize = METAPLEX_NAME_OFFSET + 4 + METAPLEX_NAME_LEN;
+
+    // Cut inside the mint, inside the name's length prefix, inside the name and inside the symbol
+    for len in [METAPLEX_NAME_OFFSET - 1, METAPLEX_NAME_OFFSET + 2, METAPLEX_NAME_OFFSET + 10, symbol_offset + 6] {
+        let mut truncated: Account = metadata_account.clone();
+        truncated.data.truncate(len);
+        assert_eq!(spl_token_mint_with_metaplex(&mint_pubkey, &truncated).symbol, None, "truncated to {len}");
+    }
+
+    // The uri is not read, cutting it keeps the name and symbol
+    let mut truncated: Account = metadata_account.clone();
+    truncated.data.truncate(symbol_offset + 4 + METAPLEX_SYMBOL_LEN);
+    assert_eq!(spl_token_mint_with_metaplex(&mint_pubkey, &truncated).symbol.as_deref(), Some("SOL"));
+}
+
+#[test]
+fn a_length_prefix_past_the_end_of_the_data_is_ignored() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mut metadata_account: Account = metaplex_metadata_account(&mint_pubkey, "Wrapped SOL", "SOL");
+    let data_len: usize = metadata_account.data.len();
+
+    for name_len in [data_len as u32, u32::MAX] {
+        metadata_account.data[METAPLEX_NAME_OFFSET..METAPLEX_NAME_OFFSET + 4].copy_from_slice(&name_len.to_le_bytes());
+        assert_eq!(spl_token_mint_with_metaplex(&mint_pubkey, &metadata_account).symbol, None, "name length {name_len}");
+    }
+}
+
+#[test]
+fn a_blank_metaplex_symbol_is_ignored() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mint_data: MintData = spl_token_mint_with_metaplex(&mint_pubkey, &metaplex_metadata_account(&mint_pubkey, "Unnamed", " "));
+
+    assert_eq!(mint_data.metadata_source, None);
+    assert_eq!(mint_data.symbol, None);
+}
+
+#[test]
+fn token_2022_metadata_is_read_from_the_mint() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let account: Account = token_2022_mint_with_metadata(&mint_pubkey, "PayPal USD\0\0", "PYUSD\0");
+    let mint_data: MintData = mint_data_from_pubkey_and_account(&mint_pubkey, &account, None).unwrap();
+
+    assert_eq!(mint_data.symbol.as_deref(), Some("PYUSD"));
+    assert_eq!(mint_data.name.as_deref(), Some("PayPal USD"));
+    assert_eq!(mint_data.metadata_source, Some(TokenMetadataSource::Token2022Extension));
+}
+
+#[test]
+fn token_2022_metadata_of_another_mint_is_ignored() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let account: Account = token_2022_mint_with_metadata(&Pubkey::new_unique(), "PayPal USD", "PYUSD");
+    let mint_data: MintData = mint_data_from_pubkey_and_account(&mint_pubkey, &account, None).unwrap();
+
+    assert_eq!(mint_data.metadata_source, None);
+    assert_eq!(mint_data.symbol, None);
+}
+
+#[test]
+fn token_2022_metadata_takes_precedence_over_metaplex() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let account: Account = token_2022_mint_with_metadata(&mint_pubkey, "PayPal USD", "PYUSD");
+    let mut mint_data: MintData = mint_data_from_pubkey_and_account(&mint_pubkey, &account, None).unwrap();
+    apply_metaplex_metadata(&mut mint_data, Some(&metaplex_metadata_account(&mint_pubkey, "Spoofed", "SPOOF")));
+
+    assert_eq!(mint_data.symbol.as_deref(), Some("PYUSD"));
+    assert_eq!(mint_data.metadata_source, Some(TokenMetadataSource::Token2022Extension));
+}
+
+#[test]
+fn overrides_are_parsed_from_json_keyed_by_mint() {
+    let (sol, usdc): (Pubkey, Pubkey) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let json: String = format!(r#"{{"{sol}": {{"symbol": "SOL", "name": "Wrapped SOL"}}, "{usdc}": {{"symbol": "USDC"}}}}"#);
+    let overrides: TokenMetadataOverrides = TokenMetadataOverrides::from_json_str(&json).unwrap();
+
+    assert_eq!(overrides.overrides.len(), 2);
+    assert_eq!(
+        overrides.overrides[&sol],
+        TokenMetadataOverride { symbol: "SOL".to_string(), name: Some("Wrapped SOL".to_string()) }
+    );
+    assert_eq!(overrides.overrides[&usdc], TokenMetadataOverride { symbol: "USDC".to_string(), name: None });
+}
+
+#[test]
+fn malformed_overrides_are_rejected() {
+    for json in [r#"{"not a pubkey": {"symbol": "SOL"}}"#, r#"{"So11111111111111111111111111111111111111112": {}}"#, "[]"] {
+        assert!(
+            matches!(TokenMetadataOverrides::from_json_str(json), Err(OrcaQuoterError::Deserialisation(_))),
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn an_override_replaces_fetched_metadata() {
+    let (mint_pubkey, other_mint): (Pubkey, Pubkey) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let overrides: TokenMetadataOverrides = TokenMetadataOverrides::from_json_str(
+        &format!(r#"{{"{mint_pubkey}": {{"symbol": "wSOL"}}}}"#)
+    ).unwrap();
+
+    let mut mint_data: MintData = spl_token_mint_with_metaplex(
+        &mint_pubkey, &metaplex_metadata_account(&mint_pubkey, "Wrapped SOL", "SOL")
+    );
+    overrides.apply(&mut mint_data);
+    assert_eq!(mint_data.symbol.as_deref(), Some("wSOL"));
+    // The override has no name, so the fetched one is dropped rather than mixed with the override's symbol
+    assert_eq!(mint_data.name, None);
+    assert_eq!(mint_data.metadata_source, Some(TokenMetadataSource::Override));
+
+    // Mints without an override keep their metadata
+    let mut other: MintData = spl_token_mint_with_metaplex(
+        &other_mint, &metaplex_metadata_account(&other_mint, "USD Coin", "USDC")
+    );
+    overrides.apply(&mut other);
+    assert_eq!(other.symbol.as_deref(), Some("USDC"));
+    assert_eq!(other.metadata_source, Some(TokenMetadataSource::Metaplex));
+}
@@ -0,0 +1,91 @@
+// Token-2022 transfer fees are decoded from the TransferFeeConfig extension, and the fee in effect at
+// the epoch the mint was read at is picked from its older and newer fee
+
+mod common;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+use spl_token_2022::extension::{
+    ExtensionType, transfer_fee::TransferFeeConfig
+};
+
+use mint::{
+    mint::mint_data_from_pubkey_and_account, structs::{MintData, TransferFeeConfigData, TransferFeeData}
+};
+
+use common::{
+    DECIMALS, SUPPLY, spl_token_mint_account, token_2022_mint_account
+};
+
+// The fee update set before NEWER_FEE_EPOCH, in effect from it
+const NEWER_FEE_EPOCH: u64 = 500;
+const OLDER_FEE: TransferFeeData = TransferFeeData { epoch: 0, maximum_fee: 5_000, transfer_fee_basis_points: 100 };
+const NEWER_FEE: TransferFeeData = TransferFeeData { epoch: NEWER_FEE_EPOCH, maximum_fee: 10_000, transfer_fee_basis_points: 250 };
+
+/// Token-2022 mint whose TransferFeeConfig holds OLDER_FEE and NEWER_FEE
+fn transfer_fee_mint_account() -> Account {
+    token_2022_mint_account(DECIMALS, &[ExtensionType::TransferFeeConfig], |state| {
+        let config: &mut TransferFeeConfig = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee.epoch = OLDER_FEE.epoch.into();
+        config.older_transfer_fee.maximum_fee = OLDER_FEE.maximum_fee.into();
+        config.older_transfer_fee.transfer_fee_basis_points = OLDER_FEE.transfer_fee_basis_points.into();
+        config.newer_transfer_fee.epoch = NEWER_FEE.epoch.into();
+        config.newer_transfer_fee.maximum_fee = NEWER_FEE.maximum_fee.into();
+        config.newer_transfer_fee.transfer_fee_basis_points = NEWER_FEE.transfer_fee_basis_points.into();
+    })
+}
+
+#[test]
+fn fee_at_picks_the_older_fee_before_the_newer_fee_epoch() {
+    let config: TransferFeeConfigData = TransferFeeConfigData { older_transfer_fee: OLDER_FEE, newer_transfer_fee: NEWER_FEE };
+    assert_eq!(config.fee_at(Some(0)), OLDER_FEE);
+    assert_eq!(config.fee_at(Some(NEWER_FEE_EPOCH - 1)), OLDER_FEE);
+    assert_eq!(config.fee_at(Some(NEWER_FEE_EPOCH)), NEWER_FEE);
+    assert_eq!(config.fee_at(Some(NEWER_FEE_EPOCH + 1)), NEWER_FEE);
+    // An unknown epoch assumes the update has taken effect
+    assert_eq!(config.fee_at(None), NEWER_FEE);
+}
+
+#[test]
+fn token_2022_mints_decode_their_transfer_fee_config() {
+    let mint_pubkey: Pubkey = Pubkey::new_unique();
+    let mint_data: MintData = mint_data_from_pubkey_and_account(
+        &mint_pubkey, &transfer_fee_mint_account(), Some(NEWER_FEE_EPOCH - 1)
+    ).unwrap();
+
+    assert_eq!(mint_data.pubkey, mint_pubkey);
+    assert_eq!((mint_data.decimals, mint_data.supply), (DECIMALS, SUPPLY));
+    assert_eq!(mint_data.token_program, spl_token_2022::id());
+    assert_eq!(
+        mint_data.transfer_fee_config,
+        Some(TransferFeeConfigData { older_transfer_fee: OLDER_FEE, newer_transfer_fee: NEWER_FEE })
+    );
+    assert_eq!(mint_data.transfer_fee, Some(OLDER_FEE));
+}
+
+#[test]
+fn the_active_fee_follows_the_epoch_the_mint_was_read_at() {
+    let account: Account = transfer_fee_mint_account();
+    let fee_at = |epoch: Option<u64>| -> Option<TransferFeeData> {
+        mint_data_from_pubkey_and_account(&Pubkey::new_unique(), &account, epoch).unwrap().transfer_fee
+    };
+    assert_eq!(fee_at(Some(NEWER_FEE_EPOCH - 1)), Some(OLDER_FEE));
+    assert_eq!(fee_at(Some(NEWER_FEE_EPOCH)), Some(NEWER_FEE));
+    assert_eq!(fee_at(None), Some(NEWER_FEE));
+}
+
+#[test]
+fn mints_without_the_extension_have_no_transfer_fee() {
+    let spl_token_mint: MintData = mint_data_from_pubkey_and_account(
+        &Pubkey::new_unique(), &spl_token_mint_account(DECIMALS), Some(NEWER_FEE_EPOCH)
+    ).unwrap();
+    assert_eq!(spl_token_mint.token_program, spl_token::id());
+    assert_eq!((spl_token_mint.transfer_fee_config, spl_token_mint.transfer_fee), (None, None));
+
+    let plain_token_2022_mint: MintData = mint_data_from_pubkey_and_account(
+        &Pubkey::new_unique(), &token_2022_mint_account(DECIMALS, &[], |_| {}), Some(NEWER_FEE_EPOCH)
+    ).unwrap();
+    assert_eq!(plain_token_2022_mint.token_program, spl_token_2022::id());
+    assert_eq!(plain_token_2022_mint.transfer_fee, None);
+}
@@ -0,0 +1,60 @@
+// Raw amounts are converted into UI units for any on-chain decimals, format_ui_amount prints them
+// exactly even past the 38 decimals 10^decimals fits in a u128
+
+use mint::mint::{
+    format_ui_amount, ui_amount
+};
+
+/// "0." followed by zeros and then digits, the UI amount of a raw amount below one whole token
+fn below_one(zeros: usize, digits: &str) -> String {
+    format!("0.{}{}", "0".repeat(zeros), digits)
+}
+
+/// Whether two floats agree to within 1e-12 of the expected value
+fn approx_eq(value: f64, expected: f64) -> bool {
+    (value - expected).abs() <= expected.abs() * 1e-12
+}
+
+#[test]
+fn zero_decimal_amounts_have_no_fraction() {
+    assert_eq!(format_ui_amount(0, 0), "0");
+    assert_eq!(format_ui_amount(1_500_000, 0), "1500000");
+    assert_eq!(format_ui_amount(u64::MAX, 0), u64::MAX.to_string());
+    assert_eq!(ui_amount(1_500_000, 0), 1_500_000.0);
+}
+
+#[test]
+fn fractions_keep_their_significant_digits() {
+    assert_eq!(format_ui_amount(0, 6), "0.0");
+    assert_eq!(format_ui_amount(1, 6), "0.000001");
+    assert_eq!(format_ui_amount(1_000_000, 6), "1.0");
+    assert_eq!(format_ui_amount(1_500_000, 6), "1.5");
+    assert_eq!(format_ui_amount(142_310_000, 6), "142.31");
+    assert_eq!(ui_amount(1_500_000, 6), 1.5);
+
+    assert_eq!(format_ui_amount(1_000_000_001, 9), "1.000000001");
+    assert_eq!(format_ui_amount(u64::MAX, 9), "18446744073.709551615");
+    assert_eq!(ui_amount(2_500_000_000, 9), 2.5);
+}
+
+#[test]
+fn the_largest_u128_scale_is_exact() {
+    // u64::MAX has 20 digits, 38 decimals leave 18 leading zeros
+    assert_eq!(format_ui_amount(1, 38), below_one(37, "1"));
+    assert_eq!(format_ui_amount(u64::MAX, 38), below_one(18, "18446744073709551615"));
+    assert_eq!(format_ui_amount(10, 38), below_one(36, "1"));
+    assert!(approx_eq(ui_amount(1, 38), 1e-38));
+}
+
+#[test]
+fn decimals_past_the_u128_scale_do_not_overflow() {
+    assert_eq!(format_ui_amount(0, 39), "0.0");
+    assert_eq!(format_ui_amount(1, 39), below_one(38, "1"));
+    assert_eq!(format_ui_amount(u64::MAX, 39), below_one(19, "18446744073709551615"));
+    assert!(approx_eq(ui_amount(1, 39), 1e-39));
+
+    assert_eq!(format_ui_amount(0, 255), "0.0");
+    assert_eq!(format_ui_amount(u64::MAX, 255), below_one(235, "18446744073709551615"));
+    assert!(approx_eq(ui_amount(u64::MAX, 255), u64::MAX as f64 * 1e-255));
+    assert_eq!(ui_amount(0, 255), 0.0);
+}
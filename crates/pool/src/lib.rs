@@ -1,3 +1,6 @@
 pub mod pool_state;
 pub mod pda;
-pub mod structs;
\ No newline at end of file
+pub mod structs;
+pub mod websocket;
+pub mod serde_facades;
+pub mod snapshot;
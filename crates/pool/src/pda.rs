@@ -1,9 +1,10 @@
 // PDA utils ///
 
-use solana_program::program_error::ProgramError;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
+use error::error::OrcaQuoterError;
+
 
 // Quick function to get the whirlpool master pubkey
 pub fn parse_whirlpool_master_pubkey() -> Pubkey {
@@ -19,11 +20,11 @@ pub fn parse_whirlpool_master_pubkey() -> Pubkey {
 ///     - start_tick_index: The first tick in the tick array
 /// 
 /// Returns: 
-///     - A tuple containing the tick array's pubkey and the discriminant or an error
+///     - A tuple containing the tick array's pubkey and the discriminant or an OrcaQuoterError
 pub fn get_tick_array_address(
     pool_pubkey: &Pubkey,
     start_tick_index: i32,
-) -> Result<(Pubkey, u8), ProgramError> {
+) -> Result<(Pubkey, u8), OrcaQuoterError> {
     let start_tick_index_str = start_tick_index.to_string();
     let seeds = &[
         b"tick_array",
@@ -31,7 +32,9 @@ pub fn get_tick_array_address(
         start_tick_index_str.as_bytes(),
     ];
     let whirlpool_master_pubkey: Pubkey = parse_whirlpool_master_pubkey(); 
-    Pubkey::try_find_program_address(seeds, &whirlpool_master_pubkey).ok_or(ProgramError::InvalidSeeds)
+    Pubkey::try_find_program_address(seeds, &whirlpool_master_pubkey).ok_or(OrcaQuoterError::PdaDerivation(
+        format!("Invalid tick array seeds for pool {} at start tick index {}", pool_pubkey, start_tick_index)
+    ))
 }
 
 
@@ -39,11 +42,13 @@ pub fn get_tick_array_address(
 ///     - pool_pubkey: Pointer to the pool's pubkey
 /// 
 /// Returns: 
-///     - A tuple containing the oracle's pubkey and the discriminant or an error
-pub fn get_oracle_address(pool_pubkey: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+///     - A tuple containing the oracle's pubkey and the discriminant or an OrcaQuoterError
+pub fn get_oracle_address(pool_pubkey: &Pubkey) -> Result<(Pubkey, u8), OrcaQuoterError> {
     let seeds = &[b"oracle", pool_pubkey.as_ref()];
     let whirlpool_master_pubkey: Pubkey = parse_whirlpool_master_pubkey(); 
-    Pubkey::try_find_program_address(seeds, &whirlpool_master_pubkey).ok_or(ProgramError::InvalidSeeds)
+    Pubkey::try_find_program_address(seeds, &whirlpool_master_pubkey).ok_or(OrcaQuoterError::PdaDerivation(
+        format!("Invalid oracle seeds for pool {}", pool_pubkey)
+    ))
 }
 
 
@@ -1,58 +1,68 @@
-use std::str::{
-    FromStr
-};
-use std::time::{
-    SystemTime, UNIX_EPOCH
-};
 use std::collections::{
-    HashMap
+    HashMap, HashSet
 };
 
-use solana_client::{
-    rpc_client::RpcClient, client_error::ClientError
-};
 use solana_sdk::{
-    account::Account, pubkey::Pubkey
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+
+use futures::{
+    executor::block_on, join
 };
 
 use orca_whirlpools_client::{
     Whirlpool, TickArray, Oracle, 
 };
 use orca_whirlpools_core::{
-    WhirlpoolFacade, TickArrayFacade, TickArrays, OracleFacade,
-    get_tick_array_start_tick_index
+    WhirlpoolFacade, TickArrayFacade, TickFacade, TickArrays, OracleFacade,
+    get_tick_array_start_tick_index, TICK_ARRAY_SIZE, MIN_TICK_INDEX, MAX_TICK_INDEX
 };
 
 use crate::pda::{
-    get_tick_array_address, get_oracle_address
+    get_tick_array_address, get_oracle_address, parse_whirlpool_master_pubkey
 };
 
 use crate::structs::{
-    PoolState
+    PoolState, FetchMetadata, ComponentFetch, TickArrayWindow
+};
+
+use error::{
+    error::OrcaQuoterError, clock::current_unix_timestamp_ms
+};
+use provider::{
+    provider::AccountProvider, nonblocking::{AsyncAccountProvider, BlockingAccountProvider}, structs::AtSlot
 };
 
 use mint::{
-    structs::MintData, mint::mint_data_from_client_and_pubkey
+    structs::{MintData, MintCache, FetchedMint}, mint::mint_data_from_pubkeys_async
 };
 
 
 /// Fetches data for PoolState struct and serialises into PoolState struct
 /// 
 /// Parameters: 
-///     - client: The RPC client used to fetch data 
+///     - provider: The account provider (e.g. RpcClient) used to fetch data 
 ///     - pool_pubkey: The pool's pubkey
 /// 
 /// Returns: 
-///     - The populated PoolState struct
-pub fn LEGACY_from_client_and_pubkey_via_RPC(client: &RpcClient, pool_pubkey: &Pubkey) -> PoolState{
-    // Fetch whirlpool account with client and serialise into Whirlpool struct 
-    let pool_account: Account = client.get_account(pool_pubkey)
-        .expect("Failed to fetch whirlpool account.");
-    let whirlpool_timestamp: u64 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("whirlpool fetch timestamp failed")
-        .as_secs();
-    let whirlpool: Whirlpool = Whirlpool::from_bytes(&pool_account.data).unwrap();
+///     - The populated PoolState struct or an OrcaQuoterError
+#[allow(non_snake_case)]
+pub fn LEGACY_from_client_and_pubkey_via_RPC<P: AccountProvider + Sync + ?Sized>(
+    provider: &P, pool_pubkey: &Pubkey
+) -> Result<PoolState, OrcaQuoterError> {
+    let endpoint: String = provider.endpoint();
+
+    // Fetch whirlpool account with provider and serialise into Whirlpool struct 
+    let pool_account: AtSlot<Option<Account>> = provider.get_account(pool_pubkey)?;
+    let whirlpool_fetched_at_ms: u64 = current_unix_timestamp_ms()?;
+    let pool_account: AtSlot<Account> = AtSlot {
+        slot: pool_account.slot,
+        value: pool_account.value.ok_or(OrcaQuoterError::AccountNotFound(*pool_pubkey))?,
+    };
+    verify_whirlpool_program_owner(pool_pubkey, &pool_account.value)?;
+    let whirlpool: Whirlpool = Whirlpool::from_bytes(&pool_account.value.data).map_err(|err| {
+        OrcaQuoterError::Deserialisation(format!("Failed to deserialize whirlpool account {}: {}", pool_pubkey, err))
+    })?;
 
     // Derive current tick array pubkey
     let whirlpool_facade: WhirlpoolFacade = WhirlpoolFacade::from(whirlpool.clone());
@@ -60,235 +70,341 @@ pub fn LEGACY_from_client_and_pubkey_via_RPC(client: &RpcClient, pool_pubkey: &P
         whirlpool_facade.tick_current_index, whirlpool_facade.tick_spacing
     );
     let (tick_array_pubkey, _tick_array_discriminant): (Pubkey, u8) = get_tick_array_address(
-        &pool_pubkey, current_tick_array_start_index)
-        .expect("Failed to derive tick array address"); 
+        pool_pubkey, current_tick_array_start_index)?; 
     
     // Fetch current tick array account and serialise into TickArrays struct 
-    let tick_array_account: Account = client.get_account(&tick_array_pubkey).unwrap();
-    let tick_array_timestamp: u64 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("tick array fetch timestamp failed")
-        .as_secs();
-    let tick_array: TickArray = TickArray::from_bytes(&tick_array_account.data).unwrap();
+    let tick_array_account: AtSlot<Option<Account>> = provider.get_account(&tick_array_pubkey)?;
+    let tick_array_fetched_at_ms: u64 = current_unix_timestamp_ms()?;
+    let tick_array_account: AtSlot<Account> = AtSlot {
+        slot: tick_array_account.slot,
+        value: tick_array_account.value.ok_or(OrcaQuoterError::AccountNotFound(tick_array_pubkey))?,
+    };
+    verify_whirlpool_program_owner(&tick_array_pubkey, &tick_array_account.value)?;
+    let tick_array: TickArray = TickArray::from_bytes(&tick_array_account.value.data).map_err(|err| {
+        OrcaQuoterError::Deserialisation(format!("Failed to deserialize tick array {}: {}", tick_array_pubkey, err))
+    })?;
     let tick_array_facade: TickArrayFacade = TickArrayFacade::from(tick_array);
     let tick_arrays: TickArrays = TickArrays::One(tick_array_facade);
 
     // Derive oracle pubkey
     let (oracle_pubkey, _oracle_discriminant): (Pubkey, u8) = get_oracle_address(
-    &pool_pubkey).unwrap(); 
+    pool_pubkey)?; 
 
     // Fetch oracle account and serialise into Option<Oracle> enum
-    let oracle_account_result: Result<Account, _> = client.get_account(&oracle_pubkey);
-    let oracle_timestamp: u64 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("oracle fetch timestamp failed")
-        .as_secs();
-    let oracle_account: Option<Account> = match oracle_account_result {
-        Ok(acc) => Some(acc),
-        Err(err) => {
-            eprintln!("failed to fetch oracle account: {}", err);
-            None
-        }
-    };
-    let oracle: Option<OracleFacade> = oracle_account.map(|acct| {
-        let oracle: Oracle = Oracle::from_bytes(&acct.data).unwrap();
-        OracleFacade::from(oracle)
-    });
+        // Most pools have no oracle, so a missing oracle account is not an error, an undecodable one is
+    let oracle_account: AtSlot<Option<Account>> = provider.get_account(&oracle_pubkey)?;
+    let oracle_fetched_at_ms: u64 = current_unix_timestamp_ms()?;
+    let oracle: Option<(OracleFacade, ComponentFetch)> = oracle_account.value
+        .map(|acct| oracle_facade_from_account(&oracle_pubkey, &acct, oracle_account.slot, oracle_fetched_at_ms, &endpoint))
+        .transpose()?;
 
     // Derive mint pubkeys
-    let mint_a_pubkey = Pubkey::from_str(&whirlpool.token_mint_a.to_string().as_str())
-        .expect("Failed to derive token A mint pubkey");
-    let mint_b_pubkey = Pubkey::from_str(&whirlpool.token_mint_b.to_string().as_str())
-        .expect("Failed to derive token B mint pubkey");
-
-    // Populate MintData structs
-    let mint_a_data: MintData = mint_data_from_client_and_pubkey(
-        &client, &mint_a_pubkey 
-    ); 
-    let mint_a_timestamp: u64 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("mint a fetch timestamp failed")
-        .as_secs();
-    let mint_b_data: MintData = mint_data_from_client_and_pubkey(
-        &client, &mint_b_pubkey 
-    ); 
-    let mint_b_timestamp: u64 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("mint b fetch timestamp failed")
-        .as_secs();
-
-    // Construct timestamp hashmap
-    let mut timestamps: HashMap<&'static str, u64> = HashMap::new();
-    timestamps.insert("whirlpool",   whirlpool_timestamp);
-    timestamps.insert("current_tick_array",  tick_array_timestamp);
-    timestamps.insert("oracle",      oracle_timestamp);
-    timestamps.insert("mint_a",      mint_a_timestamp);
-    timestamps.insert("mint_b",      mint_b_timestamp);
-
-    PoolState { 
+        // Client Pubkey type differs from the solana-sdk Pubkey type, convert via raw bytes
+    let mint_a_pubkey: Pubkey = Pubkey::new_from_array(whirlpool.token_mint_a.to_bytes());
+    let mint_b_pubkey: Pubkey = Pubkey::new_from_array(whirlpool.token_mint_b.to_bytes());
+
+    // Populate MintData structs, both mints in one request and never from the cache
+    let mut fetched_mints: HashMap<Pubkey, Result<FetchedMint, OrcaQuoterError>> = block_on(
+        mint_data_from_pubkeys_async(&BlockingAccountProvider(provider), &[mint_a_pubkey, mint_b_pubkey], None, None)
+    )?;
+    let mut take_mint = |mint_pubkey: Pubkey| -> Result<(MintData, ComponentFetch), OrcaQuoterError> {
+        fetched_mints.remove(&mint_pubkey)
+            .unwrap_or(Err(OrcaQuoterError::AccountNotFound(mint_pubkey)))
+            .map(mint_data_and_fetch)
+    };
+    let (mint_a_data, mint_a_fetch): (MintData, ComponentFetch) = take_mint(mint_a_pubkey)?;
+    let (mint_b_data, mint_b_fetch): (MintData, ComponentFetch) = take_mint(mint_b_pubkey)?;
+
+    // Construct fetch metadata
+    let metadata: FetchMetadata = FetchMetadata {
+        commitment: provider.commitment(),
+        // Only the current tick array is fetched
+        tick_array_window: TickArrayWindow { left: 0, right: 0 },
+        whirlpool: ComponentFetch::from_account(
+            &pool_account.value, pool_account.slot, whirlpool_fetched_at_ms, &endpoint
+        ),
+        tick_arrays: vec![ComponentFetch::from_account(
+            &tick_array_account.value, tick_array_account.slot, tick_array_fetched_at_ms, &endpoint
+        )],
+        oracle: oracle.as_ref().map(|(_, fetch)| fetch.clone()),
+        mint_a: Some(mint_a_fetch),
+        mint_b: Some(mint_b_fetch),
+    };
+
+    Ok(PoolState { 
         whirlpool: whirlpool_facade,
         current_tick_array: tick_arrays,
-        oracle: oracle,
-        mint_a_data: mint_a_data,
-        mint_b_data: mint_b_data,
-        timestamps: timestamps
-    }
+        oracle: oracle.map(|(facade, _)| facade),
+        mint_a_data,
+        mint_b_data,
+        metadata,
+    })
 }
 
 
-/// Populate PoolState struct from RPC client and pool address
+/// Per pool results, every phase of populate_pool_states_async carries the pool pubkey alongside
+/// its data so a failed pool can never shift the data of the pools after it
+type PerPool<T> = Vec<(Pubkey, Result<T, OrcaQuoterError>)>;
+
+/// (start tick index, pubkey) of the tick arrays in a pool's window, in ascending start tick index order
+type TickArrayPubkeys = Vec<(i32, Pubkey)>;
+
+/// (start tick index, Account) of a tick array at the slot it was read at, None where the tick array 
+/// was never initialised
+type TickArrayAccount = (i32, AtSlot<Option<Account>>);
+
+/// Tick array Accounts of a pool's window, in ascending start tick index order
+type TickArrayAccounts = Vec<TickArrayAccount>;
+
+/// Whirlpool with its fetch metadata
+type FetchedWhirlpool = (Whirlpool, ComponentFetch);
+
+/// MintData with its fetch metadata, None for unfetched placeholders
+type FetchedMintData = (MintData, Option<ComponentFetch>);
+
+
+/// Populate PoolState struct from account provider and pool address
+///     - Blocking wrapper over populate_pool_states_async, it blocks the calling thread until every 
+///       phase completes, so callers already inside async code (e.g. a tokio worker) must await 
+///       populate_pool_states_async instead
+///     - Through BlockingAccountProvider the tick array, oracle and mint phases run one after another, 
+///       the only concurrency is the thread based page concurrency of max_concurrency
 /// 
 /// Parameters:
-///     - client: a pointer to an RPC client
+///     - provider: a pointer to an account provider, e.g. an RPC client
 ///     - pool_pubkeys: a pointer to a vector of pool addresses
+///     - tick_array_window: the number of tick arrays to fetch either side of each pool's current tick array
 ///     - require_all_accounts: a boolean indicating whether to return an error if any pool account is None
-///     - require_all_tick_arrays: a boolean indicating whether to return an error if any left/right tick array pubkey is None
-///     - fetch_mint_data: a boolean indicating whether to fetch mint data via RPC client
+///     - require_all_tick_arrays: a boolean indicating whether to return an error if any tick array in the 
+///       window fails to derive or deserialise, uninitialised tick arrays are substituted with empty ones
+///     - fetch_mint_data: a boolean indicating whether to fetch mint data via the account provider
+///     - max_concurrency: the maximum number of get_multiple_accounts pages in flight at once, 
+///       None fetches pages sequentially
 /// 
 /// Returns:
-///     - A vector of populated PoolState structs
-pub fn populate_pool_states(
-    client: &RpcClient, pool_pubkeys: &Vec<Pubkey>, require_all_accounts: bool, require_all_tick_arrays: bool, 
-    fetch_mint_data: bool
-) -> Result<Vec<PoolState>, String> {
-    // Verify that there are <= 33 pool pubkeys, get_multiple_accounts works only up to 100 
-    // accounts, and we fetch 3 * num_pools when collecting tickarrays 
-    if pool_pubkeys.len() > 33 {
-        return Err("Too many pool pubkeys, max 33".to_string());
-    }
+///     - A vector of (pool pubkey, PoolState or per pool error) tuples, in the order of pool_pubkeys, 
+///       or a batch level OrcaQuoterError
+pub fn populate_pool_states<P: AccountProvider + Sync + ?Sized>(
+    provider: &P, pool_pubkeys: &[Pubkey], tick_array_window: TickArrayWindow, require_all_accounts: bool, 
+    require_all_tick_arrays: bool, fetch_mint_data: bool, max_concurrency: Option<usize>
+) -> Result<PerPool<PoolState>, OrcaQuoterError> {
+    block_on(populate_pool_states_async(
+        &BlockingAccountProvider(provider), pool_pubkeys, tick_array_window, require_all_accounts, 
+        require_all_tick_arrays, fetch_mint_data, max_concurrency
+    ))
+}
 
-    // Phase 1: Construct Vec<WhirlpoolFacade>
 
-    // Phase 1.1: Fetch Vec<Option<Account>> with RPC client
-        // We use the private fn fetch_pool_accounts_via_rpc
-    let pool_accounts_result: Result<Vec<Account>, String> = fetch_pool_accounts_via_rpc(
-        client, pool_pubkeys, require_all_accounts
-    );
+/// Populate PoolState struct from async account provider and pool address
+///     - The returned future is Send, so it can be handed to tokio::spawn with an owned provider
+/// 
+/// Parameters:
+///     - provider: a pointer to an async account provider, e.g. a nonblocking RPC client
+///     - pool_pubkeys: a pointer to a vector of pool addresses
+///     - tick_array_window: the number of tick arrays to fetch either side of each pool's current tick array
+///     - require_all_accounts: a boolean indicating whether to return an error if any pool account is None
+///     - require_all_tick_arrays: a boolean indicating whether to return an error if any tick array in the 
+///       window fails to derive or deserialise, uninitialised tick arrays are substituted with empty ones
+///     - fetch_mint_data: a boolean indicating whether to fetch mint data via the account provider
+///     - max_concurrency: the maximum number of get_multiple_accounts pages in flight at once per phase, 
+///       None awaits pages one after another
+/// 
+/// Returns:
+///     - A vector of (pool pubkey, PoolState or per pool error) tuples, in the order of pool_pubkeys, 
+///       or a batch level OrcaQuoterError
+pub async fn populate_pool_states_async<P: AsyncAccountProvider + ?Sized>(
+    provider: &P, pool_pubkeys: &[Pubkey], tick_array_window: TickArrayWindow, require_all_accounts: bool, 
+    require_all_tick_arrays: bool, fetch_mint_data: bool, max_concurrency: Option<usize>
+) -> Result<PerPool<PoolState>, OrcaQuoterError> {
+    // There is no cap on the number of pools, every get_multiple_accounts call below is paged 
+    // into <= 100 key requests by AsyncAccountProvider::get_multiple_accounts_paged
+    let endpoint: String = provider.endpoint();
+    let commitment: CommitmentConfig = provider.commitment();
+
+    // Phase 1: Construct per pool WhirlpoolFacades
+
+    // Phase 1.1: Fetch per pool Accounts with account provider
+        // We use the private fn fetch_pool_accounts_via_provider
         // Failure to fetch pool accounts is a critical error
-    if pool_accounts_result.is_err() {
-        return Err(pool_accounts_result.err().unwrap());
-    }
-    let pool_accounts: Vec<Account> = pool_accounts_result.unwrap();
-
-    // Phase 1.2: Construct Vec<WhirlpoolFacade>
-        // We use the private fn deserialise_into_whirlpool_facades
-    let whirlpool_facades_result: Result<Vec<WhirlpoolFacade>, String> 
-        = deserialise_into_whirlpool_facades(
-            pool_accounts, require_all_accounts
-        );
-        // Error Catch
-    if whirlpool_facades_result.is_err() {
-        return Err(whirlpool_facades_result.err().unwrap());
-    }
-    let whirlpool_facades: Vec<WhirlpoolFacade> = whirlpool_facades_result.unwrap();
-
-    // Phase 2: Construct Vec<TickArrays>
-
-    // Phase 2.1: Derive left, right, and current tick array pubkeys from Vec<WhirlpoolFacade> and Vec<Pubkey> (pools)#
-        // We use the private fn left_right_and_current_tick_array_pubkeys_from_whirlpool_facades
-    let tick_array_pubkeys_result: Result<Vec<(Pubkey, Pubkey, Pubkey)>, String> 
-        = left_right_and_current_tick_array_pubkeys_from_whirlpool_facades(
-            &whirlpool_facades, pool_pubkeys, require_all_tick_arrays
-        );
-    // Error Catch
-    if tick_array_pubkeys_result.is_err() {
-        return Err(tick_array_pubkeys_result.err().unwrap());
-    }
-
-    // Phase 2.2: Fetch Vec<(Account, Account, Account)> with RPC client
-        // We use the private fn left_right_and_current_tick_array_accounts_from_pubkeys_via_rpc
-    let tick_array_accounts_result: Result<Vec<(Account, Account, Account)>, String> 
-        = left_right_and_current_tick_array_accounts_from_pubkeys_via_rpc(
-            client, &tick_array_pubkeys_result.unwrap(), require_all_tick_arrays
-        );
-    // Error Catch
-    if tick_array_accounts_result.is_err() {
-        return Err(tick_array_accounts_result.err().unwrap());
-    }
-    let tick_array_accounts: Vec<(Account, Account, Account)> = tick_array_accounts_result.unwrap();
+    let pool_accounts: PerPool<AtSlot<Account>> = fetch_pool_accounts_via_provider(
+        provider, pool_pubkeys, require_all_accounts, max_concurrency
+    ).await?;
+    let whirlpool_fetched_at_ms: u64 = current_unix_timestamp_ms()?;
+
+    // Phase 1.2: Construct per pool Whirlpools
+        // We use the private fn deserialise_into_whirlpools, the full Whirlpool struct is 
+        // kept as WhirlpoolFacade carries no mint information
+    let whirlpools: PerPool<FetchedWhirlpool> = deserialise_into_whirlpools(
+        pool_accounts, require_all_accounts, whirlpool_fetched_at_ms, &endpoint
+    )?;
+
+    // Phase 1.3: Construct per pool WhirlpoolFacades
+    let whirlpool_facades: PerPool<WhirlpoolFacade> = whirlpools.iter()
+        .map(|(pool_pubkey, whirlpool)| (
+            *pool_pubkey, 
+            whirlpool.as_ref()
+                .map(|(whirlpool, _)| WhirlpoolFacade::from(whirlpool.clone()))
+                .map_err(|err| err.clone())
+        ))
+        .collect();
+
+    // Phase 2: Derive the accounts to fetch from the Whirlpools
+
+    // Phase 2.1: Derive the tick array window's pubkeys from the WhirlpoolFacades
+        // We use the private fn tick_array_pubkeys_from_whirlpool_facades
+    let tick_array_pubkeys: PerPool<TickArrayPubkeys> = tick_array_pubkeys_from_whirlpool_facades(
+        &whirlpool_facades, tick_array_window, require_all_tick_arrays
+    )?;
+
+    // Phase 2.2: Derive the deduplicated mint pubkeys from the Whirlpools
+        // Pools commonly share mints (USDC, SOL), so each mint is fetched once
+    let mint_pubkeys: Vec<Pubkey> = unique_mint_pubkeys_from_whirlpools(&whirlpools);
+
+    // Phase 3: Fetch tick arrays, oracles and mints
+        // The three fetches only depend on the Whirlpools, so they run concurrently, each 
+        // stamped when it completes
+
+    // Phase 3.1: Fetch per pool tick array window Accounts with account provider
+        // We use the private fn tick_array_accounts_from_pubkeys_via_provider
+    let tick_array_phase = async {
+        let tick_array_accounts: Result<PerPool<TickArrayAccounts>, OrcaQuoterError> 
+            = tick_array_accounts_from_pubkeys_via_provider(
+                provider, &tick_array_pubkeys, require_all_tick_arrays, max_concurrency
+            ).await;
+        (tick_array_accounts, current_unix_timestamp_ms())
+    };
 
-    // Phase 3: Construct Vec<TickArrays>
+    // Phase 3.2: Fetch per pool oracle Accounts via account provider 
+        // We use the private fn fetch_oracles_from_pubkeys_via_provider
+        // A failed fetch is a batch level error, quoting an adaptive fee pool without its 
+        // oracle would understate the fee
+    let oracle_phase = async {
+        let oracle_accounts: Result<PerPool<Option<AtSlot<Account>>>, OrcaQuoterError> 
+            = fetch_oracles_from_pubkeys_via_provider(provider, pool_pubkeys, max_concurrency).await;
+        (oracle_accounts, current_unix_timestamp_ms())
+    };
 
-    let tick_arrays_result = deserialise_into_tick_arrays(
-        tick_array_accounts, require_all_tick_arrays
-    );
-    if tick_arrays_result.is_err() {
-        return Err(tick_arrays_result.err().unwrap());
-    }
-    let tick_arrays: Vec<TickArrays> = tick_arrays_result.unwrap();
-
-    // TODO: ORACLES AND MINTS, THEN DONE :)
-
-    // Phase 3: Construct Vec<Option<OracleFacade>>  
-
-    // Phase 3.1: Fetch Vec<Option<Account>> via RPC client 
-        // We use the private fn fetch_oracles_from_pubkeys_via_rpc
-        // If the RPC call fails, use a fallback Vec of `None` with the same length 
-        // so downstream logic remains safe.
-    let oracle_accounts: Vec<Option<Account>> = match fetch_oracles_from_pubkeys_via_rpc(
-        client, pool_pubkeys) {
-        Ok(accounts) => accounts,
-        Err(err) => {
-            eprintln!("Failed to fetch oracle accounts: {}", err);
-            vec![None; pool_pubkeys.len()]
+    // Phase 3.3: Fetch and deserialise mint accounts via account provider
+        // We use the private fn fetch_mint_data_via_provider, if mint data is not requested 
+        // we fall back to unfetched MintData placeholders
+    let mint_phase = async {
+        if fetch_mint_data {
+            fetch_mint_data_via_provider(provider, &mint_pubkeys, max_concurrency).await
+        } else {
+            Ok(mint_pubkeys.iter()
+                .map(|mint_pubkey| (*mint_pubkey, Ok((MintData::unfetched(*mint_pubkey), None))))
+                .collect())
         }
     };
 
-    // Phase 3.2: Deserialise Vec<Option<Account>> into Vec<Option<OracleFacade>>
+    let (
+        (tick_array_accounts, tick_array_fetched_at_ms), 
+        (oracle_accounts, oracle_fetched_at_ms), 
+        mint_data
+    ) = join!(tick_array_phase, oracle_phase, mint_phase);
+        // Error Catch
+    let tick_array_accounts: PerPool<TickArrayAccounts> = tick_array_accounts?;
+    let oracle_accounts: PerPool<Option<AtSlot<Account>>> = oracle_accounts?;
+    let mint_data: HashMap<Pubkey, Result<FetchedMintData, OrcaQuoterError>> = mint_data?;
+    let (tick_array_fetched_at_ms, oracle_fetched_at_ms): (u64, u64) = (
+        tick_array_fetched_at_ms?, oracle_fetched_at_ms?
+    );
+
+    // Phase 4: Deserialise the fetched accounts
+
+    // Phase 4.1: Deserialise per pool tick array window Accounts into TickArrays
+    let tick_arrays: PerPool<(TickArrays, Vec<ComponentFetch>)> = deserialise_into_tick_arrays(
+        tick_array_accounts, require_all_tick_arrays, tick_array_fetched_at_ms, &endpoint
+    )?;
+
+    // Phase 4.2: Deserialise per pool oracle Accounts into Option<OracleFacade>, None for pools 
+    // without an oracle
         // We use the private fn deserialise_into_oracle_facades
-        // Again, if the deserialisation fails, use a fallback Vec of `None` with the 
-        // same length so downstream logic remains safe.
-    let oracle_facades: Vec<Option<OracleFacade>> = match deserialise_into_oracle_facades(oracle_accounts) {
-        Ok(facades) => facades,
-        Err(err) => {
-            eprintln!("Failed to deserialize oracle accounts: {}", err);
-            vec![None; pool_pubkeys.len()]
-        }
-    };
+    let oracle_facades: PerPool<Option<(OracleFacade, ComponentFetch)>> = deserialise_into_oracle_facades(
+        oracle_accounts, require_all_accounts, oracle_fetched_at_ms, &endpoint
+    )?;
+
+    // Phase 5: Zip whirlpools, tick arrays, oracles and mints into per pool PoolStates
+        // Every per pool vector is aligned with pool_pubkeys, the first error hit for a pool 
+        // is the one reported
+    let mut pool_states: PerPool<PoolState> = Vec::new();
+    for (((pool_pubkey, whirlpool), (_, tick_arrays)), (_, oracle)) in whirlpools.into_iter()
+        .zip(tick_arrays)
+        .zip(oracle_facades) 
+    {
+        let pool_state: Result<PoolState, OrcaQuoterError> = whirlpool.and_then(|(whirlpool, whirlpool_fetch)| {
+            let (tick_arrays, tick_array_fetches): (TickArrays, Vec<ComponentFetch>) = tick_arrays?;
+            let oracle: Option<(OracleFacade, ComponentFetch)> = oracle?;
+            let mint_a_pubkey: Pubkey = Pubkey::new_from_array(whirlpool.token_mint_a.to_bytes());
+            let mint_b_pubkey: Pubkey = Pubkey::new_from_array(whirlpool.token_mint_b.to_bytes());
+            let (mint_a_data, mint_a_fetch): FetchedMintData = match mint_data.get(&mint_a_pubkey) {
+                Some(data) => data.clone()?,
+                None => return Err(OrcaQuoterError::AccountNotFound(mint_a_pubkey)),
+            };
+            let (mint_b_data, mint_b_fetch): FetchedMintData = match mint_data.get(&mint_b_pubkey) {
+                Some(data) => data.clone()?,
+                None => return Err(OrcaQuoterError::AccountNotFound(mint_b_pubkey)),
+            };
 
-    // TODO: Mint data and final PoolState construction
+            // Construct fetch metadata
+            let (oracle, oracle_fetch): (Option<OracleFacade>, Option<ComponentFetch>) = oracle.unzip();
+            let metadata: FetchMetadata = FetchMetadata {
+                commitment,
+                tick_array_window,
+                whirlpool: whirlpool_fetch,
+                tick_arrays: tick_array_fetches,
+                oracle: oracle_fetch,
+                mint_a: mint_a_fetch,
+                mint_b: mint_b_fetch,
+            };
+
+            Ok(PoolState {
+                whirlpool: WhirlpoolFacade::from(whirlpool),
+                current_tick_array: tick_arrays,
+                oracle,
+                mint_a_data,
+                mint_b_data,
+                metadata,
+            })
+        });
+        pool_states.push((pool_pubkey, pool_state));
+    }
 
-    todo!()
+    Ok(pool_states)
 }
 
 
-/// Private Functions
+// Private Functions
 
 
-/// Fetch Vec<Account> for pools via RPC client 
+/// Fetch per pool Accounts via account provider 
 /// 
 /// Parameters: 
+///     - provider: a pointer to an account provider
 ///     - pool_pubkeys: A pointer to the Vec<Pubkey> struct containing pool pubkeys
 ///     - require_all_accounts: A bool dictating whether to flag an error if an account is empty
+///     - max_concurrency: the maximum number of pages in flight at once
 /// 
 /// Returns:
-///     - A Vec<Account> struct containing the pool accounts or a String type error code
-fn fetch_pool_accounts_via_rpc(
-    client: &RpcClient, pool_pubkeys: &Vec<Pubkey>, require_all_accounts: bool
-) -> Result<Vec<Account>, String> {
-    let pool_accounts_response: Result<Vec<Option<Account>>, _> = client.get_multiple_accounts(&pool_pubkeys);
-    if pool_accounts_response.is_err() {
-        return Err("Failed to fetch pool accounts".to_string());
-    }
-    let pool_account_options: Vec<Option<Account>> = pool_accounts_response.unwrap();
-
-    // Phase 1.2: Deserialise Vec<Option<Account>> into Vec<Account> 
-    let mut pool_accounts: Vec<Account> = Vec::new();
-    for (i, pool_account ) in pool_account_options.iter().enumerate() {
-        if pool_account.is_some() {
-            pool_accounts.push(pool_account.clone().unwrap());
-        }
-        else {
-            if require_all_accounts {
-                return Err(format!(
-                    "Failed to fetch pool account with address {}",
-                    pool_pubkeys[i].to_string()
-                ));
-            }
-            else {
-                eprintln!("Failed to fetch pool account with address {}", 
-                    pool_pubkeys[i].to_string());
-                continue;
+///     - The per pool Accounts at the slot they were read at, aligned with pool_pubkeys, or a batch 
+///       level OrcaQuoterError
+async fn fetch_pool_accounts_via_provider<P: AsyncAccountProvider + ?Sized>(
+    provider: &P, pool_pubkeys: &[Pubkey], require_all_accounts: bool, max_concurrency: Option<usize>
+) -> Result<PerPool<AtSlot<Account>>, OrcaQuoterError> {
+    let pool_account_options: Vec<AtSlot<Option<Account>>> = provider.get_multiple_accounts_paged(
+        pool_pubkeys, max_concurrency
+    ).await?;
+
+    // Deserialise Vec<AtSlot<Option<Account>>> into per pool Accounts
+    let mut pool_accounts: PerPool<AtSlot<Account>> = Vec::new();
+    for (pool_pubkey, pool_account) in pool_pubkeys.iter().zip(pool_account_options) {
+        match pool_account.value {
+            Some(account) => pool_accounts.push((*pool_pubkey, Ok(AtSlot { slot: pool_account.slot, value: account }))),
+            None => {
+                if require_all_accounts {
+                    return Err(OrcaQuoterError::AccountNotFound(*pool_pubkey));
+                }
+                pool_accounts.push((*pool_pubkey, Err(OrcaQuoterError::AccountNotFound(*pool_pubkey))));
             }
         }
     }
@@ -296,327 +412,477 @@ fn fetch_pool_accounts_via_rpc(
 }
 
 
-/// Deserialise Vec<Account> into Vec<WhirlpoolFacade>
+/// Deserialise per pool Accounts into per pool Whirlpools
 /// 
 /// Parameters:
-///     - pool_accounts: a vector of Account structs, obtained from RPC client
-///     - require_all_accounts: a boolean indicating whether to return an error if any pool account is None
+///     - pool_accounts: the per pool Account structs, obtained from account provider
+///     - require_all_accounts: a boolean indicating whether to return an error if any pool account fails to deserialise
+///     - fetched_at_ms: the time the pool accounts were fetched, in milliseconds since UNIX_EPOCH
+///     - endpoint: the provider's endpoint label
 /// 
 /// Returns:
-///     - A vector of WhirlpoolFacade structs
-fn deserialise_into_whirlpool_facades(
-    pool_accounts: Vec<Account>, require_all_accounts: bool
-) -> Result<Vec<WhirlpoolFacade>, String> {
-
-    let mut whirlpool_facades: Vec<WhirlpoolFacade> = Vec::new();
-    for (i, account) in pool_accounts.iter().enumerate() {
-        match Whirlpool::from_bytes(&account.data) {
-            Ok(whirlpool) => whirlpool_facades.push(WhirlpoolFacade::from(whirlpool)),
+///     - The per pool Whirlpool structs with their ComponentFetch, or a batch level OrcaQuoterError
+fn deserialise_into_whirlpools(
+    pool_accounts: PerPool<AtSlot<Account>>, require_all_accounts: bool, fetched_at_ms: u64, endpoint: &str
+) -> Result<PerPool<FetchedWhirlpool>, OrcaQuoterError> {
+
+    let mut whirlpools: PerPool<FetchedWhirlpool> = Vec::new();
+    for (pool_pubkey, account) in pool_accounts.into_iter() {
+        // Pools whose account failed to fetch keep their error
+        let AtSlot { slot, value: account } = match account {
+            Ok(account) => account,
             Err(err) => {
-                if require_all_accounts {
-                    return Err(format!(
-                        "Failed to deserialize whirlpool account at index {}: {}",
-                        i, err
-                    ));
-                } else {
-                    println!(
-                        "Failed to deserialize whirlpool account at index {}: {}",
-                        i, err
-                    );
-                    continue;
-                }
+                whirlpools.push((pool_pubkey, Err(err)));
+                continue;
             }
+        };
+        let whirlpool: Result<FetchedWhirlpool, OrcaQuoterError> = verify_whirlpool_program_owner(&pool_pubkey, &account)
+            .and_then(|_| Whirlpool::from_bytes(&account.data).map_err(|err| OrcaQuoterError::Deserialisation(
+                format!("Failed to deserialize whirlpool account {}: {}", pool_pubkey, err)
+            )))
+            .map(|whirlpool| (whirlpool, ComponentFetch::from_account(&account, slot, fetched_at_ms, endpoint)));
+        if require_all_accounts && let Err(err) = &whirlpool {
+            return Err(err.clone());
         }
+        whirlpools.push((pool_pubkey, whirlpool));
     }
 
-    Ok(whirlpool_facades)
+    Ok(whirlpools)
 }
 
 
-/// Derive left, right, and current tick array pubkeys from Vec<WhirlpoolFacade> and Vec<Pubkey>
+/// Derive the tick array window's pubkeys from per pool WhirlpoolFacades
 /// 
 /// Parameters:
-///     - whirlpool_facades: a vector of WhirlpoolFacade structs, obtained from deserialisation of Vec<Account>
-///     - pool_pubkeys: a vector of pool pubkeys
-///     - require_all_tick_arrays: a boolean indicating whether to return an error if left/right tick array pubkeys are None
+///     - whirlpool_facades: the per pool WhirlpoolFacade structs, obtained from deserialisation of the pool Accounts
+///     - tick_array_window: the number of tick arrays either side of the current tick array
+///     - require_all_tick_arrays: a boolean indicating whether to return an error if any tick array pubkey fails to derive
 /// 
 /// Returns:
-///     - A vector of tuples, each containing the left, right, and current tick array pubkeys
-fn left_right_and_current_tick_array_pubkeys_from_whirlpool_facades(
-    whirlpool_facades: &Vec<WhirlpoolFacade>, pool_pubkeys: &Vec<Pubkey>, require_all_tick_arrays: bool
-) -> Result<Vec<(Pubkey, Pubkey, Pubkey)>, String> {
-     
-    // Phase 1: Find start tick idxs 
-    let mut start_tick_idxs: Vec<(i32, i32, i32)> = Vec::new();
-    for whirlpool_facade in whirlpool_facades.iter() {
-        let current_start_tick_idx: i32 = get_tick_array_start_tick_index(
-            whirlpool_facade.tick_current_index, whirlpool_facade.tick_spacing
-        );
-        let i32_tick_spacing: i32 = whirlpool_facade.tick_spacing as i32;
-        // Each tick array is 88 ticks wide
-        let left_start_tick_idx: i32 = current_start_tick_idx - i32_tick_spacing*88;
-        let right_start_tick_idx: i32 = current_start_tick_idx + i32_tick_spacing*88;
-        start_tick_idxs.push((left_start_tick_idx, current_start_tick_idx, right_start_tick_idx));
-    }
-
-    // Phase 2: Construct Vec<(Pubkey, Pubkey, Pubkey)> of tick array pubkeys
-    let mut tick_array_pubkeys: Vec<(Pubkey, Pubkey, Pubkey)> = Vec::new();
-    for (i, (left_start_idx, current_start_idx, right_start_idx)) in start_tick_idxs.iter().enumerate() {
-        // Derive left tick array pubkey
-        let left_pubkey = match get_tick_array_address(&pool_pubkeys[i], *left_start_idx) {
-            Ok((pk, _)) => pk,
-            Err(err) => {
-                if require_all_tick_arrays {
-                    return Err(format!(
-                        "Failed to derive left tick array address for {}: {}",
-                        pool_pubkeys[i], err
-                    ));
-                } else {
-                    println!(
-                        "Failed to derive left tick array address for {}: {}",
-                        pool_pubkeys[i], err
-                    );
-                    continue;
-                }
-            }
-        };
-
-        // Derive current tick array pubkey
-            // We always return an error if current tick array pubkey is None 
-            // as this is a critical error
-        let current_pubkey = match get_tick_array_address(
-            &pool_pubkeys[i], *current_start_idx
-        ) {
-            Ok((pk, _)) => pk,
+///     - The per pool (start tick index, tick array pubkey) tuples, in ascending start tick index order, 
+///       or a batch level OrcaQuoterError
+fn tick_array_pubkeys_from_whirlpool_facades(
+    whirlpool_facades: &[(Pubkey, Result<WhirlpoolFacade, OrcaQuoterError>)], tick_array_window: TickArrayWindow,
+    require_all_tick_arrays: bool
+) -> Result<PerPool<TickArrayPubkeys>, OrcaQuoterError> {
+
+    let mut tick_array_pubkeys: PerPool<TickArrayPubkeys> = Vec::new();
+    for (pool_pubkey, whirlpool_facade) in whirlpool_facades.iter() {
+        let whirlpool_facade: &WhirlpoolFacade = match whirlpool_facade {
+            Ok(facade) => facade,
             Err(err) => {
-                return Err(format!(
-                    "Failed to derive current tick array address for {}: {}",
-                    pool_pubkeys[i], err
-                ));
-            }
-        };
-
-        // Derive right tick array pubkey
-        let right_pubkey = match get_tick_array_address(&pool_pubkeys[i], *right_start_idx) {
-            Ok((pk, _)) => pk,
-            Err(err) => {
-                if require_all_tick_arrays {
-                    return Err(format!(
-                        "Failed to derive right tick array address for {}: {}",
-                        pool_pubkeys[i], err
-                    ));
-                } else {
-                    println!(
-                        "Failed to derive right tick array address for {}: {}",
-                        pool_pubkeys[i], err
-                    );
-                    continue;
-                }
+                tick_array_pubkeys.push((*pool_pubkey, Err(err.clone())));
+                continue;
             }
         };
 
-        tick_array_pubkeys.push((left_pubkey, current_pubkey, right_pubkey));
+        // Phase 1: Find the window's start tick idxs
+        let start_idxs: Vec<i32> = tick_array_start_indexes(whirlpool_facade, tick_array_window);
+
+        // Phase 2: Derive the window's tick array pubkeys
+            // A failed derivation is an error for the pool, and a batch error only if require_all_tick_arrays
+        let pubkeys: Result<TickArrayPubkeys, OrcaQuoterError> = start_idxs.iter()
+            .map(|start_idx| get_tick_array_address(pool_pubkey, *start_idx)
+                .map(|(pk, _)| (*start_idx, pk))
+                .map_err(|err| OrcaQuoterError::PdaDerivation(format!(
+                    "Failed to derive tick array address at start tick index {} for {}: {}",
+                    start_idx, pool_pubkey, err
+                ))))
+            .collect();
+
+        if require_all_tick_arrays && let Err(err) = &pubkeys {
+            return Err(err.clone());
+        }
+        tick_array_pubkeys.push((*pool_pubkey, pubkeys));
     }
 
     Ok(tick_array_pubkeys)
 }
 
 
-fn left_right_and_current_tick_array_accounts_from_pubkeys_via_rpc(
-    client: &RpcClient, tick_array_pubkeys: &Vec<(Pubkey, Pubkey, Pubkey)>, require_all_tick_arrays: bool
-) -> Result<Vec<(Account, Account, Account)>, String> {
+/// Find the start tick indexes of the tick arrays in a window around the current tick
+///     - Tick arrays lying entirely outside [MIN_TICK_INDEX, MAX_TICK_INDEX] can never exist and are 
+///       left out, so pools priced at the extremes hold fewer tick arrays than the window
+/// 
+/// Parameters:
+///     - whirlpool_facade: a pointer to the pool's WhirlpoolFacade
+///     - tick_array_window: the number of tick arrays either side of the current tick array
+/// 
+/// Returns:
+///     - The start tick indexes in ascending order
+pub fn tick_array_start_indexes(
+    whirlpool_facade: &WhirlpoolFacade, tick_array_window: TickArrayWindow
+) -> Vec<i32> {
+    let current_start_idx: i32 = get_tick_array_start_tick_index(
+        whirlpool_facade.tick_current_index, whirlpool_facade.tick_spacing
+    );
+    // Each tick array is 88 ticks wide
+    let tick_array_width: i32 = whirlpool_facade.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    (-(tick_array_window.left as i32)..=tick_array_window.right as i32)
+        .map(|offset| current_start_idx + offset*tick_array_width)
+        .filter(|start_idx| start_idx + tick_array_width > MIN_TICK_INDEX && *start_idx <= MAX_TICK_INDEX)
+        .collect()
+}
 
-    // Phase 1: Flatten Vec<(Pubkey, Pubkey, Pubkey)> into Vec<Pubkey>
-        // Structured as left_1, current_1, right_1, left_2, current_2, right_2, ...
-    let mut flattened_pubkeys: Vec<Pubkey> = Vec::new();
-    for (left_pubkey, current_pubkey, right_pubkey) in tick_array_pubkeys.iter() {
-        flattened_pubkeys.push(left_pubkey.clone());
-        flattened_pubkeys.push(current_pubkey.clone());
-        flattened_pubkeys.push(right_pubkey.clone());
-    }
 
-    // Phase 2: Fetch Vec<Option<Account>> with RPC client 
-        // get_multiple_accounts is order preserving
-    let tick_array_accounts_response: Result<Vec<Option<Account>>, _> = client.get_multiple_accounts(&flattened_pubkeys);
-    if tick_array_accounts_response.is_err() {
-        return Err("Failed to fetch tick array accounts".to_string());
+/// Empty, zero liquidity tick array standing in for a tick array that was never initialised on chain
+/// 
+/// Parameters:
+///     - start_tick_index: the start tick index of the uninitialised tick array
+/// 
+/// Returns:
+///     - A TickArrayFacade of 88 uninitialised ticks
+pub(crate) fn empty_tick_array_facade(start_tick_index: i32) -> TickArrayFacade {
+    TickArrayFacade {
+        start_tick_index,
+        ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
     }
-    let tick_array_account_options: Vec<Option<Account>> = tick_array_accounts_response.unwrap();
-
-    // Phase 3: Deserialise Vec<Option<Account>> into Vec<Account>
-        // Structured as left_1, current_1, right_1, left_2, current_2, right_2, ...
-    let mut tick_array_accounts: Vec<Account> = Vec::new();
-    for (i, tick_array_account) in tick_array_account_options.iter().enumerate() {
-        if tick_array_account.is_some() {
-            tick_array_accounts.push(tick_array_account.clone().unwrap());
-        }
-        else {
-            if require_all_tick_arrays {
-                return Err(format!(
-                    "Failed to fetch tick array account at index {}: missing account",
-                    i
-                ));
-            }
-            else {
-                println!(
-                    "Failed to fetch tick array account at index {}: missing account",
-                    i
-                );
+}
+
+
+/// Fetch per pool tick array window Accounts via account provider
+/// 
+/// Parameters:
+///     - provider: a pointer to an account provider
+///     - tick_array_pubkeys: the per pool tick array window pubkeys
+///     - require_all_tick_arrays: a boolean indicating whether to return an error if any tick array account is 
+///       owned by another program
+///     - max_concurrency: the maximum number of pages in flight at once
+/// 
+/// Returns:
+///     - The per pool tick array window Accounts, each at the slot it was read at and None where the tick 
+///       array was never initialised, or a batch level OrcaQuoterError
+async fn tick_array_accounts_from_pubkeys_via_provider<P: AsyncAccountProvider + ?Sized>(
+    provider: &P, tick_array_pubkeys: &[(Pubkey, Result<TickArrayPubkeys, OrcaQuoterError>)], 
+    require_all_tick_arrays: bool, max_concurrency: Option<usize>
+) -> Result<PerPool<TickArrayAccounts>, OrcaQuoterError> {
+
+    // Phase 1: Flatten the derived tick array pubkeys into Vec<Pubkey>
+        // Structured as pool_1's window, pool_2's window, ..., windows may differ in length
+        // Pools whose pubkeys failed to derive are skipped
+    let flattened_pubkeys: Vec<Pubkey> = tick_array_pubkeys.iter()
+        .filter_map(|(_, pubkeys)| pubkeys.as_ref().ok())
+        .flatten()
+        .map(|(_, pubkey)| *pubkey)
+        .collect();
+
+    // Phase 2: Fetch Vec<Option<Account>> with account provider 
+        // get_multiple_accounts_paged is order preserving
+    let tick_array_account_options: Vec<AtSlot<Option<Account>>> = provider.get_multiple_accounts_paged(
+        &flattened_pubkeys, max_concurrency
+    ).await?;
+
+    // Phase 3: Regroup the accounts back into each pool's window
+    let mut fetched = tick_array_account_options.into_iter();
+    let mut tick_array_accounts: PerPool<TickArrayAccounts> = Vec::new();
+    for (pool_pubkey, pubkeys) in tick_array_pubkeys.iter() {
+        let pubkeys: &TickArrayPubkeys = match pubkeys {
+            Ok(pubkeys) => pubkeys,
+            Err(err) => {
+                tick_array_accounts.push((*pool_pubkey, Err(err.clone())));
                 continue;
             }
-        }
-    }
+        };
+        // Uninitialised tick arrays are kept as None, they are substituted with empty tick arrays 
+        // on deserialisation
+        let mut take = |(start_idx, tick_array_pubkey): &(i32, Pubkey)| -> Result<TickArrayAccount, OrcaQuoterError> {
+            let account: AtSlot<Option<Account>> = fetched.next()
+                .ok_or(OrcaQuoterError::AccountNotFound(*tick_array_pubkey))?;
+            if let Some(account) = &account.value {
+                verify_whirlpool_program_owner(tick_array_pubkey, account)?;
+            }
+            Ok((*start_idx, account))
+        };
+        // The whole window is taken before erroring so the iterator stays aligned with the next pool
+        let taken: Vec<Result<TickArrayAccount, OrcaQuoterError>> = pubkeys.iter().map(&mut take).collect();
+        let accounts: Result<TickArrayAccounts, OrcaQuoterError> = taken.into_iter().collect();
 
-    // Phase 4: regroup every 3 accounts back into (left, current, right)
-    let mut tick_array_account_tuples = Vec::new();
-    for chunk in tick_array_accounts.chunks(3) {
-        if chunk.len() < 3 { break; } // shouldn't occur, but be safe
-        tick_array_account_tuples.push((
-            chunk[0].clone(),
-            chunk[1].clone(),
-            chunk[2].clone(),
-        ));
+        if require_all_tick_arrays && let Err(err) = &accounts {
+            return Err(err.clone());
+        }
+        tick_array_accounts.push((*pool_pubkey, accounts));
     }
 
-    Ok(tick_array_account_tuples)
+    Ok(tick_array_accounts)
 }
 
 
+/// Deserialise per pool tick array window Accounts into TickArrays
+/// 
+/// Parameters:
+///     - tick_array_accounts: the per pool tick array window Accounts
+///     - require_all_tick_arrays: a boolean indicating whether to return an error if any tick array fails to deserialise
+///     - fetched_at_ms: the time the tick array accounts were fetched, in milliseconds since UNIX_EPOCH
+///     - endpoint: the provider's endpoint label
+/// 
+/// Returns:
+///     - The per pool TickArrays, One through Six depending on the window, with one ComponentFetch per 
+///       tick array, or a batch level OrcaQuoterError
+///     - Uninitialised tick arrays are substituted with empty, zero liquidity tick arrays at their start 
+///       tick index, as the Orca SDK does, so quotes still work near the edges of liquidity
 fn deserialise_into_tick_arrays(
-    tick_array_accounts: Vec<(Account, Account, Account)>,
+    tick_array_accounts: PerPool<TickArrayAccounts>,
     require_all_tick_arrays: bool,
-) -> Result<Vec<TickArrays>, String> {
-    let mut result: Vec<TickArrays> = Vec::new();
-
-    for (i, (left_acc, current_acc, right_acc)) in tick_array_accounts.iter().enumerate() {
-        // Helper macro to attempt deserialisation with unified error handling.
-        macro_rules! try_deser_tick_array {
-            ($acc:expr, $side:expr) => {
-                match TickArray::from_bytes(&$acc.data) {
-                    Ok(ta) => ta,
-                    Err(err) => {
-                        if require_all_tick_arrays {
-                            return Err(format!(
-                                "Failed to deserialize {} tick array at index {}: {}",
-                                $side, i, err
-                            ));
-                        } else {
-                            println!(
-                                "Failed to deserialize {} tick array at index {}: {}",
-                                $side, i, err
-                            );
-                            continue;
-                        }
-                    }
-                }
-            };
+    fetched_at_ms: u64,
+    endpoint: &str,
+) -> Result<PerPool<(TickArrays, Vec<ComponentFetch>)>, OrcaQuoterError> {
+    let mut result: PerPool<(TickArrays, Vec<ComponentFetch>)> = Vec::new();
+
+    for (pool_pubkey, accounts) in tick_array_accounts.into_iter() {
+        let accounts: TickArrayAccounts = match accounts {
+            Ok(accounts) => accounts,
+            Err(err) => {
+                result.push((pool_pubkey, Err(err)));
+                continue;
+            }
+        };
+
+        // Helper closure to attempt deserialisation with unified error handling.
+        let deserialise = |(start_idx, account): &TickArrayAccount| -> Result<TickArrayFacade, OrcaQuoterError> {
+            match &account.value {
+                Some(account) => TickArray::from_bytes(&account.data)
+                    .map(TickArrayFacade::from)
+                    .map_err(|err| OrcaQuoterError::Deserialisation(format!(
+                        "Failed to deserialize tick array at start tick index {} for pool {}: {}",
+                        start_idx, pool_pubkey, err
+                    ))),
+                None => Ok(empty_tick_array_facade(*start_idx)),
+            }
+        };
+        let fetch = |(_, account): &TickArrayAccount| -> ComponentFetch {
+            match &account.value {
+                Some(value) => ComponentFetch::from_account(value, account.slot, fetched_at_ms, endpoint),
+                None => ComponentFetch::from_missing_account(account.slot, fetched_at_ms, endpoint),
+            }
+        };
+        let tick_arrays: Result<(TickArrays, Vec<ComponentFetch>), OrcaQuoterError> = accounts.iter()
+            .map(deserialise)
+            .collect::<Result<Vec<TickArrayFacade>, OrcaQuoterError>>()
+            .and_then(|facades| tick_arrays_from_facades(&facades))
+            .map(|tick_arrays| (tick_arrays, accounts.iter().map(fetch).collect()));
+
+        if require_all_tick_arrays && let Err(err) = &tick_arrays {
+            return Err(err.clone());
         }
+        result.push((pool_pubkey, tick_arrays));
+    }
 
-        let left_ta = try_deser_tick_array!(left_acc, "left");
-        let current_ta = try_deser_tick_array!(current_acc, "current");
-        let right_ta = try_deser_tick_array!(right_acc, "right");
+    Ok(result)
+}
 
-        // Convert to facades
-        let left_facade = TickArrayFacade::from(left_ta);
-        let current_facade = TickArrayFacade::from(current_ta);
-        let right_facade = TickArrayFacade::from(right_ta);
 
-        result.push(TickArrays::Three(left_facade, current_facade, right_facade));
+/// Derive per pool oracle addresses & fetch their Accounts via account provider
+/// 
+/// Parameters:
+///     - provider: a pointer to an account provider
+///     - pool_pubkeys: a pointer to a vector of pool pubkeys
+///     - max_concurrency: the maximum number of pages in flight at once
+/// 
+/// Returns:
+///     - The per pool oracle Accounts, None where the oracle does not exist and an error where its 
+///       address fails to derive, or a batch level OrcaQuoterError if the fetch itself fails
+async fn fetch_oracles_from_pubkeys_via_provider<P: AsyncAccountProvider + ?Sized>(
+    provider: &P,
+    pool_pubkeys: &[Pubkey],
+    max_concurrency: Option<usize>,
+) -> Result<PerPool<Option<AtSlot<Account>>>, OrcaQuoterError> {
+    // Phase 1: Derive per pool oracle pubkeys
+    let oracle_pubkeys: PerPool<Pubkey> = pool_pubkeys.iter()
+        .map(|pool_pubkey| (*pool_pubkey, get_oracle_address(pool_pubkey).map(|(pk, _)| pk)))
+        .collect();
+
+    // Phase 2: Fetch the derived oracle accounts (order preserving)
+    let pubkeys_to_fetch: Vec<Pubkey> = oracle_pubkeys.iter()
+        .filter_map(|(_, oracle_pubkey)| oracle_pubkey.as_ref().ok().copied())
+        .collect();
+    let fetched_accounts: Vec<AtSlot<Option<Account>>> = provider.get_multiple_accounts_paged(
+        &pubkeys_to_fetch, max_concurrency
+    ).await?;
+
+    // Phase 3: Regroup the accounts back into per pool results, pools whose oracle address failed 
+    // to derive keep their error
+    let mut fetched = fetched_accounts.into_iter();
+    let mut oracle_accounts: PerPool<Option<AtSlot<Account>>> = Vec::new();
+    for (pool_pubkey, oracle_pubkey) in oracle_pubkeys.into_iter() {
+        let oracle_account: Result<Option<AtSlot<Account>>, OrcaQuoterError> = oracle_pubkey.and_then(|oracle_pubkey| {
+            let account: AtSlot<Option<Account>> = fetched.next()
+                .ok_or(OrcaQuoterError::AccountNotFound(oracle_pubkey))?;
+            let slot: u64 = account.slot;
+            Ok(account.value.map(|account| AtSlot { slot, value: account }))
+        });
+        oracle_accounts.push((pool_pubkey, oracle_account));
     }
 
-    Ok(result)
+    Ok(oracle_accounts)
 }
 
 
-/// Derive Oracle addresses & fetch Vec<Option<Account>> via RPC client
+/// Deserialise per pool oracle Accounts into per pool Option<(OracleFacade, ComponentFetch)>
 /// 
 /// Parameters:
-///     - client: a pointer to an RPC client
-///     - pool_pubkeys: a pointer to avector of pool pubkeys
+///     - oracle_accounts: the per pool oracle Accounts, obtained from account provider
+///     - require_all_accounts: a boolean indicating whether to return an error if any oracle account fails to deserialise
+///     - fetched_at_ms: the time the oracle accounts were fetched, in milliseconds since UNIX_EPOCH
+///     - endpoint: the provider's endpoint label
 /// 
 /// Returns:
-///     - A vector of Option<Account> structs
-fn fetch_oracles_from_pubkeys_via_rpc(
-    client: &RpcClient,
-    pool_pubkeys: &Vec<Pubkey>,
-) -> Result<Vec<Option<Account>>, String> {
-    // Phase 1: derive oracle pubkeys (Vec<Option<Pubkey>> of same length)
-    let mut oracle_pubkeys: Vec<Option<Pubkey>> = Vec::new();
-    for (i, pool_pk) in pool_pubkeys.iter().enumerate() {
-        match get_oracle_address(pool_pk) {
-            Ok((pk, _)) => oracle_pubkeys.push(Some(pk)),
-            Err(err) => {
-                eprintln!(
-                    "Failed to derive oracle pubkey for pool at index {}: {}",
-                    i, err
-                );
-                oracle_pubkeys.push(None);
-            }
+///     - The per pool oracles, None for pools without one, or a batch level OrcaQuoterError
+fn deserialise_into_oracle_facades(
+    oracle_accounts: PerPool<Option<AtSlot<Account>>>, require_all_accounts: bool, fetched_at_ms: u64, endpoint: &str
+) -> Result<PerPool<Option<(OracleFacade, ComponentFetch)>>, OrcaQuoterError> {
+    let mut oracle_facades: PerPool<Option<(OracleFacade, ComponentFetch)>> = Vec::new();
+    for (pool_pubkey, account) in oracle_accounts.into_iter() {
+        let oracle: Result<Option<(OracleFacade, ComponentFetch)>, OrcaQuoterError> = account.and_then(|account| {
+            account.map(|AtSlot { slot, value: account }| {
+                let oracle_pubkey: Pubkey = get_oracle_address(&pool_pubkey)?.0;
+                oracle_facade_from_account(&oracle_pubkey, &account, slot, fetched_at_ms, endpoint)
+            }).transpose()
+        });
+        if require_all_accounts && let Err(err) = &oracle {
+            return Err(err.clone());
         }
+        oracle_facades.push((pool_pubkey, oracle));
     }
 
-    // Collect indices & pubkeys where we actually have a key to fetch
-    let mut indices: Vec<usize> = Vec::new();
-    let mut pubkeys_to_fetch: Vec<Pubkey> = Vec::new();
-    for (idx, maybe_pk) in oracle_pubkeys.iter().enumerate() {
-        if let Some(pk) = maybe_pk {
-            indices.push(idx);
-            pubkeys_to_fetch.push(pk.clone());
+    Ok(oracle_facades)
+}
+
+
+/// Deserialise an oracle Account into an OracleFacade and record its fetch
+/// 
+/// Parameters:
+///     - oracle_pubkey: a pointer to the oracle's pubkey, used for error reporting
+///     - account: a pointer to the oracle Account
+///     - slot: the slot the account was read at
+///     - fetched_at_ms: the fetch time in milliseconds since UNIX_EPOCH
+///     - endpoint: the provider's endpoint label
+/// 
+/// Returns:
+///     - The (OracleFacade, ComponentFetch) pair or an OrcaQuoterError
+fn oracle_facade_from_account(
+    oracle_pubkey: &Pubkey, account: &Account, slot: u64, fetched_at_ms: u64, endpoint: &str
+) -> Result<(OracleFacade, ComponentFetch), OrcaQuoterError> {
+    verify_whirlpool_program_owner(oracle_pubkey, account)?;
+    let oracle: Oracle = Oracle::from_bytes(&account.data).map_err(|err| OrcaQuoterError::Deserialisation(
+        format!("Failed to deserialize oracle account {}: {}", oracle_pubkey, err)
+    ))?;
+    Ok((OracleFacade::from(oracle), ComponentFetch::from_account(account, slot, fetched_at_ms, endpoint)))
+}
+
+
+/// Derive the deduplicated token mint pubkeys from per pool Whirlpools
+/// 
+/// Parameters:
+///     - whirlpools: the per pool Whirlpool structs, failed pools are skipped
+/// 
+/// Returns:
+///     - A vector of unique mint pubkeys, in order of first appearance
+fn unique_mint_pubkeys_from_whirlpools(
+    whirlpools: &[(Pubkey, Result<FetchedWhirlpool, OrcaQuoterError>)]
+) -> Vec<Pubkey> {
+    let mut seen: HashSet<Pubkey> = HashSet::new();
+    let mut mint_pubkeys: Vec<Pubkey> = Vec::new();
+    for whirlpool in whirlpools.iter().filter_map(|(_, whirlpool)| whirlpool.as_ref().ok().map(|(whirlpool, _)| whirlpool)) {
+        // Client Pubkey type differs from the solana-sdk Pubkey type, convert via raw bytes
+        let mint_a_pubkey: Pubkey = Pubkey::new_from_array(whirlpool.token_mint_a.to_bytes());
+        let mint_b_pubkey: Pubkey = Pubkey::new_from_array(whirlpool.token_mint_b.to_bytes());
+        for mint_pubkey in [mint_a_pubkey, mint_b_pubkey] {
+            if seen.insert(mint_pubkey) {
+                mint_pubkeys.push(mint_pubkey);
+            }
         }
     }
+    mint_pubkeys
+}
 
-    // If there are no pubkeys to fetch just return vec of None, saves an RPC call
-    if pubkeys_to_fetch.is_empty() {
-        eprintln!("No oracle pubkeys to fetch");
-        return Ok(vec![None; pool_pubkeys.len()]);
-    }
 
-    // Phase 2: fetch all present oracle accounts (order-preserving)
-    let accounts_response: Result<Vec<Option<Account>>, _> = client.get_multiple_accounts(&pubkeys_to_fetch);
-    if accounts_response.is_err() {
-        eprintln!("Failed to fetch oracle accounts");
-        return Ok(vec![None; pool_pubkeys.len()]);
-    }
-    let fetched_accounts = accounts_response.unwrap();
+/// Fetch and deserialise mint accounts into HashMap<Pubkey, Result<FetchedMintData, OrcaQuoterError>> via account provider
+///     - Mints are fetched with mint_data_from_pubkeys_async through MintCache::global, a cached mint's 
+///       ComponentFetch records the fetch that filled its cache entry
+/// 
+/// Parameters:
+///     - provider: a pointer to an account provider
+///     - mint_pubkeys: a pointer to a vector of deduplicated mint pubkeys
+///     - max_concurrency: the maximum number of pages in flight at once
+/// 
+/// Returns:
+///     - A HashMap from mint pubkey to (MintData, ComponentFetch) or the mint's error, or a batch 
+///       level OrcaQuoterError if the fetch itself fails
+async fn fetch_mint_data_via_provider<P: AsyncAccountProvider + ?Sized>(
+    provider: &P, mint_pubkeys: &[Pubkey], max_concurrency: Option<usize>
+) -> Result<HashMap<Pubkey, Result<FetchedMintData, OrcaQuoterError>>, OrcaQuoterError> {
+    // A missing mint fails every pool using it as no quote can be built without decimals
+    let fetched_mints: HashMap<Pubkey, Result<FetchedMint, OrcaQuoterError>> = mint_data_from_pubkeys_async(
+        provider, mint_pubkeys, Some(MintCache::global()), max_concurrency
+    ).await?;
+    Ok(fetched_mints.into_iter()
+        .map(|(mint_pubkey, fetched_mint)| (
+            mint_pubkey,
+            fetched_mint.map(|fetched_mint| {
+                let (mint_data, fetch): (MintData, ComponentFetch) = mint_data_and_fetch(fetched_mint);
+                (mint_data, Some(fetch))
+            })
+        ))
+        .collect())
+}
 
-    // Phase 3: reconstruct Vec<Option<Account>> matching pool order
-    let mut oracle_accounts: Vec<Option<Account>> = vec![None; pool_pubkeys.len()];
-    for (pos, fetched_opt) in indices.iter().zip(fetched_accounts.into_iter()) {
-        oracle_accounts[*pos] = fetched_opt;
-    }
 
-    Ok(oracle_accounts)
+/// Split a FetchedMint into its MintData and ComponentFetch
+/// 
+/// Parameters:
+///     - fetched_mint: the FetchedMint, as returned by mint_data_from_pubkeys_async
+/// 
+/// Returns:
+///     - The (MintData, ComponentFetch) pair
+fn mint_data_and_fetch(fetched_mint: FetchedMint) -> (MintData, ComponentFetch) {
+    let fetch: ComponentFetch = ComponentFetch {
+        fetched_at_ms: fetched_mint.fetched_at_ms,
+        slot: fetched_mint.slot,
+        endpoint: fetched_mint.endpoint,
+        data_hash: fetched_mint.data_hash,
+    };
+    (fetched_mint.mint_data, fetch)
 }
 
 
-/// Deserialise Vec<Option<Account>> into Vec<Option<OracleFacade>>
+/// Verify that an account is owned by the Whirlpool program
 /// 
 /// Parameters:
-///     - oracle_accounts: a vector of Option<Account> structs, obtained from RPC client
+///     - pubkey: a pointer to the account's pubkey, used for error reporting
+///     - account: a pointer to the fetched account
 /// 
 /// Returns:
-///     - A vector of Option<OracleFacade> structs
-fn deserialise_into_oracle_facades(
-    oracle_accounts: Vec<Option<Account>>
-) -> Result<Vec<Option<OracleFacade>>, String> {
-    let mut oracle_facades: Vec<Option<OracleFacade>> = Vec::new();
-    for (i, account_opt) in oracle_accounts.iter().enumerate() {
-        match account_opt {
-            Some(acc) => {
-                match Oracle::from_bytes(&acc.data) {
-                    Ok(oracle) => oracle_facades.push(Some(OracleFacade::from(oracle))),
-                    Err(err) => {
-                        eprintln!(
-                            "Failed to deserialize oracle account at index {}: {}",
-                            i, err
-                        );
-                        oracle_facades.push(None);
-                    }
-                }
-            }
-            None => oracle_facades.push(None),
-        }
+///     - Ok(()) or OrcaQuoterError::WrongOwner
+pub(crate) fn verify_whirlpool_program_owner(pubkey: &Pubkey, account: &Account) -> Result<(), OrcaQuoterError> {
+    let whirlpool_master_pubkey: Pubkey = parse_whirlpool_master_pubkey();
+    if account.owner != whirlpool_master_pubkey {
+        return Err(OrcaQuoterError::WrongOwner {
+            account: *pubkey,
+            expected: vec![whirlpool_master_pubkey],
+            found: account.owner,
+        });
     }
+    Ok(())
+}
 
-    Ok(oracle_facades)
-}
\ No newline at end of file
+
+/// Rebuild a TickArrays enum from between one and six TickArrayFacades
+///
+/// Parameters:
+///     - facades: a pointer to the TickArrayFacades, in ascending start tick index order
+///
+/// Returns:
+///     - The TickArrays or OrcaQuoterError::Deserialisation if there are zero or more than six facades
+pub(crate) fn tick_arrays_from_facades(facades: &[TickArrayFacade]) -> Result<TickArrays, OrcaQuoterError> {
+    match *facades {
+        [a] => Ok(TickArrays::One(a)),
+        [a, b] => Ok(TickArrays::Two(a, b)),
+        [a, b, c] => Ok(TickArrays::Three(a, b, c)),
+        [a, b, c, d] => Ok(TickArrays::Four(a, b, c, d)),
+        [a, b, c, d, e] => Ok(TickArrays::Five(a, b, c, d, e)),
+        [a, b, c, d, e, f] => Ok(TickArrays::Six(a, b, c, d, e, f)),
+        _ => Err(OrcaQuoterError::Deserialisation(format!(
+            "Expected between 1 and 6 tick arrays, found {}", facades.len()
+        ))),
+    }
+}
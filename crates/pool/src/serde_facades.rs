@@ -0,0 +1,194 @@
+// Serde support for the orca_whirlpools_core facade types ///
+
+// orca_whirlpools_core only derives serde behind its wasm feature, so the facades are
+// serialised through serde remote definitions, use them with #[serde(with = "...")]
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer, de::Error
+};
+
+use orca_whirlpools_core::{
+    WhirlpoolFacade, WhirlpoolRewardInfoFacade, TickFacade, TickArrayFacade, TickArrays, OracleFacade,
+    AdaptiveFeeConstantsFacade, AdaptiveFeeVariablesFacade, NUM_REWARDS, TICK_ARRAY_SIZE
+};
+
+use crate::pool_state::tick_arrays_from_facades;
+
+
+/// Serde remote definition of WhirlpoolFacade
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "WhirlpoolFacade")]
+pub struct WhirlpoolFacadeDef {
+    pub fee_tier_index_seed: [u8; 2],
+    pub tick_spacing: u16,
+    pub fee_rate: u16,
+    pub protocol_fee_rate: u16,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub fee_growth_global_a: u128,
+    pub fee_growth_global_b: u128,
+    pub reward_last_updated_timestamp: u64,
+    #[serde(with = "reward_infos")]
+    pub reward_infos: [WhirlpoolRewardInfoFacade; NUM_REWARDS],
+}
+
+/// Serde remote definition of WhirlpoolRewardInfoFacade
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "WhirlpoolRewardInfoFacade")]
+pub struct WhirlpoolRewardInfoFacadeDef {
+    pub emissions_per_second_x64: u128,
+    pub growth_global_x64: u128,
+}
+
+/// Serde remote definition of TickFacade
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "TickFacade")]
+pub struct TickFacadeDef {
+    pub initialized: bool,
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_a: u128,
+    pub fee_growth_outside_b: u128,
+    pub reward_growths_outside: [u128; 3],
+}
+
+/// Serde remote definition of TickArrayFacade, the 88 ticks are written as a sequence
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "TickArrayFacade")]
+pub struct TickArrayFacadeDef {
+    pub start_tick_index: i32,
+    #[serde(with = "ticks")]
+    pub ticks: [TickFacade; TICK_ARRAY_SIZE],
+}
+
+/// Serde remote definition of OracleFacade
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "OracleFacade")]
+pub struct OracleFacadeDef {
+    pub trade_enable_timestamp: u64,
+    #[serde(with = "AdaptiveFeeConstantsFacadeDef")]
+    pub adaptive_fee_constants: AdaptiveFeeConstantsFacade,
+    #[serde(with = "AdaptiveFeeVariablesFacadeDef")]
+    pub adaptive_fee_variables: AdaptiveFeeVariablesFacade,
+}
+
+/// Serde remote definition of AdaptiveFeeConstantsFacade
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AdaptiveFeeConstantsFacade")]
+pub struct AdaptiveFeeConstantsFacadeDef {
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub adaptive_fee_control_factor: u32,
+    pub max_volatility_accumulator: u32,
+    pub tick_group_size: u16,
+    pub major_swap_threshold_ticks: u16,
+}
+
+/// Serde remote definition of AdaptiveFeeVariablesFacade
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AdaptiveFeeVariablesFacade")]
+pub struct AdaptiveFeeVariablesFacadeDef {
+    pub last_reference_update_timestamp: u64,
+    pub last_major_swap_timestamp: u64,
+    pub volatility_reference: u32,
+    pub tick_group_index_reference: i32,
+    pub volatility_accumulator: u32,
+}
+
+
+// Owned wrappers, so sequences and options of remote types can derive serde
+#[derive(Serialize, Deserialize)]
+struct RewardInfoWrapper(#[serde(with = "WhirlpoolRewardInfoFacadeDef")] WhirlpoolRewardInfoFacade);
+
+#[derive(Serialize, Deserialize)]
+struct TickWrapper(#[serde(with = "TickFacadeDef")] TickFacade);
+
+#[derive(Serialize, Deserialize)]
+struct TickArrayWrapper(#[serde(with = "TickArrayFacadeDef")] TickArrayFacade);
+
+#[derive(Serialize, Deserialize)]
+struct OracleWrapper(#[serde(with = "OracleFacadeDef")] OracleFacade);
+
+
+/// Collect a deserialised sequence into a fixed size array
+///
+/// Parameters:
+///     - items: the deserialised items
+///     - expected: a description of the expected length, used for error reporting
+///
+/// Returns:
+///     - The array or a length mismatch error
+fn into_array<T, const N: usize, E: Error>(items: Vec<T>, expected: &'static str) -> Result<[T; N], E> {
+    let len: usize = items.len();
+    items.try_into().map_err(|_| E::invalid_length(len, &expected))
+}
+
+/// #[serde(with)] module for the WhirlpoolFacade reward infos
+mod reward_infos {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        reward_infos: &[WhirlpoolRewardInfoFacade; NUM_REWARDS], serializer: S
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(reward_infos.iter().map(|reward_info| RewardInfoWrapper(*reward_info)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<[WhirlpoolRewardInfoFacade; NUM_REWARDS], D::Error> {
+        let wrappers: Vec<RewardInfoWrapper> = Vec::deserialize(deserializer)?;
+        into_array(wrappers.into_iter().map(|wrapper| wrapper.0).collect(), "3 reward infos")
+    }
+}
+
+/// #[serde(with)] module for the TickArrayFacade ticks
+mod ticks {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        ticks: &[TickFacade; TICK_ARRAY_SIZE], serializer: S
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(ticks.iter().map(|tick| TickWrapper(*tick)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<[TickFacade; TICK_ARRAY_SIZE], D::Error> {
+        let wrappers: Vec<TickWrapper> = Vec::deserialize(deserializer)?;
+        into_array(wrappers.into_iter().map(|wrapper| wrapper.0).collect(), "88 ticks")
+    }
+}
+
+/// #[serde(with)] module for TickArrays, written as a sequence of one to six tick arrays
+pub mod tick_arrays {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(tick_arrays: &TickArrays, serializer: S) -> Result<S::Ok, S::Error> {
+        // Collected first, bincode needs the sequence length up front
+        let facades: [Option<TickArrayFacade>; 6] = tick_arrays.clone().into();
+        let wrappers: Vec<TickArrayWrapper> = facades.into_iter().flatten().map(TickArrayWrapper).collect();
+        wrappers.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TickArrays, D::Error> {
+        let wrappers: Vec<TickArrayWrapper> = Vec::deserialize(deserializer)?;
+        let facades: Vec<TickArrayFacade> = wrappers.into_iter().map(|wrapper| wrapper.0).collect();
+        tick_arrays_from_facades(&facades).map_err(D::Error::custom)
+    }
+}
+
+/// #[serde(with)] module for Option<OracleFacade>
+pub mod option_oracle {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(oracle: &Option<OracleFacade>, serializer: S) -> Result<S::Ok, S::Error> {
+        oracle.map(OracleWrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<OracleFacade>, D::Error> {
+        let wrapper: Option<OracleWrapper> = Option::deserialize(deserializer)?;
+        Ok(wrapper.map(|wrapper| wrapper.0))
+    }
+}
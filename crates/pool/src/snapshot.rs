@@ -0,0 +1,98 @@
+// PoolState snapshot files ///
+
+// Snapshots hold the full PoolState of a set of pools, so quotes can be replayed
+// deterministically without an RPC connection
+
+use std::fs;
+use std::path::Path;
+
+use serde::{
+    Deserialize, Serialize
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::structs::PoolState;
+
+use error::error::OrcaQuoterError;
+
+
+/// Version written into every snapshot, bumped whenever the PoolState layout changes
+pub const SNAPSHOT_VERSION: u32 = 5;
+
+/// On disk encoding of a snapshot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Human readable, pretty printed JSON
+    Json,
+    /// Compact bincode encoding
+    Bincode,
+}
+
+/// Contents of a snapshot file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoolSnapshot {
+    pub version: u32,
+    pub pool_states: Vec<(Pubkey, PoolState)>,
+}
+
+
+/// Write the given pool states to a snapshot file, overwriting any existing file
+///
+/// Parameters:
+///     - path: a pointer to the path of the snapshot file
+///     - pool_states: a pointer to a slice of (pool pubkey, PoolState) tuples
+///     - format: the SnapshotFormat to encode the snapshot with
+///
+/// Returns:
+///     - () or an OrcaQuoterError if encoding or writing the file failed
+pub fn write_snapshot(
+    path: &Path, pool_states: &[(Pubkey, PoolState)], format: SnapshotFormat
+) -> Result<(), OrcaQuoterError> {
+    let snapshot: PoolSnapshot = PoolSnapshot {
+        version: SNAPSHOT_VERSION,
+        pool_states: pool_states.to_vec(),
+    };
+    let bytes: Vec<u8> = match format {
+        SnapshotFormat::Json => serde_json::to_vec_pretty(&snapshot).map_err(|err| OrcaQuoterError::Serialisation(
+            format!("Failed to encode snapshot {} as JSON: {}", path.display(), err)
+        ))?,
+        SnapshotFormat::Bincode => bincode::serialize(&snapshot).map_err(|err| OrcaQuoterError::Serialisation(
+            format!("Failed to encode snapshot {} as bincode: {}", path.display(), err)
+        ))?,
+    };
+    fs::write(path, bytes).map_err(|err| OrcaQuoterError::Io(
+        format!("Failed to write snapshot {}: {}", path.display(), err)
+    ))
+}
+
+
+/// Read the pool states back from a snapshot file
+///     - Each PoolState is decoded on the stack, unoptimised builds need a few MiB of it, more than the
+///       2 MiB of a spawned or test thread
+///
+/// Parameters:
+///     - path: a pointer to the path of the snapshot file
+///     - format: the SnapshotFormat the snapshot was written with
+///
+/// Returns:
+///     - A vector of (pool pubkey, PoolState) tuples, in the order they were written, or an OrcaQuoterError
+///       if the file could not be read, decoded or was written with another SNAPSHOT_VERSION
+pub fn read_snapshot(path: &Path, format: SnapshotFormat) -> Result<Vec<(Pubkey, PoolState)>, OrcaQuoterError> {
+    let bytes: Vec<u8> = fs::read(path).map_err(|err| OrcaQuoterError::Io(
+        format!("Failed to read snapshot {}: {}", path.display(), err)
+    ))?;
+    let snapshot: PoolSnapshot = match format {
+        SnapshotFormat::Json => serde_json::from_slice(&bytes).map_err(|err| OrcaQuoterError::Deserialisation(
+            format!("Failed to parse JSON snapshot {}: {}", path.display(), err)
+        ))?,
+        SnapshotFormat::Bincode => bincode::deserialize(&bytes).map_err(|err| OrcaQuoterError::Deserialisation(
+            format!("Failed to parse bincode snapshot {}: {}", path.display(), err)
+        ))?,
+    };
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(OrcaQuoterError::Deserialisation(format!(
+            "Snapshot {} has version {}, expected {}", path.display(), snapshot.version, SNAPSHOT_VERSION
+        )));
+    }
+    Ok(snapshot.pool_states)
+}
@@ -2,8 +2,16 @@ use std::fmt::{self, Display};
 use std::collections::{
     HashMap
 };
-use std::time::{
-    UNIX_EPOCH, Duration
+use std::time::Duration;
+
+use serde::{
+    Deserialize, Serialize
+};
+use chrono::{
+    DateTime, SecondsFormat, Utc
+};
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig, hash::{Hash, hash}
 };
 
 use orca_whirlpools_core::{
@@ -11,23 +19,362 @@ use orca_whirlpools_core::{
 };
 
 use mint::{
-   structs::MintData
+   structs::{MintData, TokenMetadataOverrides}
+};
+use error::{
+    error::OrcaQuoterError, clock::current_unix_timestamp_ms
+};
+
+use crate::serde_facades::{
+    self, WhirlpoolFacadeDef
 };
 
+/// Full on-chain state needed to quote a pool, serialisable so quotes can be replayed offline
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PoolState {
+    #[serde(with = "WhirlpoolFacadeDef")]
     pub whirlpool: WhirlpoolFacade, 
+    #[serde(with = "serde_facades::tick_arrays")]
     pub current_tick_array: TickArrays,
+    #[serde(with = "serde_facades::option_oracle")]
     pub oracle: Option<OracleFacade>,
     pub mint_a_data: MintData,
     pub mint_b_data: MintData,
-    pub timestamps: HashMap<&'static str, u64>
+    pub metadata: FetchMetadata,
+}
+
+/// The PoolState components FetchMetadata records fetches for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PoolComponent {
+    Whirlpool,
+    TickArrays,
+    Oracle,
+    MintA,
+    MintB,
+}
+
+impl Display for PoolComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label: &str = match self {
+            PoolComponent::Whirlpool => "whirlpool",
+            PoolComponent::TickArrays => "tick_arrays",
+            PoolComponent::Oracle => "oracle",
+            PoolComponent::MintA => "mint_a",
+            PoolComponent::MintB => "mint_b",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// When, where from and at which slot a single account was fetched
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentFetch {
+    // Milliseconds since UNIX_EPOCH
+    pub fetched_at_ms: u64,
+    pub slot: u64,
+    pub endpoint: String,
+    // SHA-256 of the account data
+    pub data_hash: Hash,
+}
+
+impl ComponentFetch {
+    /// Record the fetch of an account
+    /// 
+    /// Parameters:
+    ///     - account: a pointer to the fetched account
+    ///     - slot: the slot the account was read at
+    ///     - fetched_at_ms: the fetch time in milliseconds since UNIX_EPOCH
+    ///     - endpoint: the label of the endpoint the account was read from
+    /// 
+    /// Returns:
+    ///     - The ComponentFetch
+    pub fn from_account(account: &Account, slot: u64, fetched_at_ms: u64, endpoint: &str) -> ComponentFetch {
+        ComponentFetch {
+            fetched_at_ms,
+            slot,
+            endpoint: endpoint.to_string(),
+            data_hash: hash(&account.data),
+        }
+    }
+
+    /// Record the fetch of an account that does not exist, e.g. an uninitialised tick array
+    ///     - The data hash is left as the all zero Hash, marking the account as missing
+    /// 
+    /// Parameters:
+    ///     - slot: the slot the account was found missing at
+    ///     - fetched_at_ms: the fetch time in milliseconds since UNIX_EPOCH
+    ///     - endpoint: the label of the endpoint the account was read from
+    /// 
+    /// Returns:
+    ///     - The ComponentFetch
+    pub fn from_missing_account(slot: u64, fetched_at_ms: u64, endpoint: &str) -> ComponentFetch {
+        ComponentFetch {
+            fetched_at_ms,
+            slot,
+            endpoint: endpoint.to_string(),
+            data_hash: Hash::default(),
+        }
+    }
+
+    /// Whether the fetched account did not exist, see from_missing_account
+    /// 
+    /// Returns:
+    ///     - true if the data hash is the all zero Hash
+    pub fn is_missing_account(&self) -> bool {
+        self.data_hash == Hash::default()
+    }
+
+    /// Time elapsed since the fetch, zero if the fetch time lies in the future
+    /// 
+    /// Returns:
+    ///     - The age as a Duration
+    pub fn age(&self) -> Duration {
+        self.age_at(current_unix_timestamp_ms().unwrap_or(0))
+    }
+
+    /// Time elapsed between the fetch and now_ms, zero if the fetch time lies after now_ms
+    /// 
+    /// Parameters:
+    ///     - now_ms: the reference time in milliseconds since UNIX_EPOCH
+    /// 
+    /// Returns:
+    ///     - The age as a Duration
+    pub fn age_at(&self, now_ms: u64) -> Duration {
+        Duration::from_millis(now_ms.saturating_sub(self.fetched_at_ms))
+    }
+}
+
+impl Display for ComponentFetch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // RFC 3339 in UTC with millisecond precision, e.g. 2025-06-01T12:00:00.000Z
+        let fetched_at: String = DateTime::<Utc>::from_timestamp_millis(self.fetched_at_ms as i64)
+            .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Millis, true))
+            .unwrap_or_else(|| format!("{}ms", self.fetched_at_ms));
+        write!(
+            f, "fetched {} (age {:.3}s) at slot {} from {}, data hash {}",
+            fetched_at, self.age().as_secs_f64(), self.slot, self.endpoint, self.data_hash
+        )
+    }
+}
+
+/// Number of tick arrays held either side of the tick array containing the current tick
+///     - a to b swaps lower the price and walk left, b to a swaps walk right
+///     - Each tick array spans 88 * tick_spacing ticks, so tight spacing pools need wider windows 
+///       to quote size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickArrayWindow {
+    pub left: u8,
+    pub right: u8,
+}
+
+impl TickArrayWindow {
+    /// The most tick arrays a window can hold, TickArrays::Six
+    pub const MAX_TICK_ARRAYS: usize = 6;
+
+    /// Window of left and right tick arrays around the current tick array
+    /// 
+    /// Parameters:
+    ///     - left: the number of tick arrays below the current tick array
+    ///     - right: the number of tick arrays above the current tick array
+    /// 
+    /// Returns:
+    ///     - The TickArrayWindow or OrcaQuoterError::InvalidArgument if it holds more than MAX_TICK_ARRAYS
+    pub fn new(left: u8, right: u8) -> Result<TickArrayWindow, OrcaQuoterError> {
+        let window: TickArrayWindow = TickArrayWindow { left, right };
+        if window.tick_array_count() > TickArrayWindow::MAX_TICK_ARRAYS {
+            return Err(OrcaQuoterError::InvalidArgument(format!(
+                "Tick array window of {} left and {} right holds {} tick arrays, at most {} are supported",
+                left, right, window.tick_array_count(), TickArrayWindow::MAX_TICK_ARRAYS
+            )));
+        }
+        Ok(window)
+    }
+
+    /// Window with the same number of tick arrays on both sides, e.g. for two sided quotes
+    /// 
+    /// Parameters:
+    ///     - arrays_per_side: the number of tick arrays either side of the current tick array
+    /// 
+    /// Returns:
+    ///     - The TickArrayWindow or an OrcaQuoterError if it holds more than MAX_TICK_ARRAYS
+    pub fn symmetric(arrays_per_side: u8) -> Result<TickArrayWindow, OrcaQuoterError> {
+        TickArrayWindow::new(arrays_per_side, arrays_per_side)
+    }
+
+    /// Window extending only in the swap direction, e.g. for one sided quotes of size
+    /// 
+    /// Parameters:
+    ///     - a_to_b: the swap direction, true walks left towards lower ticks
+    ///     - arrays_in_direction: the number of tick arrays past the current tick array
+    /// 
+    /// Returns:
+    ///     - The TickArrayWindow or an OrcaQuoterError if it holds more than MAX_TICK_ARRAYS
+    pub fn directional(a_to_b: bool, arrays_in_direction: u8) -> Result<TickArrayWindow, OrcaQuoterError> {
+        match a_to_b {
+            true => TickArrayWindow::new(arrays_in_direction, 0),
+            false => TickArrayWindow::new(0, arrays_in_direction),
+        }
+    }
+
+    /// Number of tick arrays in the window, including the current tick array
+    /// 
+    /// Returns:
+    ///     - left + 1 + right
+    pub fn tick_array_count(&self) -> usize {
+        self.left as usize + 1 + self.right as usize
+    }
+}
+
+/// The (left, current, right) triple the loaders fetched before windows were configurable
+impl Default for TickArrayWindow {
+    fn default() -> TickArrayWindow {
+        TickArrayWindow { left: 1, right: 1 }
+    }
+}
+
+/// Fetch metadata of every PoolState component, so stale components and components fetched 
+/// from different chain states can be detected
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchMetadata {
+    pub commitment: CommitmentConfig,
+    // The window the tick arrays were fetched with, kept so refreshes re-derive the same window
+    pub tick_array_window: TickArrayWindow,
+    pub whirlpool: ComponentFetch,
+    // One entry per held tick array, in TickArrays order
+    pub tick_arrays: Vec<ComponentFetch>,
+    // None if the pool has no oracle
+    pub oracle: Option<ComponentFetch>,
+    // None for unfetched MintData placeholders
+    pub mint_a: Option<ComponentFetch>,
+    pub mint_b: Option<ComponentFetch>,
+}
+
+impl FetchMetadata {
+    /// The fetches recorded for a component, tick arrays may have several
+    /// 
+    /// Parameters:
+    ///     - component: the PoolComponent
+    /// 
+    /// Returns:
+    ///     - A vector of pointers to the component's ComponentFetch structs, empty if never fetched
+    pub fn fetches_of(&self, component: PoolComponent) -> Vec<&ComponentFetch> {
+        match component {
+            PoolComponent::Whirlpool => vec![&self.whirlpool],
+            PoolComponent::TickArrays => self.tick_arrays.iter().collect(),
+            PoolComponent::Oracle => self.oracle.iter().collect(),
+            PoolComponent::MintA => self.mint_a.iter().collect(),
+            PoolComponent::MintB => self.mint_b.iter().collect(),
+        }
+    }
+
+    /// Age of a component, for tick arrays the age of the oldest one
+    /// 
+    /// Parameters:
+    ///     - component: the PoolComponent
+    /// 
+    /// Returns:
+    ///     - Some(age), None if the component was never fetched
+    pub fn age_of(&self, component: PoolComponent) -> Option<Duration> {
+        self.age_of_at(component, current_unix_timestamp_ms().unwrap_or(0))
+    }
+
+    /// Age of a component at now_ms, see age_of
+    /// 
+    /// Parameters:
+    ///     - component: the PoolComponent
+    ///     - now_ms: the reference time in milliseconds since UNIX_EPOCH
+    /// 
+    /// Returns:
+    ///     - Some(age), None if the component was never fetched
+    pub fn age_of_at(&self, component: PoolComponent, now_ms: u64) -> Option<Duration> {
+        self.fetches_of(component).iter().map(|fetch| fetch.age_at(now_ms)).max()
+    }
+
+    /// The component fetched longest ago
+    /// 
+    /// Returns:
+    ///     - The (PoolComponent, age) pair of the stalest component
+    pub fn oldest(&self) -> Option<(PoolComponent, Duration)> {
+        self.oldest_at(current_unix_timestamp_ms().unwrap_or(0))
+    }
+
+    /// The component fetched longest ago, with its age at now_ms
+    /// 
+    /// Parameters:
+    ///     - now_ms: the reference time in milliseconds since UNIX_EPOCH
+    /// 
+    /// Returns:
+    ///     - The (PoolComponent, age) pair of the stalest component
+    pub fn oldest_at(&self, now_ms: u64) -> Option<(PoolComponent, Duration)> {
+        [
+            PoolComponent::Whirlpool, PoolComponent::TickArrays, PoolComponent::Oracle, 
+            PoolComponent::MintA, PoolComponent::MintB
+        ].into_iter()
+            .filter_map(|component| self.age_of_at(component, now_ms).map(|age| (component, age)))
+            .max_by_key(|(_, age)| *age)
+    }
+
+    /// Largest difference between the slots the whirlpool, tick arrays and oracle were read at
+    ///     - Mints are left out, they do not feed the swap math
+    /// 
+    /// Returns:
+    ///     - The slot skew, 0 if every quote relevant component was read at the same slot
+    pub fn slot_skew(&self) -> u64 {
+        let slots = std::iter::once(&self.whirlpool)
+            .chain(self.tick_arrays.iter())
+            .chain(self.oracle.iter())
+            .map(|fetch| fetch.slot);
+        let (min_slot, max_slot): (u64, u64) = slots.fold(
+            (u64::MAX, u64::MIN), |(min_slot, max_slot), slot| (min_slot.min(slot), max_slot.max(slot))
+        );
+        max_slot - min_slot
+    }
+}
+
+impl PoolState {
+    /// Whether the whirlpool, tick arrays and oracle were all read at the same slot
+    /// 
+    /// Returns:
+    ///     - true if the slot skew is 0
+    pub fn is_consistent(&self) -> bool {
+        self.metadata.slot_skew() == 0
+    }
+
+    /// Whether the whirlpool, tick arrays and oracle were read within max_slot_skew slots of 
+    /// each other, e.g. to reject quotes built from a whirlpool and tick array of different slots
+    /// 
+    /// Parameters:
+    ///     - max_slot_skew: the largest tolerated slot difference
+    /// 
+    /// Returns:
+    ///     - true if the slot skew is at most max_slot_skew
+    pub fn is_within_slot_skew(&self, max_slot_skew: u64) -> bool {
+        self.metadata.slot_skew() <= max_slot_skew
+    }
+
+    /// Short human readable label of the pool, e.g. "SOL/USDC ts=4"
+    /// 
+    /// Returns:
+    ///     - The mint A and mint B labels, see MintData::label, and the tick spacing
+    pub fn pair_label(&self) -> String {
+        format!("{}/{} ts={}", self.mint_a_data.label(), self.mint_b_data.label(), self.whirlpool.tick_spacing)
+    }
+
+    /// Overwrite the symbols and names of both mints with any local overrides
+    /// 
+    /// Parameters:
+    ///     - overrides: a pointer to the TokenMetadataOverrides, e.g. read via TokenMetadataOverrides::from_file
+    pub fn apply_token_metadata_overrides(&mut self, overrides: &TokenMetadataOverrides) {
+        overrides.apply(&mut self.mint_a_data);
+        overrides.apply(&mut self.mint_b_data);
+    }
 }
 
 /// Pretty printing implementation for PoolState
 /// Prints as follows if we just run a "println!("{}", pool_state);"
 impl Display for PoolState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "=== PoolState ===")?;
+        writeln!(f, "=== PoolState {} ===", self.pair_label())?;
 
         // Print full Whirlpool struct via Debug
         writeln!(f, "\nWhirlpool:\n{:#?}", self.whirlpool)?;
@@ -41,7 +388,7 @@ impl Display for PoolState {
             "\nOracle: {}",
             match &self.oracle {
                 Some(_) => "NEED TO DEAL W/ ORACLE, CANNY BE ARSED",
-                None => "oracle not found".into(),
+                None => "oracle not found",
             }
         )?;
 
@@ -53,17 +400,73 @@ impl Display for PoolState {
         writeln!(f, "\nMint B Data:")?;
         writeln!(f, "{}", self.mint_b_data)?;
 
-        // 6) Timestamps converted to ISO‐style datetime
-        writeln!(f, "\nTimestamps:")?;
-        for (label, ts) in &self.timestamps {
-            // Interpret the u64 as seconds since UNIX_EPOCH:
-            let time = UNIX_EPOCH
-                .checked_add(Duration::from_secs(*ts))
-                .unwrap_or(UNIX_EPOCH);
-            // Debug‐print it; you’ll get something like:
-            //   SystemTime { tv_sec: 1_629_163_200, tv_nsec: 0 }
-            writeln!(f, "  {}: {:?}", label, time)?;
+        // 6) Fetch metadata, one line per fetched account
+        writeln!(f, "\nFetch Metadata ({:?}):", self.metadata.commitment.commitment)?;
+        writeln!(f, "  {}: {}", PoolComponent::Whirlpool, self.metadata.whirlpool)?;
+        writeln!(
+            f, "  tick array window: {} left, {} right", 
+            self.metadata.tick_array_window.left, self.metadata.tick_array_window.right
+        )?;
+        for tick_array_fetch in self.metadata.tick_arrays.iter() {
+            writeln!(f, "  {}: {}", PoolComponent::TickArrays, tick_array_fetch)?;
+        }
+        for (component, fetch) in [
+            (PoolComponent::Oracle, &self.metadata.oracle), 
+            (PoolComponent::MintA, &self.metadata.mint_a), 
+            (PoolComponent::MintB, &self.metadata.mint_b)
+        ] {
+            match fetch {
+                Some(fetch) => writeln!(f, "  {}: {}", component, fetch)?,
+                None => writeln!(f, "  {}: not fetched", component)?,
+            }
         }
+        writeln!(f, "  slot skew: {}", self.metadata.slot_skew())?;
         Ok(())
     }
 }
+
+
+
+/// The kind of account a websocket subscription tracks for its pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TrackedAccount {
+    Whirlpool,
+    TickArray,
+    Oracle,
+}
+
+/// Every account subscribed to by update_pool_states_via_websocket, keyed by account pubkey
+///     - Each value is the (pool pubkey, TrackedAccount) the account's updates are applied to
+#[derive(Clone, Debug, Default)]
+pub struct PoolSubscriptions {
+    pub accounts: HashMap<Pubkey, (Pubkey, TrackedAccount)>,
+}
+
+/// A failure update_pool_states_via_websocket skips past without stopping, passed to its on_error callback
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListenerError {
+    // A notification that could not be decoded or applied to its PoolState, it is dropped
+    Notification { account: Pubkey, error: OrcaQuoterError },
+    // A window refresh that could not fetch the pool's new tick arrays, the old ones are kept
+    TickArrayRefresh { pool: Pubkey, error: OrcaQuoterError },
+    // A subscription that failed, it is retried before the next notification is awaited
+    Subscription { account: Pubkey, error: OrcaQuoterError },
+    // The websocket client failed to shut down cleanly once the listener stopped
+    Shutdown { error: OrcaQuoterError },
+}
+
+impl Display for ListenerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenerError::Notification { account, error } => write!(
+                f, "Failed to apply websocket notification for account {}: {}", account, error
+            ),
+            ListenerError::TickArrayRefresh { pool, error } => write!(
+                f, "Failed to refresh tick arrays for pool {}: {}", pool, error
+            ),
+            // The error already names the account
+            ListenerError::Subscription { error, .. } => write!(f, "{}, retrying before the next notification", error),
+            ListenerError::Shutdown { error } => write!(f, "Websocket closed with error: {}", error),
+        }
+    }
+}
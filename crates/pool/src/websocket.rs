@@ -0,0 +1,453 @@
+// Live PoolState updates via account websocket subscriptions ///
+
+use std::collections::{
+    HashMap, HashSet
+};
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig,
+    rpc_response::Response
+};
+use solana_account_decoder::{
+    UiAccount, UiAccountEncoding
+};
+
+use futures::{
+    future::BoxFuture, stream::{BoxStream, SelectAll}, StreamExt
+};
+
+use orca_whirlpools_client::{
+    Whirlpool, TickArray, Oracle
+};
+use orca_whirlpools_core::{
+    WhirlpoolFacade, TickArrayFacade, OracleFacade
+};
+
+use crate::pda::{
+    get_tick_array_address, get_oracle_address
+};
+use crate::pool_state::{
+    tick_array_start_indexes, verify_whirlpool_program_owner,
+    tick_arrays_from_facades, empty_tick_array_facade
+};
+use crate::structs::{
+    PoolState, PoolSubscriptions, TrackedAccount, ComponentFetch, ListenerError
+};
+
+use error::{
+    error::OrcaQuoterError, clock::current_unix_timestamp_ms
+};
+use provider::{
+    nonblocking::AsyncAccountProvider, structs::AtSlot
+};
+
+
+// Mirrors solana_pubsub_client's private UnsubscribeFn
+type UnsubscribeFn = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+type AccountNotifications<'a> = SelectAll<BoxStream<'a, (Pubkey, Response<UiAccount>)>>;
+
+
+/// Keep PoolStates up to date from account websocket notifications
+///     - Subscribes (accountSubscribe) to each pool's whirlpool, tick arrays and oracle
+///     - Notifications are decoded and applied to the matching PoolState in place
+///     - When tick_current_index leaves the tick array window, the window is re-derived,
+///       fetched via the account provider and its tick arrays re-subscribed
+///     - Bad notifications, failed window refreshes and failed re-subscriptions are passed to 
+///       on_error and never stop the listener, a failed re-subscription is retried before the next 
+///       notification is awaited
+///
+/// Parameters:
+///     - ws_url: the websocket endpoint, e.g. wss://api.mainnet-beta.solana.com or a local mock server
+///     - provider: a pointer to an async account provider, used to fetch newly entered tick arrays
+///     - pool_states: the PoolStates to keep updated, keyed by pool pubkey
+///     - commitment: the commitment level notifications are sent at
+///     - max_updates: the number of notifications to apply before returning,
+///       None listens until the websocket closes
+///     - on_error: called with every ListenerError skipped past, e.g. to log it
+///
+/// Returns:
+///     - The number of notifications applied or an OrcaQuoterError
+pub async fn update_pool_states_via_websocket<P: AsyncAccountProvider + ?Sized>(
+    ws_url: &str, provider: &P, pool_states: &mut HashMap<Pubkey, PoolState>,
+    commitment: CommitmentConfig, max_updates: Option<usize>, mut on_error: impl FnMut(ListenerError)
+) -> Result<usize, OrcaQuoterError> {
+
+    // Phase 1: Connect to the websocket endpoint
+    let pubsub_client: PubsubClient = PubsubClient::new(ws_url).await.map_err(|err| {
+        OrcaQuoterError::Rpc(format!("Failed to connect to websocket {}: {}", ws_url, err))
+    })?;
+
+    // Phase 2: Derive every tracked account from the PoolStates
+    let mut subscriptions: PoolSubscriptions = subscriptions_from_pool_states(pool_states)?;
+
+    // Phase 3: Subscribe to every tracked account
+        // Each notification stream is tagged with its account pubkey and merged into one stream
+    let config: RpcAccountInfoConfig = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        ..RpcAccountInfoConfig::default()
+    };
+    let mut notifications: AccountNotifications = SelectAll::new();
+    let mut unsubscribe_fns: HashMap<Pubkey, UnsubscribeFn> = HashMap::new();
+    let account_pubkeys: Vec<Pubkey> = subscriptions.accounts.keys().copied().collect();
+    let failed_subscriptions: Vec<(Pubkey, OrcaQuoterError)> = subscribe_to_accounts(
+        &pubsub_client, &config, &account_pubkeys, &mut notifications, &mut unsubscribe_fns
+    ).await;
+        // Error Catch, the listener never starts with accounts it cannot follow
+    if let Some((_, err)) = failed_subscriptions.into_iter().next() {
+        return Err(err);
+    }
+
+    // Phase 4: Apply notifications as they arrive
+        // A bad notification is passed to on_error and skipped, it never stops the listener
+    let mut updates_applied: usize = 0;
+    let mut pending_subscriptions: Vec<Pubkey> = Vec::new();
+    loop {
+        if max_updates.is_some_and(|max_updates| updates_applied >= max_updates) {
+            break;
+        }
+
+        // Retry the re-subscriptions that failed after a window refresh, skipping tick arrays that
+        // have since left the window
+        if !pending_subscriptions.is_empty() {
+            let retry_pubkeys: Vec<Pubkey> = pending_subscriptions.drain(..)
+                .filter(|pubkey| subscriptions.accounts.contains_key(pubkey))
+                .collect();
+            pending_subscriptions = report_failed_subscriptions(subscribe_to_accounts(
+                &pubsub_client, &config, &retry_pubkeys, &mut notifications, &mut unsubscribe_fns
+            ).await, &mut on_error);
+        }
+
+        // The merged stream ends once the websocket closes
+        let Some((account_pubkey, response)) = notifications.next().await else { break };
+
+        // Stale notifications from tick arrays that have left the window are dropped
+        if !subscriptions.accounts.contains_key(&account_pubkey) {
+            continue;
+        }
+        let account: Account = match response.value.decode::<Account>() {
+            Some(account) => account,
+            None => {
+                on_error(ListenerError::Notification {
+                    account: account_pubkey,
+                    error: OrcaQuoterError::Deserialisation(format!(
+                        "Failed to decode websocket notification for account {}", account_pubkey
+                    )),
+                });
+                continue;
+            }
+        };
+
+        // Phase 4.1: Apply the notification to its PoolState, at the slot of the notification
+        let pool_to_refresh: Option<Pubkey> = match apply_account_update(
+            pool_states, &subscriptions, &account_pubkey, &account, response.context.slot, ws_url
+        ) {
+            Ok(pool_to_refresh) => pool_to_refresh,
+            Err(error) => {
+                on_error(ListenerError::Notification { account: account_pubkey, error });
+                continue;
+            }
+        };
+        updates_applied += 1;
+
+        // Phase 4.2: Re-derive, fetch and re-subscribe tick arrays if the window was left
+        if let Some(pool_pubkey) = pool_to_refresh {
+            let Some(pool_state) = pool_states.get_mut(&pool_pubkey) else { continue };
+            let tick_array_pubkeys: Vec<Pubkey> = match refresh_tick_arrays(provider, &pool_pubkey, pool_state).await {
+                Ok(pubkeys) => pubkeys,
+                Err(error) => {
+                    on_error(ListenerError::TickArrayRefresh { pool: pool_pubkey, error });
+                    continue;
+                }
+            };
+            let (added, removed): (Vec<Pubkey>, Vec<Pubkey>) = replace_tick_array_subscriptions(
+                &mut subscriptions, &pool_pubkey, &tick_array_pubkeys
+            );
+            for pubkey in removed.iter() {
+                if let Some(unsubscribe) = unsubscribe_fns.remove(pubkey) {
+                    unsubscribe().await;
+                }
+            }
+            pending_subscriptions.extend(report_failed_subscriptions(subscribe_to_accounts(
+                &pubsub_client, &config, &added, &mut notifications, &mut unsubscribe_fns
+            ).await, &mut on_error));
+        }
+    }
+
+    // Phase 5: Unsubscribe and shut the client down
+    drop(notifications);
+    for (_, unsubscribe) in unsubscribe_fns.into_iter() {
+        unsubscribe().await;
+    }
+    if let Err(err) = pubsub_client.shutdown().await {
+        on_error(ListenerError::Shutdown { error: OrcaQuoterError::Rpc(err.to_string()) });
+    }
+
+    Ok(updates_applied)
+}
+
+
+/// Derive the whirlpool, tick array and oracle accounts tracked for every PoolState
+///
+/// Parameters:
+///     - pool_states: a pointer to the PoolStates, keyed by pool pubkey
+///
+/// Returns:
+///     - The PoolSubscriptions or an OrcaQuoterError
+pub fn subscriptions_from_pool_states(
+    pool_states: &HashMap<Pubkey, PoolState>
+) -> Result<PoolSubscriptions, OrcaQuoterError> {
+    let mut subscriptions: PoolSubscriptions = PoolSubscriptions::default();
+    for (pool_pubkey, pool_state) in pool_states.iter() {
+        subscriptions.accounts.insert(*pool_pubkey, (*pool_pubkey, TrackedAccount::Whirlpool));
+
+        // Tick array pubkeys are derived from the start tick index of each held tick array
+        let tick_array_facades: [Option<TickArrayFacade>; 6] = pool_state.current_tick_array.clone().into();
+        for tick_array_facade in tick_array_facades.iter().flatten() {
+            let (tick_array_pubkey, _): (Pubkey, u8) = get_tick_array_address(
+                pool_pubkey, tick_array_facade.start_tick_index
+            )?;
+            subscriptions.accounts.insert(tick_array_pubkey, (*pool_pubkey, TrackedAccount::TickArray));
+        }
+
+        // The oracle is subscribed to even if it does not exist yet, it is created on first use
+        let (oracle_pubkey, _): (Pubkey, u8) = get_oracle_address(pool_pubkey)?;
+        subscriptions.accounts.insert(oracle_pubkey, (*pool_pubkey, TrackedAccount::Oracle));
+    }
+    Ok(subscriptions)
+}
+
+
+/// Apply a single account notification to the PoolState it belongs to
+///
+/// Parameters:
+///     - pool_states: the PoolStates, keyed by pool pubkey
+///     - subscriptions: a pointer to the PoolSubscriptions mapping accounts to pools
+///     - account_pubkey: a pointer to the notified account's pubkey
+///     - account: a pointer to the decoded account
+///     - slot: the slot the account was read at
+///     - endpoint: the label of the endpoint the notification came from
+///
+/// Returns:
+///     - Some(pool pubkey) if the pool's tick_current_index has left its tick array window
+///       and the tick arrays must be re-derived, None otherwise, or an OrcaQuoterError
+pub fn apply_account_update(
+    pool_states: &mut HashMap<Pubkey, PoolState>, subscriptions: &PoolSubscriptions,
+    account_pubkey: &Pubkey, account: &Account, slot: u64, endpoint: &str
+) -> Result<Option<Pubkey>, OrcaQuoterError> {
+    let (pool_pubkey, tracked_account): (Pubkey, TrackedAccount) = *subscriptions.accounts.get(account_pubkey)
+        .ok_or(OrcaQuoterError::AccountNotFound(*account_pubkey))?;
+    let pool_state: &mut PoolState = pool_states.get_mut(&pool_pubkey)
+        .ok_or(OrcaQuoterError::AccountNotFound(pool_pubkey))?;
+    verify_whirlpool_program_owner(account_pubkey, account)?;
+    let fetch: ComponentFetch = ComponentFetch::from_account(account, slot, current_unix_timestamp_ms()?, endpoint);
+
+    match tracked_account {
+        TrackedAccount::Whirlpool => {
+            let whirlpool: Whirlpool = Whirlpool::from_bytes(&account.data).map_err(|err| {
+                OrcaQuoterError::Deserialisation(format!("Failed to deserialize whirlpool account {}: {}", account_pubkey, err))
+            })?;
+            pool_state.whirlpool = WhirlpoolFacade::from(whirlpool);
+            pool_state.metadata.whirlpool = fetch;
+
+            // The window must be re-derived once the current tick crosses into another tick array, 
+            // so the held tick arrays stay centred on the current tick
+            let start_idxs: Vec<i32> = tick_array_start_indexes(
+                &pool_state.whirlpool, pool_state.metadata.tick_array_window
+            );
+            let tick_array_facades: [Option<TickArrayFacade>; 6] = pool_state.current_tick_array.clone().into();
+            let held_start_idxs: Vec<i32> = tick_array_facades.iter().flatten()
+                .map(|facade| facade.start_tick_index)
+                .collect();
+            Ok((held_start_idxs != start_idxs).then_some(pool_pubkey))
+        }
+        TrackedAccount::TickArray => {
+            let tick_array_facade: TickArrayFacade = TickArray::from_bytes(&account.data)
+                .map(TickArrayFacade::from)
+                .map_err(|err| OrcaQuoterError::Deserialisation(format!(
+                    "Failed to deserialize tick array {}: {}", account_pubkey, err
+                )))?;
+
+            // Replace the held tick array with the same start tick index
+            let mut tick_array_facades: Vec<TickArrayFacade> = <[Option<TickArrayFacade>; 6]>::from(
+                pool_state.current_tick_array.clone()
+            ).into_iter().flatten().collect();
+            if let Some(held_idx) = tick_array_facades.iter()
+                .position(|facade| facade.start_tick_index == tick_array_facade.start_tick_index)
+            {
+                tick_array_facades[held_idx] = tick_array_facade;
+                pool_state.current_tick_array = tick_arrays_from_facades(&tick_array_facades)?;
+                if let Some(held_fetch) = pool_state.metadata.tick_arrays.get_mut(held_idx) {
+                    *held_fetch = fetch;
+                }
+            }
+            Ok(None)
+        }
+        TrackedAccount::Oracle => {
+            let oracle: Oracle = Oracle::from_bytes(&account.data).map_err(|err| {
+                OrcaQuoterError::Deserialisation(format!("Failed to deserialize oracle account {}: {}", account_pubkey, err))
+            })?;
+            pool_state.oracle = Some(OracleFacade::from(oracle));
+            pool_state.metadata.oracle = Some(fetch);
+            Ok(None)
+        }
+    }
+}
+
+
+/// Re-derive and fetch the tick array window around a pool's current tick
+///     - The window is the one recorded in the PoolState's FetchMetadata
+///
+/// Parameters:
+///     - provider: a pointer to an async account provider
+///     - pool_pubkey: a pointer to the pool's pubkey
+///     - pool_state: the pool's PoolState, its tick arrays are replaced on success
+///
+/// Returns:
+///     - The new tick array pubkeys, in ascending start tick index order, or an OrcaQuoterError
+pub async fn refresh_tick_arrays<P: AsyncAccountProvider + ?Sized>(
+    provider: &P, pool_pubkey: &Pubkey, pool_state: &mut PoolState
+) -> Result<Vec<Pubkey>, OrcaQuoterError> {
+
+    // Phase 1: Derive the new window's tick array pubkeys
+    let start_idxs: Vec<i32> = tick_array_start_indexes(&pool_state.whirlpool, pool_state.metadata.tick_array_window);
+    let mut tick_array_pubkeys: Vec<Pubkey> = Vec::new();
+    for start_idx in start_idxs.iter() {
+        tick_array_pubkeys.push(get_tick_array_address(pool_pubkey, *start_idx)?.0);
+    }
+
+    // Phase 2: Fetch and deserialise the tick arrays
+        // Uninitialised tick arrays are substituted with empty ones, they stay subscribed to and are 
+        // replaced once initialised on chain
+    let tick_array_accounts: AtSlot<Vec<Option<Account>>> = provider.get_multiple_accounts(&tick_array_pubkeys).await?;
+    let fetched_at_ms: u64 = current_unix_timestamp_ms()?;
+    let endpoint: String = provider.endpoint();
+    let mut tick_array_facades: Vec<TickArrayFacade> = Vec::new();
+    let mut tick_array_fetches: Vec<ComponentFetch> = Vec::new();
+    for ((tick_array_pubkey, start_idx), account) in tick_array_pubkeys.iter()
+        .zip(start_idxs)
+        .zip(tick_array_accounts.value) 
+    {
+        let account: Account = match account {
+            Some(account) => account,
+            None => {
+                tick_array_facades.push(empty_tick_array_facade(start_idx));
+                tick_array_fetches.push(ComponentFetch::from_missing_account(
+                    tick_array_accounts.slot, fetched_at_ms, &endpoint
+                ));
+                continue;
+            }
+        };
+        verify_whirlpool_program_owner(tick_array_pubkey, &account)?;
+        let tick_array_facade: TickArrayFacade = TickArray::from_bytes(&account.data)
+            .map(TickArrayFacade::from)
+            .map_err(|err| OrcaQuoterError::Deserialisation(format!(
+                "Failed to deserialize tick array {}: {}", tick_array_pubkey, err
+            )))?;
+        tick_array_facades.push(tick_array_facade);
+        tick_array_fetches.push(ComponentFetch::from_account(
+            &account, tick_array_accounts.slot, fetched_at_ms, &endpoint
+        ));
+    }
+
+    // Phase 3: Replace the PoolState's tick arrays
+    pool_state.current_tick_array = tick_arrays_from_facades(&tick_array_facades)?;
+    pool_state.metadata.tick_arrays = tick_array_fetches;
+
+    Ok(tick_array_pubkeys)
+}
+
+
+/// Replace a pool's tracked tick arrays in the PoolSubscriptions
+///
+/// Parameters:
+///     - subscriptions: the PoolSubscriptions to update
+///     - pool_pubkey: a pointer to the pool's pubkey
+///     - tick_array_pubkeys: a pointer to the pool's new tick array pubkeys
+///
+/// Returns:
+///     - The (added, removed) tick array pubkeys, to subscribe to and unsubscribe from
+pub fn replace_tick_array_subscriptions(
+    subscriptions: &mut PoolSubscriptions, pool_pubkey: &Pubkey, tick_array_pubkeys: &[Pubkey]
+) -> (Vec<Pubkey>, Vec<Pubkey>) {
+    let new_pubkeys: HashSet<Pubkey> = tick_array_pubkeys.iter().copied().collect();
+    let old_pubkeys: HashSet<Pubkey> = subscriptions.accounts.iter()
+        .filter(|(_, (pool, tracked_account))| {
+            pool == pool_pubkey && *tracked_account == TrackedAccount::TickArray
+        })
+        .map(|(pubkey, _)| *pubkey)
+        .collect();
+
+    let added: Vec<Pubkey> = new_pubkeys.difference(&old_pubkeys).copied().collect();
+    let removed: Vec<Pubkey> = old_pubkeys.difference(&new_pubkeys).copied().collect();
+    for pubkey in removed.iter() {
+        subscriptions.accounts.remove(pubkey);
+    }
+    for pubkey in added.iter() {
+        subscriptions.accounts.insert(*pubkey, (*pool_pubkey, TrackedAccount::TickArray));
+    }
+    (added, removed)
+}
+
+
+// Private Functions
+
+/// Subscribe to a set of accounts and merge their notifications into one stream
+///     - Every account is attempted, a failed subscription does not stop the ones after it
+///
+/// Parameters:
+///     - pubsub_client: a pointer to the connected PubsubClient
+///     - config: a pointer to the account subscription config
+///     - account_pubkeys: a pointer to the pubkeys to subscribe to
+///     - notifications: the merged notification stream, each item tagged with its account pubkey
+///     - unsubscribe_fns: the unsubscribe functions, keyed by account pubkey
+///
+/// Returns:
+///     - The (account pubkey, OrcaQuoterError::Rpc) of every failed subscription, empty if all succeeded
+async fn subscribe_to_accounts<'a>(
+    pubsub_client: &'a PubsubClient, config: &RpcAccountInfoConfig, account_pubkeys: &[Pubkey],
+    notifications: &mut AccountNotifications<'a>, unsubscribe_fns: &mut HashMap<Pubkey, UnsubscribeFn>
+) -> Vec<(Pubkey, OrcaQuoterError)> {
+    let mut failed_subscriptions: Vec<(Pubkey, OrcaQuoterError)> = Vec::new();
+    for account_pubkey in account_pubkeys.iter() {
+        if unsubscribe_fns.contains_key(account_pubkey) {
+            continue;
+        }
+        let (stream, unsubscribe) = match pubsub_client.account_subscribe(account_pubkey, Some(config.clone())).await {
+            Ok(subscription) => subscription,
+            Err(err) => {
+                failed_subscriptions.push((*account_pubkey, OrcaQuoterError::Rpc(format!(
+                    "Failed to subscribe to account {}: {}", account_pubkey, err
+                ))));
+                continue;
+            }
+        };
+        let account_pubkey: Pubkey = *account_pubkey;
+        notifications.push(stream.map(move |response| (account_pubkey, response)).boxed());
+        unsubscribe_fns.insert(account_pubkey, unsubscribe);
+    }
+    failed_subscriptions
+}
+
+
+/// Pass failed subscriptions to on_error so they can be retried
+///
+/// Parameters:
+///     - failed_subscriptions: the (account pubkey, OrcaQuoterError) of every failed subscription
+///     - on_error: the listener's error callback
+///
+/// Returns:
+///     - The pubkeys of the failed subscriptions
+fn report_failed_subscriptions(
+    failed_subscriptions: Vec<(Pubkey, OrcaQuoterError)>, on_error: &mut impl FnMut(ListenerError)
+) -> Vec<Pubkey> {
+    failed_subscriptions.into_iter()
+        .map(|(account_pubkey, error)| {
+            on_error(ListenerError::Subscription { account: account_pubkey, error });
+            account_pubkey
+        })
+        .collect()
+}
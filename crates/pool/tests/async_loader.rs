@@ -0,0 +1,78 @@
+// populate_pool_states_async must stay Send so a tokio service can spawn it onto a multi
+// threaded runtime
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states_async, structs::{PoolState, TickArrayWindow}
+};
+use provider::{
+    provider::AccountProvider, nonblocking::AsyncAccountProvider, structs::{AtSlot, InMemoryAccountProvider}
+};
+
+use common::TestPool;
+
+/// Natively async in-memory provider that yields to the runtime before every answer, so each 
+/// request is a real suspension point of the loader's future
+struct YieldingProvider(InMemoryAccountProvider);
+
+impl AsyncAccountProvider for YieldingProvider {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        tokio::task::yield_now().await;
+        self.0.get_account(pubkey)
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        tokio::task::yield_now().await;
+        self.0.get_multiple_accounts(pubkeys)
+    }
+
+    async fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        tokio::task::yield_now().await;
+        self.0.get_slot()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.0.commitment()
+    }
+
+    fn endpoint(&self) -> String {
+        "yielding".to_string()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn populate_pool_states_async_can_be_spawned() {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let pools: Vec<TestPool> = (0..3)
+        .map(|idx| TestPool::insert(&mut accounts, idx * 1_000, &[-1, 0, 1], 0, None, 6, 9))
+        .collect();
+    let pool_pubkeys: Vec<Pubkey> = pools.iter().map(|pool| pool.pool_pubkey).collect();
+    let provider: Arc<YieldingProvider> = Arc::new(YieldingProvider(InMemoryAccountProvider::from(accounts)));
+
+    let handle = tokio::spawn({
+        let pool_pubkeys: Vec<Pubkey> = pool_pubkeys.clone();
+        async move {
+            populate_pool_states_async(
+                provider.as_ref(), &pool_pubkeys, TickArrayWindow::default(), true, true, true, Some(2)
+            ).await
+        }
+    });
+    let pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = handle.await.unwrap().unwrap();
+
+    assert_eq!(pool_states.len(), pools.len());
+    for ((pool_pubkey, pool_state), pool) in pool_states.iter().zip(pools.iter()) {
+        assert_eq!(*pool_pubkey, pool.pool_pubkey);
+        let pool_state: &PoolState = pool_state.as_ref().unwrap();
+        assert_eq!(pool_state.whirlpool.tick_current_index, pool.tick_current_index);
+        assert_eq!(pool_state.metadata.whirlpool.endpoint, "yielding");
+    }
+}
@@ -0,0 +1,178 @@
+// Shared builders for the pool integration tests, accounts are laid out byte for byte as the
+// Whirlpool and SPL Token programs store them on chain
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, program_option::COption, program_pack::Pack, pubkey::Pubkey
+};
+
+use borsh::BorshSerialize;
+
+use orca_whirlpools_client::{
+    Whirlpool, TickArray, Oracle
+};
+use orca_whirlpools_core::{
+    tick_index_to_sqrt_price, get_tick_array_start_tick_index, TICK_ARRAY_SIZE
+};
+
+use pool::pda::{
+    get_tick_array_address, get_oracle_address, parse_whirlpool_master_pubkey
+};
+
+pub const TICK_SPACING: u16 = 64;
+pub const TICK_ARRAY_WIDTH: i32 = TICK_SPACING as i32 * TICK_ARRAY_SIZE as i32;
+pub const POOL_LIQUIDITY: u128 = 1_000_000_000_000;
+
+// Anchor account discriminators, sha256("account:<Name>")[..8]
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+const ORACLE_DISCRIMINATOR: [u8; 8] = [139, 194, 131, 179, 140, 179, 229, 244];
+
+
+/// Account owned by the Whirlpool program
+pub fn whirlpool_program_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: parse_whirlpool_master_pubkey(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+
+/// Whirlpool account at tick_current_index with its sqrt price on the tick and constant liquidity
+pub fn whirlpool_account(mint_a: &Pubkey, mint_b: &Pubkey, tick_current_index: i32) -> Account {
+    // The client Whirlpool has no Default, start from an all zero account
+    let mut whirlpool: Whirlpool = Whirlpool::from_bytes(&[0; Whirlpool::LEN]).unwrap();
+    whirlpool.discriminator = WHIRLPOOL_DISCRIMINATOR;
+    whirlpool.tick_spacing = TICK_SPACING;
+    whirlpool.fee_tier_index_seed = TICK_SPACING.to_le_bytes();
+    whirlpool.fee_rate = 3000;
+    whirlpool.liquidity = POOL_LIQUIDITY;
+    whirlpool.sqrt_price = tick_index_to_sqrt_price(tick_current_index);
+    whirlpool.tick_current_index = tick_current_index;
+    // Client Pubkey type differs from the solana-sdk Pubkey type, convert via raw bytes
+    whirlpool.token_mint_a = mint_a.to_bytes().into();
+    whirlpool.token_mint_b = mint_b.to_bytes().into();
+    whirlpool_program_account(whirlpool.try_to_vec().unwrap())
+}
+
+
+/// Tick array account whose first tick is initialised with liquidity_net = marker, so tests can tell
+/// which pool and start tick index a decoded tick array came from
+pub fn tick_array_account(pool_pubkey: &Pubkey, start_tick_index: i32, marker: i128) -> Account {
+    let mut tick_array: TickArray = TickArray::from_bytes(&[0; TickArray::LEN]).unwrap();
+    tick_array.discriminator = TICK_ARRAY_DISCRIMINATOR;
+    tick_array.start_tick_index = start_tick_index;
+    tick_array.ticks[0].initialized = true;
+    tick_array.ticks[0].liquidity_net = marker;
+    tick_array.ticks[0].liquidity_gross = marker.unsigned_abs();
+    tick_array.whirlpool = pool_pubkey.to_bytes().into();
+    whirlpool_program_account(tick_array.try_to_vec().unwrap())
+}
+
+
+/// Oracle account with adaptive fees disabled, trade_enable_timestamp doubles as a marker
+pub fn oracle_account(pool_pubkey: &Pubkey, trade_enable_timestamp: u64) -> Account {
+    let mut oracle: Oracle = Oracle::from_bytes(&[0; Oracle::LEN]).unwrap();
+    oracle.discriminator = ORACLE_DISCRIMINATOR;
+    oracle.whirlpool = pool_pubkey.to_bytes().into();
+    oracle.trade_enable_timestamp = trade_enable_timestamp;
+    whirlpool_program_account(oracle.try_to_vec().unwrap())
+}
+
+
+/// Initialised SPL Token mint account without authorities
+pub fn mint_account(decimals: u8, supply: u64) -> Account {
+    let mint: spl_token::state::Mint = spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let mut data: Vec<u8> = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint::pack(mint, &mut data).unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+
+/// Tick array pubkey of a pool at start_tick_index
+pub fn tick_array_pubkey(pool_pubkey: &Pubkey, start_tick_index: i32) -> Pubkey {
+    get_tick_array_address(pool_pubkey, start_tick_index).unwrap().0
+}
+
+
+/// Oracle pubkey of a pool
+pub fn oracle_pubkey(pool_pubkey: &Pubkey) -> Pubkey {
+    get_oracle_address(pool_pubkey).unwrap().0
+}
+
+
+/// Start tick index of the tick array holding tick_index
+pub fn start_tick_index(tick_index: i32) -> i32 {
+    get_tick_array_start_tick_index(tick_index, TICK_SPACING)
+}
+
+
+/// A pool and everything populate_pool_states loads for it
+pub struct TestPool {
+    pub pool_pubkey: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub tick_current_index: i32,
+}
+
+impl TestPool {
+    /// Insert a pool with fresh pubkeys, see insert_accounts
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert(
+        accounts: &mut HashMap<Pubkey, Account>, tick_current_index: i32, tick_array_offsets: &[i32],
+        marker_base: i128, oracle_timestamp: Option<u64>, decimals_a: u8, decimals_b: u8
+    ) -> TestPool {
+        let pool: TestPool = TestPool {
+            pool_pubkey: Pubkey::new_unique(),
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+            tick_current_index,
+        };
+        pool.insert_accounts(accounts, tick_array_offsets, marker_base, oracle_timestamp, decimals_a, decimals_b);
+        pool
+    }
+
+    /// Insert the pool's whirlpool, the tick arrays at the given offsets from its current tick array,
+    /// an optional oracle and its two mints into accounts
+    ///     - Each tick array's marker is marker_base + its offset, mint decimals are decimals_a and decimals_b
+    pub fn insert_accounts(
+        &self, accounts: &mut HashMap<Pubkey, Account>, tick_array_offsets: &[i32], marker_base: i128,
+        oracle_timestamp: Option<u64>, decimals_a: u8, decimals_b: u8
+    ) {
+        accounts.insert(self.pool_pubkey, whirlpool_account(&self.mint_a, &self.mint_b, self.tick_current_index));
+        for offset in tick_array_offsets {
+            let start_idx: i32 = self.tick_array_start_index(*offset);
+            accounts.insert(
+                tick_array_pubkey(&self.pool_pubkey, start_idx),
+                tick_array_account(&self.pool_pubkey, start_idx, marker_base + *offset as i128)
+            );
+        }
+        if let Some(timestamp) = oracle_timestamp {
+            accounts.insert(oracle_pubkey(&self.pool_pubkey), oracle_account(&self.pool_pubkey, timestamp));
+        }
+        accounts.insert(self.mint_a, mint_account(decimals_a, 1_000_000));
+        accounts.insert(self.mint_b, mint_account(decimals_b, 2_000_000));
+    }
+
+    /// Start tick index of the tick array offset arrays away from the current one
+    pub fn tick_array_start_index(&self, offset: i32) -> i32 {
+        start_tick_index(self.tick_current_index) + offset * TICK_ARRAY_WIDTH
+    }
+}
@@ -0,0 +1,102 @@
+// Fetch ages are measured against a reference time, FetchMetadata::oldest names the stalest component
+// and ComponentFetch prints its fetch time as an RFC 3339 UTC timestamp
+
+use std::time::Duration;
+
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, hash::hash, pubkey::Pubkey
+};
+
+use pool::structs::{
+    ComponentFetch, FetchMetadata, PoolComponent, TickArrayWindow
+};
+
+// 2023-11-14T22:13:20.123Z
+const FETCHED_AT_MS: u64 = 1_700_000_000_123;
+const ENDPOINT: &str = "in-memory";
+
+fn fetch_at(fetched_at_ms: u64) -> ComponentFetch {
+    ComponentFetch::from_missing_account(7, fetched_at_ms, ENDPOINT)
+}
+
+/// Metadata whose whirlpool is fetched at FETCHED_AT_MS and every other component offset_ms after it,
+/// the second tick array lags the first by a further 250ms
+fn metadata(tick_array_offset_ms: u64, oracle_offset_ms: u64, mint_offset_ms: u64) -> FetchMetadata {
+    FetchMetadata {
+        commitment: CommitmentConfig::confirmed(),
+        tick_array_window: TickArrayWindow::default(),
+        whirlpool: fetch_at(FETCHED_AT_MS),
+        tick_arrays: vec![
+            fetch_at(FETCHED_AT_MS + tick_array_offset_ms + 250), fetch_at(FETCHED_AT_MS + tick_array_offset_ms)
+        ],
+        oracle: Some(fetch_at(FETCHED_AT_MS + oracle_offset_ms)),
+        mint_a: Some(fetch_at(FETCHED_AT_MS + mint_offset_ms)),
+        mint_b: None,
+    }
+}
+
+#[test]
+fn age_is_measured_against_the_given_time() {
+    let fetch: ComponentFetch = fetch_at(FETCHED_AT_MS);
+
+    assert_eq!(fetch.age_at(FETCHED_AT_MS), Duration::ZERO);
+    assert_eq!(fetch.age_at(FETCHED_AT_MS + 1_500), Duration::from_millis(1_500));
+    // A fetch stamped after the reference time is not negative
+    assert_eq!(fetch.age_at(FETCHED_AT_MS - 1), Duration::ZERO);
+    // Against the wall clock a 2023 fetch is older than a year
+    assert!(fetch.age() > Duration::from_secs(365 * 24 * 60 * 60));
+}
+
+#[test]
+fn age_of_a_component_is_its_oldest_fetch() {
+    let metadata: FetchMetadata = metadata(100, 200, 300);
+    let now_ms: u64 = FETCHED_AT_MS + 1_000;
+
+    assert_eq!(metadata.age_of_at(PoolComponent::Whirlpool, now_ms), Some(Duration::from_millis(1_000)));
+    // The tick arrays were fetched 100ms and 350ms after the whirlpool, the older one counts
+    assert_eq!(metadata.age_of_at(PoolComponent::TickArrays, now_ms), Some(Duration::from_millis(900)));
+    assert_eq!(metadata.age_of_at(PoolComponent::Oracle, now_ms), Some(Duration::from_millis(800)));
+    assert_eq!(metadata.age_of_at(PoolComponent::MintA, now_ms), Some(Duration::from_millis(700)));
+    assert_eq!(metadata.age_of_at(PoolComponent::MintB, now_ms), None);
+}
+
+#[test]
+fn oldest_names_the_stalest_component() {
+    let now_ms: u64 = FETCHED_AT_MS + 10_000;
+
+    // Everything after the whirlpool was fetched later, the whirlpool is the stalest
+    assert_eq!(
+        metadata(100, 200, 300).oldest_at(now_ms), Some((PoolComponent::Whirlpool, Duration::from_millis(10_000)))
+    );
+
+    // A component fetched before the whirlpool is staler than it
+    let mut metadata: FetchMetadata = metadata(100, 200, 300);
+    metadata.oracle = Some(fetch_at(FETCHED_AT_MS - 2_000));
+    assert_eq!(metadata.oldest_at(now_ms), Some((PoolComponent::Oracle, Duration::from_millis(12_000))));
+
+    metadata.mint_a = Some(fetch_at(FETCHED_AT_MS - 5_000));
+    assert_eq!(metadata.oldest_at(now_ms), Some((PoolComponent::MintA, Duration::from_millis(15_000))));
+}
+
+#[test]
+fn display_prints_the_fetch_time_as_rfc_3339() {
+    let account: Account = Account {
+        lamports: 1,
+        data: vec![1, 2, 3],
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    let fetch: ComponentFetch = ComponentFetch::from_account(&account, 42, FETCHED_AT_MS, ENDPOINT);
+    let printed: String = fetch.to_string();
+
+    // The age depends on the wall clock, everything around it is fixed
+    assert!(printed.starts_with("fetched 2023-11-14T22:13:20.123Z (age "), "{printed}");
+    assert!(
+        printed.ends_with(&format!("s) at slot 42 from in-memory, data hash {}", hash(&[1, 2, 3]))),
+        "{printed}"
+    );
+
+    // Whole seconds keep their millisecond digits
+    assert!(fetch_at(1_700_000_000_000).to_string().starts_with("fetched 2023-11-14T22:13:20.000Z "));
+}
@@ -0,0 +1,173 @@
+// Drive populate_pool_states end to end from the account fixtures in tests/fixtures/pool, stored in
+// the `solana account <PUBKEY> --output json` format read by FileAccountProvider
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{
+    Path, PathBuf
+};
+
+use serde_json::json;
+use solana_account_decoder::{
+    UiAccount, UiAccountEncoding
+};
+use solana_sdk::{
+    account::Account, hash::hash, pubkey::Pubkey
+};
+
+use orca_whirlpools_core::{
+    tick_index_to_sqrt_price, TickArrayFacade
+};
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states, structs::{PoolState, TickArrayWindow}
+};
+use provider::{
+    provider::AccountProvider, structs::{FileAccountProvider, InMemoryAccountProvider}
+};
+
+use common::{
+    TestPool, POOL_LIQUIDITY, TICK_SPACING, oracle_pubkey, tick_array_pubkey
+};
+
+const FIXTURE_SLOT: u64 = 250_000_000;
+const TICK_CURRENT_INDEX: i32 = -16_000;
+const MARKER_BASE: i128 = 5_000;
+const ORACLE_TIMESTAMP: u64 = 1_700_000_000;
+const DECIMALS_A: u8 = 9;
+const DECIMALS_B: u8 = 6;
+
+/// The fixture pool, with fixed pubkeys so the fixture file names stay stable
+fn fixture_pool() -> TestPool {
+    TestPool {
+        pool_pubkey: Pubkey::new_from_array([11; 32]),
+        mint_a: Pubkey::new_from_array([12; 32]),
+        mint_b: Pubkey::new_from_array([13; 32]),
+        tick_current_index: TICK_CURRENT_INDEX,
+    }
+}
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pool")
+}
+
+/// The fixture pool's accounts keyed by (file label, pubkey)
+fn fixture_accounts() -> Vec<(String, Pubkey, Account)> {
+    let pool: TestPool = fixture_pool();
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    pool.insert_accounts(&mut accounts, &[-1, 0, 1], MARKER_BASE, Some(ORACLE_TIMESTAMP), DECIMALS_A, DECIMALS_B);
+
+    let mut labels: Vec<(String, Pubkey)> = vec![
+        ("whirlpool".to_string(), pool.pool_pubkey),
+        ("oracle".to_string(), oracle_pubkey(&pool.pool_pubkey)),
+        ("mint_a".to_string(), pool.mint_a),
+        ("mint_b".to_string(), pool.mint_b),
+    ];
+    for offset in -1..=1 {
+        let start_idx: i32 = pool.tick_array_start_index(offset);
+        labels.push((format!("tick_array_{}", start_idx), tick_array_pubkey(&pool.pool_pubkey, start_idx)));
+    }
+    labels.into_iter()
+        .map(|(label, pubkey)| {
+            let account: Account = accounts.remove(&pubkey).unwrap();
+            (label, pubkey, account)
+        })
+        .collect()
+}
+
+/// Rewrite tests/fixtures/pool from fixture_accounts
+///     - Run with `cargo test -p pool --test fixtures -- --ignored` after changing the fixture pool
+#[test]
+#[ignore]
+fn write_fixtures() {
+    fs::create_dir_all(fixture_dir()).unwrap();
+    for (label, pubkey, account) in fixture_accounts() {
+        let ui_account: UiAccount = UiAccount::encode(&pubkey, &account, UiAccountEncoding::Base64, None, None);
+        let fixture = json!({ "pubkey": pubkey.to_string(), "account": ui_account });
+        fs::write(
+            fixture_dir().join(format!("{}.json", label)),
+            serde_json::to_string_pretty(&fixture).unwrap() + "\n"
+        ).unwrap();
+    }
+}
+
+#[test]
+fn fixtures_decode_to_the_recorded_accounts() {
+    let provider: FileAccountProvider = FileAccountProvider::from_dir(&fixture_dir(), FIXTURE_SLOT).unwrap();
+    let expected: HashMap<Pubkey, Account> = fixture_accounts().into_iter()
+        .map(|(_, pubkey, account)| (pubkey, account))
+        .collect();
+    assert_eq!(provider.inner.accounts, expected);
+}
+
+#[test]
+fn populate_pool_states_from_fixtures() {
+    let provider: FileAccountProvider = FileAccountProvider::from_dir(&fixture_dir(), FIXTURE_SLOT).unwrap();
+    let pool: TestPool = fixture_pool();
+    let pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &provider, &[pool.pool_pubkey], TickArrayWindow::default(), true, true, true, None
+    ).unwrap();
+    let pool_state: &PoolState = pool_states[0].1.as_ref().unwrap();
+
+    // Whirlpool
+    assert_eq!(pool_state.whirlpool.tick_spacing, TICK_SPACING);
+    assert_eq!(pool_state.whirlpool.fee_rate, 3000);
+    assert_eq!(pool_state.whirlpool.liquidity, POOL_LIQUIDITY);
+    assert_eq!(pool_state.whirlpool.tick_current_index, TICK_CURRENT_INDEX);
+    assert_eq!(pool_state.whirlpool.sqrt_price, tick_index_to_sqrt_price(TICK_CURRENT_INDEX));
+
+    // Tick arrays, one either side of the current one
+    let tick_arrays: Vec<TickArrayFacade> = <[Option<TickArrayFacade>; 6]>::from(pool_state.current_tick_array.clone())
+        .into_iter()
+        .flatten()
+        .collect();
+    let start_idxs: Vec<i32> = tick_arrays.iter().map(|tick_array| tick_array.start_tick_index).collect();
+    assert_eq!(start_idxs, vec![-22_528, -16_896, -11_264]);
+    for (tick_array, offset) in tick_arrays.iter().zip(-1..=1) {
+        assert!(tick_array.ticks[0].initialized);
+        assert_eq!(tick_array.ticks[0].liquidity_net, MARKER_BASE + offset as i128);
+    }
+
+    // Oracle and mints
+    assert_eq!(pool_state.oracle.map(|oracle| oracle.trade_enable_timestamp), Some(ORACLE_TIMESTAMP));
+    assert_eq!(pool_state.mint_a_data.pubkey, pool.mint_a);
+    assert_eq!(pool_state.mint_a_data.decimals, DECIMALS_A);
+    assert_eq!(pool_state.mint_a_data.supply, 1_000_000);
+    assert_eq!(pool_state.mint_b_data.pubkey, pool.mint_b);
+    assert_eq!(pool_state.mint_b_data.decimals, DECIMALS_B);
+    assert_eq!(pool_state.mint_b_data.token_program, spl_token::id());
+
+    // Fetch metadata, every component read from the fixture directory at the fixture slot
+    let whirlpool_account: Account = provider.get_account(&pool.pool_pubkey).unwrap().value.unwrap();
+    assert_eq!(pool_state.metadata.whirlpool.slot, FIXTURE_SLOT);
+    assert_eq!(pool_state.metadata.whirlpool.endpoint, provider.endpoint());
+    assert_eq!(pool_state.metadata.whirlpool.data_hash, hash(&whirlpool_account.data));
+    assert_eq!(pool_state.metadata.tick_arrays.len(), 3);
+    assert!(pool_state.metadata.tick_arrays.iter().all(|fetch| !fetch.is_missing_account()));
+    assert!(pool_state.metadata.oracle.is_some());
+    assert!(pool_state.metadata.mint_a.is_some() && pool_state.metadata.mint_b.is_some());
+}
+
+#[test]
+fn in_memory_and_fixture_providers_load_the_same_pool() {
+    let file_provider: FileAccountProvider = FileAccountProvider::from_dir(&fixture_dir(), FIXTURE_SLOT).unwrap();
+    let memory_provider: InMemoryAccountProvider = InMemoryAccountProvider {
+        slot: FIXTURE_SLOT,
+        ..InMemoryAccountProvider::from(file_provider.inner.accounts.clone())
+    };
+    let pool_pubkey: Pubkey = fixture_pool().pool_pubkey;
+
+    let load = |provider: &(dyn AccountProvider + Sync)| -> PoolState {
+        let mut pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+            provider, &[pool_pubkey], TickArrayWindow::default(), true, true, true, None
+        ).unwrap();
+        pool_states.remove(0).1.unwrap()
+    };
+    let (from_file, from_memory): (PoolState, PoolState) = (load(&file_provider), load(&memory_provider));
+    assert_eq!(from_file.current_tick_array, from_memory.current_tick_array);
+    assert_eq!(from_file.whirlpool, from_memory.whirlpool);
+    assert_eq!(from_file.metadata.whirlpool.data_hash, from_memory.metadata.whirlpool.data_hash);
+}
@@ -0,0 +1,79 @@
+// A pool whose neighbouring tick arrays were never initialised still quotes, the loader substitutes empty
+// zero liquidity tick arrays and records their fetches as missing accounts
+
+mod common;
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, ExactInSwapQuote, TickArrayFacade, TickArrays
+};
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states, structs::{ComponentFetch, PoolState, TickArrayWindow}
+};
+use provider::structs::InMemoryAccountProvider;
+
+use common::TestPool;
+
+// Enough token a to push the price from tick 100 down past tick 0, into the left tick array
+const CROSSING_AMOUNT_IN: u64 = 10_000_000_000;
+
+/// A pool at tick 100 holding only its current tick array, loaded with the default window
+fn load_pool_without_neighbours() -> (TestPool, PoolState) {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let pool: TestPool = TestPool::insert(&mut accounts, 100, &[0], 0, None, 6, 6);
+    let provider: InMemoryAccountProvider = InMemoryAccountProvider::from(accounts);
+    let mut pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &provider, &[pool.pool_pubkey], TickArrayWindow::default(), true, true, true, None
+    ).unwrap();
+    let pool_state: PoolState = pool_states.remove(0).1.unwrap();
+    (pool, pool_state)
+}
+
+/// Quote token a in through the given tick arrays, no oracle and no transfer fees
+fn quote_a_to_b(pool_state: &PoolState, tick_arrays: TickArrays) -> Result<ExactInSwapQuote, &'static str> {
+    swap_quote_by_input_token(CROSSING_AMOUNT_IN, true, 100, pool_state.whirlpool, None, tick_arrays, 0, None, None)
+}
+
+#[test]
+fn missing_neighbours_are_substituted_with_empty_tick_arrays() {
+    let (pool, pool_state): (TestPool, PoolState) = load_pool_without_neighbours();
+
+    let tick_arrays: Vec<TickArrayFacade> = <[Option<TickArrayFacade>; 6]>::from(pool_state.current_tick_array.clone())
+        .into_iter()
+        .flatten()
+        .collect();
+    let start_idxs: Vec<i32> = tick_arrays.iter().map(|tick_array| tick_array.start_tick_index).collect();
+    assert_eq!(start_idxs, (-1..=1).map(|offset| pool.tick_array_start_index(offset)).collect::<Vec<i32>>());
+    for neighbour in [&tick_arrays[0], &tick_arrays[2]] {
+        assert!(neighbour.ticks.iter().all(|tick| !tick.initialized && tick.liquidity_net == 0));
+    }
+    assert!(tick_arrays[1].ticks[0].initialized);
+
+    // The missing neighbours are recorded with the all zero data hash, the current tick array is not
+    let fetches: &[ComponentFetch] = &pool_state.metadata.tick_arrays;
+    let missing: Vec<bool> = fetches.iter().map(|fetch| fetch.is_missing_account()).collect();
+    assert_eq!(missing, vec![true, false, true]);
+    assert_eq!(fetches[0].data_hash, Default::default());
+    assert_eq!(fetches[0].slot, fetches[1].slot);
+}
+
+#[test]
+fn a_pool_with_missing_neighbours_still_quotes_across_them() {
+    let (_, pool_state): (TestPool, PoolState) = load_pool_without_neighbours();
+
+    let quote: ExactInSwapQuote = quote_a_to_b(&pool_state, pool_state.current_tick_array.clone()).unwrap();
+    assert_eq!(quote.token_in, CROSSING_AMOUNT_IN);
+    assert!(quote.token_est_out > 0 && quote.token_min_out <= quote.token_est_out);
+
+    // Without the substituted left tick array the same trade runs off the end of the sequence
+    let current_only: TickArrayFacade = <[Option<TickArrayFacade>; 6]>::from(pool_state.current_tick_array.clone())[1]
+        .unwrap();
+    assert!(quote_a_to_b(&pool_state, TickArrays::One(current_only)).is_err());
+}
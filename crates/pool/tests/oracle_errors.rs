@@ -0,0 +1,51 @@
+// An undecodable oracle fails its pool instead of being silently dropped, quoting an adaptive fee
+// pool without its oracle would understate the fee
+
+mod common;
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states, structs::{PoolState, TickArrayWindow}
+};
+use provider::structs::InMemoryAccountProvider;
+
+use common::{
+    TestPool, oracle_pubkey, whirlpool_program_account
+};
+
+/// Provider holding a pool with a valid oracle and a pool whose oracle account is truncated
+fn provider_with_broken_oracle() -> (InMemoryAccountProvider, TestPool, TestPool) {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let healthy: TestPool = TestPool::insert(&mut accounts, 0, &[-1, 0, 1], 0, Some(42), 6, 6);
+    let broken: TestPool = TestPool::insert(&mut accounts, 0, &[-1, 0, 1], 0, None, 6, 6);
+    accounts.insert(oracle_pubkey(&broken.pool_pubkey), whirlpool_program_account(vec![1; 16]));
+    (InMemoryAccountProvider::from(accounts), healthy, broken)
+}
+
+#[test]
+fn an_undecodable_oracle_fails_only_its_pool() {
+    let (provider, healthy, broken): (InMemoryAccountProvider, TestPool, TestPool) = provider_with_broken_oracle();
+    let pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &provider, &[healthy.pool_pubkey, broken.pool_pubkey], TickArrayWindow::default(), false, false, true, None
+    ).unwrap();
+
+    let healthy_state: &PoolState = pool_states[0].1.as_ref().unwrap();
+    assert_eq!(healthy_state.oracle.map(|oracle| oracle.trade_enable_timestamp), Some(42));
+    assert!(healthy_state.metadata.oracle.is_some());
+    assert!(matches!(pool_states[1].1, Err(OrcaQuoterError::Deserialisation(_))));
+}
+
+#[test]
+fn an_undecodable_oracle_is_a_batch_error_when_all_accounts_are_required() {
+    let (provider, healthy, broken): (InMemoryAccountProvider, TestPool, TestPool) = provider_with_broken_oracle();
+    let result = populate_pool_states(
+        &provider, &[healthy.pool_pubkey, broken.pool_pubkey], TickArrayWindow::default(), true, false, true, None
+    );
+    assert!(matches!(result, Err(OrcaQuoterError::Deserialisation(_))));
+}
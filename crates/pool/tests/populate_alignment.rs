@@ -0,0 +1,83 @@
+// A missing or undecodable pool account must not shift the tick arrays, oracle or mints of the
+// pools after it onto the wrong whirlpool
+
+mod common;
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+
+use orca_whirlpools_core::TickArrayFacade;
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states, structs::{PoolState, TickArrayWindow}
+};
+use provider::structs::InMemoryAccountProvider;
+
+use common::{
+    TestPool, whirlpool_program_account
+};
+
+/// Check that a loaded PoolState holds exactly the accounts inserted for its own pool
+fn assert_pool_state_matches(pool_state: &PoolState, pool: &TestPool, marker_base: i128, oracle_timestamp: Option<u64>, decimals: (u8, u8)) {
+    assert_eq!(pool_state.whirlpool.tick_current_index, pool.tick_current_index);
+
+    // The default window is one tick array either side, each carrying its pool's marker
+    let tick_arrays: Vec<TickArrayFacade> = <[Option<TickArrayFacade>; 6]>::from(pool_state.current_tick_array.clone())
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(tick_arrays.len(), 3);
+    for (tick_array, offset) in tick_arrays.iter().zip(-1..=1) {
+        assert_eq!(tick_array.start_tick_index, pool.tick_array_start_index(offset));
+        assert_eq!(tick_array.ticks[0].liquidity_net, marker_base + offset as i128);
+    }
+
+    assert_eq!(pool_state.oracle.map(|oracle| oracle.trade_enable_timestamp), oracle_timestamp);
+    assert_eq!(pool_state.mint_a_data.pubkey, pool.mint_a);
+    assert_eq!(pool_state.mint_b_data.pubkey, pool.mint_b);
+    assert_eq!((pool_state.mint_a_data.decimals, pool_state.mint_b_data.decimals), decimals);
+}
+
+#[test]
+fn skipped_pools_keep_every_other_pool_aligned() {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let first: TestPool = TestPool::insert(&mut accounts, 100, &[-1, 0, 1], 1_000, Some(111), 6, 9);
+    // Never inserted, so its account is missing
+    let missing_pubkey: Pubkey = Pubkey::new_unique();
+    // Owned by the Whirlpool program but far too short to decode as a Whirlpool
+    let undecodable_pubkey: Pubkey = Pubkey::new_unique();
+    accounts.insert(undecodable_pubkey, whirlpool_program_account(vec![7; 10]));
+    let last: TestPool = TestPool::insert(&mut accounts, -20_000, &[-1, 0, 1], 2_000, None, 8, 5);
+
+    let provider: InMemoryAccountProvider = InMemoryAccountProvider::from(accounts);
+    let pool_pubkeys: Vec<Pubkey> = vec![first.pool_pubkey, missing_pubkey, undecodable_pubkey, last.pool_pubkey];
+    let pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &provider, &pool_pubkeys, TickArrayWindow::default(), false, false, true, None
+    ).unwrap();
+
+    // Results are reported per pool, in the order the pools were requested
+    let result_pubkeys: Vec<Pubkey> = pool_states.iter().map(|(pubkey, _)| *pubkey).collect();
+    assert_eq!(result_pubkeys, pool_pubkeys);
+    assert_eq!(pool_states[1].1.as_ref().unwrap_err(), &OrcaQuoterError::AccountNotFound(missing_pubkey));
+    assert!(matches!(pool_states[2].1, Err(OrcaQuoterError::Deserialisation(_))));
+
+    assert_pool_state_matches(pool_states[0].1.as_ref().unwrap(), &first, 1_000, Some(111), (6, 9));
+    assert_pool_state_matches(pool_states[3].1.as_ref().unwrap(), &last, 2_000, None, (8, 5));
+}
+
+#[test]
+fn a_missing_pool_is_a_batch_error_when_all_accounts_are_required() {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let pool: TestPool = TestPool::insert(&mut accounts, 0, &[-1, 0, 1], 0, None, 6, 6);
+    let missing_pubkey: Pubkey = Pubkey::new_unique();
+
+    let provider: InMemoryAccountProvider = InMemoryAccountProvider::from(accounts);
+    let result = populate_pool_states(
+        &provider, &[pool.pool_pubkey, missing_pubkey], TickArrayWindow::default(), true, false, true, None
+    );
+    assert_eq!(result.unwrap_err(), OrcaQuoterError::AccountNotFound(missing_pubkey));
+}
@@ -0,0 +1,136 @@
+// Pools whose whirlpool, tick arrays and oracle are read at different slots report the spread as
+// their slot skew, so callers can reject quotes mixing a whirlpool and tick arrays of different slots
+
+mod common;
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey
+};
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states, structs::{PoolState, TickArrayWindow}
+};
+use provider::{
+    provider::AccountProvider, structs::{AtSlot, InMemoryAccountProvider}
+};
+
+use common::{
+    TestPool, oracle_pubkey, tick_array_pubkey
+};
+
+const BASE_SLOT: u64 = 1_000;
+
+/// In memory provider that reads each account at its own slot
+///     - A get_multiple_accounts call is reported at the highest slot of the accounts it reads,
+///       accounts without a slot of their own are read at the inner provider's slot
+struct PerAccountSlotProvider {
+    inner: InMemoryAccountProvider,
+    slots: HashMap<Pubkey, u64>,
+}
+
+impl PerAccountSlotProvider {
+    fn slot_of(&self, pubkeys: &[Pubkey]) -> u64 {
+        pubkeys.iter()
+            .map(|pubkey| self.slots.get(pubkey).copied().unwrap_or(self.inner.slot))
+            .max()
+            .unwrap_or(self.inner.slot)
+    }
+}
+
+impl AccountProvider for PerAccountSlotProvider {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        Ok(AtSlot { slot: self.slot_of(&[*pubkey]), value: self.inner.get_account(pubkey)?.value })
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        Ok(AtSlot { slot: self.slot_of(pubkeys), value: self.inner.get_multiple_accounts(pubkeys)?.value })
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        Ok(self.inner.slot)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.inner.commitment
+    }
+
+    fn endpoint(&self) -> String {
+        "per-account-slot".to_string()
+    }
+}
+
+/// Load a single pool whose tick arrays are read tick_array_lag and its oracle oracle_lag slots after
+/// its whirlpool, the mints are read far later to show they are left out of the skew
+fn load_pool(tick_array_lag: u64, oracle_lag: u64) -> PoolState {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let pool: TestPool = TestPool::insert(&mut accounts, 0, &[-1, 0, 1], 0, Some(42), 6, 6);
+
+    let mut slots: HashMap<Pubkey, u64> = HashMap::new();
+    for offset in [-1, 0, 1] {
+        slots.insert(tick_array_pubkey(&pool.pool_pubkey, pool.tick_array_start_index(offset)), BASE_SLOT + tick_array_lag);
+    }
+    slots.insert(oracle_pubkey(&pool.pool_pubkey), BASE_SLOT + oracle_lag);
+    slots.insert(pool.mint_a, BASE_SLOT + 500);
+    slots.insert(pool.mint_b, BASE_SLOT + 500);
+
+    let provider: PerAccountSlotProvider = PerAccountSlotProvider {
+        inner: InMemoryAccountProvider { slot: BASE_SLOT, ..InMemoryAccountProvider::from(accounts) },
+        slots,
+    };
+    let mut pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &provider, &[pool.pool_pubkey], TickArrayWindow::default(), true, true, true, None
+    ).unwrap();
+    pool_states.remove(0).1.unwrap()
+}
+
+#[test]
+fn components_read_at_one_slot_are_consistent() {
+    let pool_state: PoolState = load_pool(0, 0);
+
+    assert_eq!(pool_state.metadata.slot_skew(), 0);
+    assert!(pool_state.is_consistent());
+    assert!(pool_state.is_within_slot_skew(0));
+    // The mints were read 500 slots later but do not feed the swap math
+    assert_eq!(pool_state.metadata.mint_a.as_ref().map(|fetch| fetch.slot), Some(BASE_SLOT + 500));
+}
+
+#[test]
+fn slot_skew_spans_whirlpool_tick_arrays_and_oracle() {
+    let pool_state: PoolState = load_pool(2, 5);
+
+    assert_eq!(pool_state.metadata.whirlpool.slot, BASE_SLOT);
+    assert!(pool_state.metadata.tick_arrays.iter().all(|fetch| fetch.slot == BASE_SLOT + 2));
+    assert_eq!(pool_state.metadata.oracle.as_ref().map(|fetch| fetch.slot), Some(BASE_SLOT + 5));
+    assert_eq!(pool_state.metadata.slot_skew(), 5);
+    assert!(!pool_state.is_consistent());
+}
+
+#[test]
+fn slot_skew_is_accepted_up_to_the_max_and_rejected_past_it() {
+    let pool_state: PoolState = load_pool(3, 0);
+
+    assert_eq!(pool_state.metadata.slot_skew(), 3);
+    assert!(pool_state.is_within_slot_skew(u64::MAX));
+    assert!(pool_state.is_within_slot_skew(3));
+    assert!(!pool_state.is_within_slot_skew(2));
+    assert!(!pool_state.is_within_slot_skew(0));
+}
+
+#[test]
+fn an_oracle_read_before_the_whirlpool_still_counts() {
+    let mut pool_state: PoolState = load_pool(1, 1);
+    if let Some(oracle) = pool_state.metadata.oracle.as_mut() {
+        oracle.slot = BASE_SLOT - 4;
+    }
+
+    assert_eq!(pool_state.metadata.slot_skew(), 5);
+    assert!(pool_state.is_within_slot_skew(5));
+    assert!(!pool_state.is_within_slot_skew(4));
+
+    // Without an oracle only the whirlpool and tick arrays are compared
+    pool_state.metadata.oracle = None;
+    assert_eq!(pool_state.metadata.slot_skew(), 1);
+}
@@ -0,0 +1,107 @@
+// Snapshot files reload the exact PoolStates they were written from, in both formats, and refuse
+// snapshots written with another SNAPSHOT_VERSION
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+use tempfile::TempDir;
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states, structs::{PoolState, TickArrayWindow},
+    snapshot::{PoolSnapshot, SnapshotFormat, SNAPSHOT_VERSION, read_snapshot, write_snapshot}
+};
+use provider::structs::InMemoryAccountProvider;
+
+use common::TestPool;
+
+/// Two loaded pools, one with an oracle and one with a tick array missing from its window
+fn loaded_pool_states() -> Vec<(Pubkey, PoolState)> {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let with_oracle: TestPool = TestPool::insert(&mut accounts, -16_000, &[-1, 0, 1], 100, Some(42), 9, 6);
+    let partial: TestPool = TestPool::insert(&mut accounts, 3_000, &[0, 1], 200, None, 6, 6);
+    let provider: InMemoryAccountProvider = InMemoryAccountProvider::from(accounts);
+    populate_pool_states(
+        &provider, &[with_oracle.pool_pubkey, partial.pool_pubkey], TickArrayWindow::default(), false, false, true, None
+    ).unwrap()
+        .into_iter()
+        .map(|(pool_pubkey, pool_state)| (pool_pubkey, pool_state.unwrap()))
+        .collect()
+}
+
+// A PoolState holds up to six tick arrays inline, decoding one in an unoptimised build needs more than the
+// 2 MiB a test thread gets
+const DECODE_STACK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Run f on a thread with DECODE_STACK_SIZE of stack
+fn with_decode_stack<F: FnOnce() + Send + 'static>(f: F) {
+    thread::Builder::new().stack_size(DECODE_STACK_SIZE).spawn(f).unwrap().join().unwrap();
+}
+
+/// PoolState has no PartialEq, snapshots are compared through their JSON encoding, serde_json::Value
+/// cannot hold the u128 sqrt price and liquidity
+fn as_json(pool_states: &[(Pubkey, PoolState)]) -> String {
+    serde_json::to_string(pool_states).unwrap()
+}
+
+fn assert_round_trip(format: SnapshotFormat) {
+    let dir: TempDir = TempDir::new().unwrap();
+    let path: PathBuf = dir.path().join("pools.snapshot");
+    let pool_states: Vec<(Pubkey, PoolState)> = loaded_pool_states();
+
+    write_snapshot(&path, &pool_states, format).unwrap();
+    let reloaded: Vec<(Pubkey, PoolState)> = read_snapshot(&path, format).unwrap();
+    assert_eq!(reloaded.len(), 2);
+    assert_eq!(as_json(&reloaded), as_json(&pool_states));
+}
+
+#[test]
+fn json_snapshots_round_trip() {
+    with_decode_stack(|| assert_round_trip(SnapshotFormat::Json));
+}
+
+#[test]
+fn bincode_snapshots_round_trip() {
+    with_decode_stack(|| assert_round_trip(SnapshotFormat::Bincode));
+}
+
+#[test]
+fn snapshots_with_another_version_are_rejected() {
+    with_decode_stack(assert_other_versions_are_rejected);
+}
+
+fn assert_other_versions_are_rejected() {
+    let dir: TempDir = TempDir::new().unwrap();
+    let snapshot: PoolSnapshot = PoolSnapshot {
+        version: SNAPSHOT_VERSION - 1,
+        pool_states: loaded_pool_states(),
+    };
+    let formats: [(SnapshotFormat, Vec<u8>); 2] = [
+        (SnapshotFormat::Json, serde_json::to_vec(&snapshot).unwrap()),
+        (SnapshotFormat::Bincode, bincode::serialize(&snapshot).unwrap()),
+    ];
+    for (format, bytes) in formats {
+        let path: PathBuf = dir.path().join(format!("{:?}.snapshot", format));
+        fs::write(&path, bytes).unwrap();
+        let result: Result<Vec<(Pubkey, PoolState)>, OrcaQuoterError> = read_snapshot(&path, format);
+        assert!(
+            matches!(&result, Err(OrcaQuoterError::Deserialisation(err)) if err.contains("expected 5")),
+            "{:?}", result.map(|pool_states| pool_states.len())
+        );
+    }
+}
+
+#[test]
+fn snapshots_read_in_the_wrong_format_are_rejected() {
+    let dir: TempDir = TempDir::new().unwrap();
+    let path: PathBuf = dir.path().join("pools.snapshot");
+    write_snapshot(&path, &loaded_pool_states(), SnapshotFormat::Bincode).unwrap();
+    assert!(matches!(read_snapshot(&path, SnapshotFormat::Json), Err(OrcaQuoterError::Deserialisation(_))));
+}
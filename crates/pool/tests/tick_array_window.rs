@@ -0,0 +1,120 @@
+// TickArrayWindow construction, and the start tick indexes a window derives around the current tick,
+// clipped at MIN_TICK_INDEX and MAX_TICK_INDEX
+
+mod common;
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+
+use orca_whirlpools_core::{
+    TickArrayFacade, TickArrays, WhirlpoolFacade, MAX_TICK_INDEX, MIN_TICK_INDEX
+};
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::{populate_pool_states, tick_array_start_indexes}, structs::{PoolState, TickArrayWindow}
+};
+use provider::structs::InMemoryAccountProvider;
+
+use common::{
+    TestPool, TICK_ARRAY_WIDTH, TICK_SPACING, start_tick_index
+};
+
+/// WhirlpoolFacade at tick_current_index with the test tick spacing
+fn whirlpool_at(tick_current_index: i32) -> WhirlpoolFacade {
+    WhirlpoolFacade {
+        tick_spacing: TICK_SPACING,
+        tick_current_index,
+        ..WhirlpoolFacade::default()
+    }
+}
+
+#[test]
+fn windows_hold_at_most_six_tick_arrays() {
+    assert_eq!(TickArrayWindow::new(2, 3).unwrap(), TickArrayWindow { left: 2, right: 3 });
+    assert_eq!(TickArrayWindow::new(2, 3).unwrap().tick_array_count(), TickArrayWindow::MAX_TICK_ARRAYS);
+    assert_eq!(TickArrayWindow::default(), TickArrayWindow { left: 1, right: 1 });
+
+    assert!(matches!(TickArrayWindow::new(3, 3), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(TickArrayWindow::new(0, 6), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(TickArrayWindow::new(u8::MAX, u8::MAX), Err(OrcaQuoterError::InvalidArgument(_))));
+}
+
+#[test]
+fn symmetric_windows_extend_both_ways() {
+    assert_eq!(TickArrayWindow::symmetric(0).unwrap(), TickArrayWindow { left: 0, right: 0 });
+    assert_eq!(TickArrayWindow::symmetric(2).unwrap(), TickArrayWindow { left: 2, right: 2 });
+    assert!(matches!(TickArrayWindow::symmetric(3), Err(OrcaQuoterError::InvalidArgument(_))));
+}
+
+#[test]
+fn directional_windows_extend_in_the_swap_direction() {
+    // a to b lowers the price and walks left
+    assert_eq!(TickArrayWindow::directional(true, 5).unwrap(), TickArrayWindow { left: 5, right: 0 });
+    assert_eq!(TickArrayWindow::directional(false, 5).unwrap(), TickArrayWindow { left: 0, right: 5 });
+    assert!(matches!(TickArrayWindow::directional(true, 6), Err(OrcaQuoterError::InvalidArgument(_))));
+}
+
+#[test]
+fn start_indexes_follow_the_window() {
+    let whirlpool: WhirlpoolFacade = whirlpool_at(100);
+    assert_eq!(tick_array_start_indexes(&whirlpool, TickArrayWindow::default()), vec![-5_632, 0, 5_632]);
+    assert_eq!(
+        tick_array_start_indexes(&whirlpool, TickArrayWindow::new(2, 3).unwrap()),
+        vec![-11_264, -5_632, 0, 5_632, 11_264, 16_896]
+    );
+    assert_eq!(tick_array_start_indexes(&whirlpool, TickArrayWindow::directional(true, 2).unwrap()), vec![-11_264, -5_632, 0]);
+
+    // Negative ticks round down to the tick array holding them
+    assert_eq!(tick_array_start_indexes(&whirlpool_at(-1), TickArrayWindow::symmetric(0).unwrap()), vec![-5_632]);
+}
+
+#[test]
+fn start_indexes_are_clipped_at_the_tick_bounds() {
+    let window: TickArrayWindow = TickArrayWindow::symmetric(2).unwrap();
+
+    // The last tick array holds MAX_TICK_INDEX, none start above it
+    let top: i32 = start_tick_index(MAX_TICK_INDEX);
+    assert_eq!(
+        tick_array_start_indexes(&whirlpool_at(MAX_TICK_INDEX), window),
+        vec![top - 2*TICK_ARRAY_WIDTH, top - TICK_ARRAY_WIDTH, top]
+    );
+
+    // The first tick array holds MIN_TICK_INDEX, none end below it
+    let bottom: i32 = start_tick_index(MIN_TICK_INDEX);
+    assert!(bottom <= MIN_TICK_INDEX && bottom + TICK_ARRAY_WIDTH > MIN_TICK_INDEX);
+    assert_eq!(
+        tick_array_start_indexes(&whirlpool_at(MIN_TICK_INDEX), window),
+        vec![bottom, bottom + TICK_ARRAY_WIDTH, bottom + 2*TICK_ARRAY_WIDTH]
+    );
+
+    // One tick array in from the bottom keeps its left neighbour and drops the one past it
+    assert_eq!(
+        tick_array_start_indexes(&whirlpool_at(bottom + TICK_ARRAY_WIDTH), window),
+        vec![bottom, bottom + TICK_ARRAY_WIDTH, bottom + 2*TICK_ARRAY_WIDTH, bottom + 3*TICK_ARRAY_WIDTH]
+    );
+}
+
+#[test]
+fn a_six_array_window_loads_tick_arrays_six() {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let pool: TestPool = TestPool::insert(&mut accounts, 100, &[-2, -1, 0, 1, 2, 3], 0, None, 6, 6);
+    let provider: InMemoryAccountProvider = InMemoryAccountProvider::from(accounts);
+
+    let pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &provider, &[pool.pool_pubkey], TickArrayWindow::new(2, 3).unwrap(), true, true, false, None
+    ).unwrap();
+    let pool_state: &PoolState = pool_states[0].1.as_ref().unwrap();
+    assert!(matches!(pool_state.current_tick_array, TickArrays::Six(..)), "{:?}", pool_state.current_tick_array);
+    let start_idxs: Vec<i32> = <[Option<TickArrayFacade>; 6]>::from(pool_state.current_tick_array.clone())
+        .into_iter()
+        .flatten()
+        .map(|tick_array| tick_array.start_tick_index)
+        .collect();
+    assert_eq!(start_idxs, (-2..=3).map(|offset| pool.tick_array_start_index(offset)).collect::<Vec<i32>>());
+    assert_eq!(pool_state.metadata.tick_array_window, TickArrayWindow::new(2, 3).unwrap());
+    assert_eq!(pool_state.metadata.tick_arrays.len(), 6);
+}
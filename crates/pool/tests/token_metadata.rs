@@ -0,0 +1,78 @@
+// Pools are labelled by their mints' symbols, falling back to shortened pubkeys, and local token
+// metadata overrides relabel both mints of a loaded pool
+
+mod common;
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account, pubkey::Pubkey
+};
+
+use error::error::OrcaQuoterError;
+use mint::structs::{
+    TokenMetadataOverrides, TokenMetadataSource
+};
+use pool::{
+    pool_state::populate_pool_states, structs::{PoolState, TickArrayWindow}
+};
+use provider::structs::InMemoryAccountProvider;
+
+use common::{
+    TestPool, TICK_SPACING
+};
+
+/// Load a pool whose mints have no on-chain metadata
+fn load_pool() -> (TestPool, PoolState) {
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    let pool: TestPool = TestPool::insert(&mut accounts, 0, &[-1, 0, 1], 0, None, 9, 6);
+    let mut pool_states: Vec<(Pubkey, Result<PoolState, OrcaQuoterError>)> = populate_pool_states(
+        &InMemoryAccountProvider::from(accounts), &[pool.pool_pubkey], TickArrayWindow::default(), true, false, true, None
+    ).unwrap();
+    let pool_state: PoolState = pool_states.remove(0).1.unwrap();
+    (pool, pool_state)
+}
+
+/// First and last four characters of a pubkey, as MintData::label prints mints without a symbol
+fn short_pubkey(pubkey: &Pubkey) -> String {
+    let pubkey: String = pubkey.to_string();
+    format!("{}..{}", &pubkey[..4], &pubkey[pubkey.len() - 4..])
+}
+
+#[test]
+fn pools_without_metadata_are_labelled_by_shortened_pubkeys() {
+    let (pool, pool_state): (TestPool, PoolState) = load_pool();
+
+    assert_eq!(pool_state.mint_a_data.symbol, None);
+    assert_eq!(
+        pool_state.pair_label(),
+        format!("{}/{} ts={}", short_pubkey(&pool.mint_a), short_pubkey(&pool.mint_b), TICK_SPACING)
+    );
+}
+
+#[test]
+fn overrides_relabel_both_mints() {
+    let (pool, mut pool_state): (TestPool, PoolState) = load_pool();
+    let overrides: TokenMetadataOverrides = TokenMetadataOverrides::from_json_str(&format!(
+        r#"{{"{}": {{"symbol": "SOL", "name": "Wrapped SOL"}}, "{}": {{"symbol": "USDC"}}}}"#, pool.mint_a, pool.mint_b
+    )).unwrap();
+    pool_state.apply_token_metadata_overrides(&overrides);
+
+    assert_eq!(pool_state.pair_label(), format!("SOL/USDC ts={}", TICK_SPACING));
+    assert_eq!(pool_state.mint_a_data.name.as_deref(), Some("Wrapped SOL"));
+    assert_eq!(pool_state.mint_a_data.metadata_source, Some(TokenMetadataSource::Override));
+    assert_eq!(pool_state.mint_b_data.name, None);
+    assert_eq!(pool_state.mint_b_data.metadata_source, Some(TokenMetadataSource::Override));
+}
+
+#[test]
+fn mints_without_an_override_keep_their_label() {
+    let (pool, mut pool_state): (TestPool, PoolState) = load_pool();
+    let overrides: TokenMetadataOverrides = TokenMetadataOverrides::from_json_str(&format!(
+        r#"{{"{}": {{"symbol": "USDC"}}, "{}": {{"symbol": "BONK"}}}}"#, pool.mint_b, Pubkey::new_unique()
+    )).unwrap();
+    pool_state.apply_token_metadata_overrides(&overrides);
+
+    assert_eq!(pool_state.pair_label(), format!("{}/USDC ts={}", short_pubkey(&pool.mint_a), TICK_SPACING));
+    assert_eq!(pool_state.mint_a_data.metadata_source, None);
+}
@@ -0,0 +1,250 @@
+// update_pool_states_via_websocket against a local mock websocket server replaying the recorded
+// accountNotification frames in tests/fixtures/websocket/notifications.json
+
+mod common;
+
+use std::collections::{
+    HashMap, HashSet
+};
+use std::fs;
+use std::path::{
+    Path, PathBuf
+};
+use std::str::FromStr;
+
+use futures::{
+    SinkExt, StreamExt
+};
+use serde_json::{
+    json, Value
+};
+use solana_account_decoder::{
+    UiAccount, UiAccountEncoding
+};
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+use orca_whirlpools_core::TickArrayFacade;
+
+use error::error::OrcaQuoterError;
+use pool::{
+    pool_state::populate_pool_states_async, structs::{ListenerError, PoolState, TickArrayWindow},
+    websocket::update_pool_states_via_websocket
+};
+use provider::{
+    nonblocking::BlockingAccountProvider, structs::InMemoryAccountProvider
+};
+
+use common::{
+    TestPool, tick_array_account, tick_array_pubkey, whirlpool_account
+};
+
+const NOTIFICATION_SLOT: u64 = 300_000_000;
+const TICK_CURRENT_INDEX: i32 = 100;
+// Crosses from the tick array starting at 0 into the one starting at 5632
+const CROSSED_TICK_INDEX: i32 = 6_000;
+const UPDATED_MARKER: i128 = 777;
+const ENTERED_MARKER: i128 = 888;
+
+/// A recorded notification frame and the account it was sent for
+struct RecordedFrame {
+    account: Pubkey,
+    frame: Value,
+}
+
+/// What the mock server saw, returned once the client disconnects
+#[derive(Default)]
+struct ServerLog {
+    subscribed: Vec<Pubkey>,
+    rejected: Vec<Pubkey>,
+    unsubscribed: Vec<Pubkey>,
+}
+
+/// The recorded pool, with fixed pubkeys so the recorded frames stay valid
+fn recorded_pool() -> TestPool {
+    TestPool {
+        pool_pubkey: Pubkey::new_from_array([21; 32]),
+        mint_a: Pubkey::new_from_array([22; 32]),
+        mint_b: Pubkey::new_from_array([23; 32]),
+        tick_current_index: TICK_CURRENT_INDEX,
+    }
+}
+
+fn frames_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/websocket/notifications.json")
+}
+
+/// The accounts notified, in the order they are sent
+///     1. The current tick array with its first tick updated
+///     2. The whirlpool with tick_current_index crossed into the next tick array
+///     3. The tick array entered by the re-derived window, once it is subscribed to
+fn notified_accounts() -> Vec<(Pubkey, Account)> {
+    let pool: TestPool = recorded_pool();
+    let (current_start_idx, entered_start_idx): (i32, i32) = (pool.tick_array_start_index(0), pool.tick_array_start_index(2));
+    vec![
+        (
+            tick_array_pubkey(&pool.pool_pubkey, current_start_idx),
+            tick_array_account(&pool.pool_pubkey, current_start_idx, UPDATED_MARKER)
+        ),
+        (pool.pool_pubkey, whirlpool_account(&pool.mint_a, &pool.mint_b, CROSSED_TICK_INDEX)),
+        (
+            tick_array_pubkey(&pool.pool_pubkey, entered_start_idx),
+            tick_array_account(&pool.pool_pubkey, entered_start_idx, ENTERED_MARKER)
+        ),
+    ]
+}
+
+/// Rewrite tests/fixtures/websocket/notifications.json from notified_accounts
+///     - Frames carry subscription id 0, the mock server swaps in the id it assigned to the account
+///     - Run with `cargo test -p pool --test websocket -- --ignored` after changing the recorded pool
+#[test]
+#[ignore]
+fn write_recorded_frames() {
+    let frames: Vec<Value> = notified_accounts().into_iter()
+        .map(|(pubkey, account)| {
+            let ui_account: UiAccount = UiAccount::encode(&pubkey, &account, UiAccountEncoding::Base64, None, None);
+            json!({
+                "account": pubkey.to_string(),
+                "frame": {
+                    "jsonrpc": "2.0",
+                    "method": "accountNotification",
+                    "params": {
+                        "result": { "context": { "slot": NOTIFICATION_SLOT }, "value": ui_account },
+                        "subscription": 0
+                    }
+                }
+            })
+        })
+        .collect();
+    fs::create_dir_all(frames_path().parent().unwrap()).unwrap();
+    fs::write(frames_path(), serde_json::to_string_pretty(&frames).unwrap() + "\n").unwrap();
+}
+
+fn recorded_frames() -> Vec<RecordedFrame> {
+    let frames: Vec<Value> = serde_json::from_str(&fs::read_to_string(frames_path()).unwrap()).unwrap();
+    frames.into_iter()
+        .map(|frame| RecordedFrame {
+            account: Pubkey::from_str(frame["account"].as_str().unwrap()).unwrap(),
+            frame: frame["frame"].clone(),
+        })
+        .collect()
+}
+
+/// Mock pubsub server for a single client
+///     - Answers accountSubscribe with a fresh subscription id, except the first request for reject_once
+///     - Sends each recorded frame, in order, as soon as its account is subscribed
+async fn serve(listener: TcpListener, frames: Vec<RecordedFrame>, reject_once: Pubkey) -> ServerLog {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+    let mut log: ServerLog = ServerLog::default();
+    let mut subscription_ids: HashMap<Pubkey, u64> = HashMap::new();
+    let mut frames = frames.into_iter().peekable();
+
+    while let Some(Ok(message)) = ws.next().await {
+        let Message::Text(text) = message else { continue };
+        let request: Value = serde_json::from_str(&text).unwrap();
+        let response: Value = match request["method"].as_str().unwrap() {
+            "accountSubscribe" => {
+                let account: Pubkey = Pubkey::from_str(request["params"][0].as_str().unwrap()).unwrap();
+                if account == reject_once && !log.rejected.contains(&account) {
+                    log.rejected.push(account);
+                    json!({"jsonrpc": "2.0", "error": {"code": -32603, "message": "Internal error"}, "id": request["id"]})
+                } else {
+                    let subscription_id: u64 = subscription_ids.len() as u64 + 1;
+                    subscription_ids.insert(account, subscription_id);
+                    log.subscribed.push(account);
+                    json!({"jsonrpc": "2.0", "result": subscription_id, "id": request["id"]})
+                }
+            }
+            "accountUnsubscribe" => {
+                let subscription_id: u64 = request["params"][0].as_u64().unwrap();
+                if let Some((account, _)) = subscription_ids.iter().find(|(_, id)| **id == subscription_id) {
+                    log.unsubscribed.push(*account);
+                }
+                json!({"jsonrpc": "2.0", "result": true, "id": request["id"]})
+            }
+            method => panic!("unexpected method {}", method),
+        };
+        ws.send(Message::Text(response.to_string())).await.unwrap();
+
+        while let Some(subscription_id) = frames.peek().and_then(|frame| subscription_ids.get(&frame.account)) {
+            let mut frame: Value = frames.next().unwrap().frame;
+            frame["params"]["subscription"] = json!(subscription_id);
+            ws.send(Message::Text(frame.to_string())).await.unwrap();
+        }
+    }
+    log
+}
+
+fn held_tick_arrays(pool_state: &PoolState) -> Vec<TickArrayFacade> {
+    <[Option<TickArrayFacade>; 6]>::from(pool_state.current_tick_array.clone()).into_iter().flatten().collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replays_recorded_notifications_and_rederives_the_window() {
+    let pool: TestPool = recorded_pool();
+    let frames: Vec<RecordedFrame> = recorded_frames();
+
+    // The chain holds every tick array the pool can enter, the current one already at its notified
+    // state so the window refresh agrees with the notification whichever is applied first
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    pool.insert_accounts(&mut accounts, &[-1, 0, 1, 2], 0, None, 6, 6);
+    accounts.extend(notified_accounts().into_iter().take(1));
+    let provider: InMemoryAccountProvider = InMemoryAccountProvider::from(accounts);
+    let provider: BlockingAccountProvider<InMemoryAccountProvider> = BlockingAccountProvider(&provider);
+
+    let mut pool_states: HashMap<Pubkey, PoolState> = populate_pool_states_async(
+        &provider, &[pool.pool_pubkey], TickArrayWindow::default(), true, true, true, None
+    ).await.unwrap()
+        .into_iter()
+        .map(|(pool_pubkey, pool_state)| (pool_pubkey, pool_state.unwrap()))
+        .collect();
+    let held_start_idxs: Vec<i32> = held_tick_arrays(&pool_states[&pool.pool_pubkey]).iter()
+        .map(|tick_array| tick_array.start_tick_index)
+        .collect();
+    assert_eq!(held_start_idxs, vec![-5_632, 0, 5_632]);
+
+    // Serve the recorded frames, rejecting the first subscription to the entered tick array
+    let entered_pubkey: Pubkey = tick_array_pubkey(&pool.pool_pubkey, pool.tick_array_start_index(2));
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url: String = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve(listener, frames, entered_pubkey));
+
+    let mut listener_errors: Vec<ListenerError> = Vec::new();
+    let updates_applied: Result<usize, OrcaQuoterError> = update_pool_states_via_websocket(
+        &ws_url, &provider, &mut pool_states, CommitmentConfig::confirmed(), Some(3),
+        |listener_error| listener_errors.push(listener_error)
+    ).await;
+    assert_eq!(updates_applied, Ok(3));
+
+    // The rejected subscription was passed to the callback rather than printed, and nothing else failed
+    assert_eq!(listener_errors.len(), 1);
+    assert!(matches!(
+        &listener_errors[0], ListenerError::Subscription { account, error: OrcaQuoterError::Rpc(_) } if *account == entered_pubkey
+    ));
+
+    // The whirlpool moved, and the window was re-derived one tick array to the right
+    let pool_state: &PoolState = &pool_states[&pool.pool_pubkey];
+    assert_eq!(pool_state.whirlpool.tick_current_index, CROSSED_TICK_INDEX);
+    assert_eq!(pool_state.metadata.whirlpool.slot, NOTIFICATION_SLOT);
+    let tick_arrays: Vec<TickArrayFacade> = held_tick_arrays(pool_state);
+    let start_idxs: Vec<i32> = tick_arrays.iter().map(|tick_array| tick_array.start_tick_index).collect();
+    assert_eq!(start_idxs, vec![0, 5_632, 11_264]);
+
+    // Both tick array notifications were applied, the entered one after its retried subscription
+    assert_eq!(tick_arrays[0].ticks[0].liquidity_net, UPDATED_MARKER);
+    assert_eq!(tick_arrays[2].ticks[0].liquidity_net, ENTERED_MARKER);
+    assert_eq!(pool_state.metadata.tick_arrays[2].endpoint, ws_url);
+
+    // The tick array that left the window was unsubscribed from
+    let log: ServerLog = server.await.unwrap();
+    let left_pubkey: Pubkey = tick_array_pubkey(&pool.pool_pubkey, pool.tick_array_start_index(-1));
+    assert_eq!(log.rejected, vec![entered_pubkey]);
+    assert!(log.subscribed.contains(&entered_pubkey));
+    assert!(log.unsubscribed.contains(&left_pubkey));
+    let subscribed: HashSet<Pubkey> = log.subscribed.iter().copied().collect();
+    assert_eq!(subscribed.len(), log.subscribed.len());
+}
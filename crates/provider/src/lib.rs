@@ -0,0 +1,3 @@
+pub mod provider;
+pub mod nonblocking;
+pub mod structs;
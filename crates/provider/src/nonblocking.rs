@@ -0,0 +1,155 @@
+// Async account provider utils ///
+
+use std::future::Future;
+
+use futures::{
+    stream, StreamExt, TryStreamExt
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+
+use error::error::OrcaQuoterError;
+
+use crate::provider::{
+    AccountProvider, MAX_ACCOUNTS_PER_REQUEST, get_multiple_accounts_paged, spread_slot
+};
+use crate::structs::AtSlot;
+
+/// Async source of on-chain accounts, the counterpart of AccountProvider
+///     - Implemented for the nonblocking RpcClient, and for any AccountProvider through 
+///       BlockingAccountProvider
+///     - The returned futures are Send, so loaders built on them can be handed to tokio::spawn on a 
+///       multi threaded runtime
+pub trait AsyncAccountProvider: Sync {
+    /// Fetch a single account
+    /// 
+    /// Parameters: 
+    ///     - pubkey: The account's pubkey
+    /// 
+    /// Returns: 
+    ///     - Some(Account), None if the account does not exist, at the slot it was read at, 
+    ///       or an OrcaQuoterError
+    fn get_account(&self, pubkey: &Pubkey) -> impl Future<Output = Result<AtSlot<Option<Account>>, OrcaQuoterError>> + Send;
+
+    /// Fetch at most MAX_ACCOUNTS_PER_REQUEST accounts, order preserving
+    /// 
+    /// Parameters: 
+    ///     - pubkeys: The accounts' pubkeys
+    /// 
+    /// Returns: 
+    ///     - A vector of Option<Account> in the order of pubkeys, at the slot they were read at, 
+    ///       or an OrcaQuoterError
+    fn get_multiple_accounts(
+        &self, pubkeys: &[Pubkey]
+    ) -> impl Future<Output = Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError>> + Send;
+
+    /// Current slot of the provider
+    /// 
+    /// Returns: 
+    ///     - The slot or an OrcaQuoterError
+    fn get_slot(&self) -> impl Future<Output = Result<u64, OrcaQuoterError>> + Send;
+
+    /// Commitment level accounts are read at
+    /// 
+    /// Returns: 
+    ///     - The CommitmentConfig
+    fn commitment(&self) -> CommitmentConfig;
+
+    /// Label of the endpoint accounts are read from, e.g. the RPC url
+    /// 
+    /// Returns: 
+    ///     - The endpoint label
+    fn endpoint(&self) -> String;
+
+    /// Fetch any number of accounts, paging the pubkeys into <= 100 key requests with up to 
+    /// max_concurrency pages in flight at once
+    /// 
+    /// Parameters: 
+    ///     - pubkeys: The accounts' pubkeys, of any length
+    ///     - max_concurrency: the maximum number of pages in flight at once, None (or 1) awaits 
+    ///       pages one after another
+    /// 
+    /// Returns: 
+    ///     - A vector of Option<Account> in the order of pubkeys, each at the slot its page was 
+    ///       read at, or the first failed page's OrcaQuoterError
+    fn get_multiple_accounts_paged(
+        &self, pubkeys: &[Pubkey], max_concurrency: Option<usize>
+    ) -> impl Future<Output = Result<Vec<AtSlot<Option<Account>>>, OrcaQuoterError>> + Send {
+        async move {
+            let concurrency: usize = max_concurrency.unwrap_or(1).max(1);
+            // Futures are lazy, so creating every page's future up front sends no request, collecting 
+            // them first keeps the stream free of a closure the Send bound cannot see through
+            let page_futures: Vec<_> = pubkeys.chunks(MAX_ACCOUNTS_PER_REQUEST)
+                .map(|page| self.get_multiple_accounts(page))
+                .collect();
+            // buffered keeps the pages in order while polling up to concurrency of them at once
+            let pages: Vec<AtSlot<Vec<Option<Account>>>> = stream::iter(page_futures)
+                .buffered(concurrency)
+                .try_collect()
+                .await?;
+            Ok(pages.into_iter().flat_map(spread_slot).collect())
+        }
+    }
+}
+
+impl AsyncAccountProvider for RpcClient {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        // get_account_with_commitment returns Ok(None) for a missing account, so transport 
+        // errors and missing accounts stay distinguishable
+        let response = self.get_account_with_commitment(pubkey, RpcClient::commitment(self)).await?;
+        Ok(AtSlot { slot: response.context.slot, value: response.value })
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        let response = self.get_multiple_accounts_with_commitment(pubkeys, RpcClient::commitment(self)).await?;
+        Ok(AtSlot { slot: response.context.slot, value: response.value })
+    }
+
+    async fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        Ok(RpcClient::get_slot(self).await?)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        RpcClient::commitment(self)
+    }
+
+    fn endpoint(&self) -> String {
+        self.url()
+    }
+}
+
+
+/// Adapter running a blocking AccountProvider behind the AsyncAccountProvider interface
+///     - Every call blocks the polling thread, paged fetches keep the thread based concurrency 
+///       of get_multiple_accounts_paged
+pub struct BlockingAccountProvider<'a, P: ?Sized>(pub &'a P);
+
+impl<P: AccountProvider + Sync + ?Sized> AsyncAccountProvider for BlockingAccountProvider<'_, P> {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        self.0.get_account(pubkey)
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        self.0.get_multiple_accounts(pubkeys)
+    }
+
+    async fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        self.0.get_slot()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.0.commitment()
+    }
+
+    fn endpoint(&self) -> String {
+        self.0.endpoint()
+    }
+
+    async fn get_multiple_accounts_paged(
+        &self, pubkeys: &[Pubkey], max_concurrency: Option<usize>
+    ) -> Result<Vec<AtSlot<Option<Account>>>, OrcaQuoterError> {
+        get_multiple_accounts_paged(self.0, pubkeys, max_concurrency)
+    }
+}
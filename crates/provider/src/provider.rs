@@ -0,0 +1,162 @@
+// Account provider utils ///
+
+use std::any::Any;
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+
+use error::error::OrcaQuoterError;
+
+use crate::structs::AtSlot;
+
+/// Maximum number of accounts the getMultipleAccounts RPC method accepts per request
+pub const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Source of on-chain accounts for the pool and mint loaders
+///     - Implemented for RpcClient, InMemoryAccountProvider and FileAccountProvider, so the 
+///       whole pipeline can run offline against recorded accounts
+pub trait AccountProvider {
+    /// Fetch a single account
+    /// 
+    /// Parameters: 
+    ///     - pubkey: The account's pubkey
+    /// 
+    /// Returns: 
+    ///     - Some(Account), None if the account does not exist, at the slot it was read at, 
+    ///       or an OrcaQuoterError
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError>;
+
+    /// Fetch several accounts, order preserving
+    ///     - Implementations may reject more than MAX_ACCOUNTS_PER_REQUEST pubkeys, use 
+    ///       get_multiple_accounts_paged for arbitrary lengths
+    /// 
+    /// Parameters: 
+    ///     - pubkeys: The accounts' pubkeys
+    /// 
+    /// Returns: 
+    ///     - A vector of Option<Account> in the order of pubkeys, at the slot they were read at, 
+    ///       or an OrcaQuoterError
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError>;
+
+    /// Current slot of the provider
+    /// 
+    /// Returns: 
+    ///     - The slot or an OrcaQuoterError
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError>;
+
+    /// Commitment level accounts are read at
+    /// 
+    /// Returns: 
+    ///     - The CommitmentConfig
+    fn commitment(&self) -> CommitmentConfig;
+
+    /// Label of the endpoint accounts are read from, e.g. the RPC url
+    /// 
+    /// Returns: 
+    ///     - The endpoint label
+    fn endpoint(&self) -> String;
+}
+
+impl AccountProvider for RpcClient {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        // get_account_with_commitment returns Ok(None) for a missing account, so transport 
+        // errors and missing accounts stay distinguishable
+        let response = self.get_account_with_commitment(pubkey, RpcClient::commitment(self))?;
+        Ok(AtSlot { slot: response.context.slot, value: response.value })
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        let response = self.get_multiple_accounts_with_commitment(pubkeys, RpcClient::commitment(self))?;
+        Ok(AtSlot { slot: response.context.slot, value: response.value })
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        Ok(RpcClient::get_slot(self)?)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        RpcClient::commitment(self)
+    }
+
+    fn endpoint(&self) -> String {
+        self.url()
+    }
+}
+
+
+/// Fetch Vec<Option<Account>> via account provider, paging the pubkeys into <= 100 key 
+/// get_multiple_accounts requests
+/// 
+/// Parameters:
+///     - provider: a pointer to an account provider
+///     - pubkeys: the account pubkeys to fetch, of any length
+///     - max_concurrency: the maximum number of pages in flight at once, None (or 1) fetches 
+///       pages sequentially on the calling thread
+/// 
+/// Returns:
+///     - A vector of Option<Account> structs in the order of pubkeys, each at the slot its page 
+///       was read at, or the first failed page's OrcaQuoterError, a page whose thread panicked 
+///       fails with OrcaQuoterError::ProviderPanic
+pub fn get_multiple_accounts_paged<P: AccountProvider + Sync + ?Sized>(
+    provider: &P, pubkeys: &[Pubkey], max_concurrency: Option<usize>
+) -> Result<Vec<AtSlot<Option<Account>>>, OrcaQuoterError> {
+    let pages: Vec<&[Pubkey]> = pubkeys.chunks(MAX_ACCOUNTS_PER_REQUEST).collect();
+    let concurrency: usize = max_concurrency.unwrap_or(1).max(1);
+
+    let mut accounts: Vec<AtSlot<Option<Account>>> = Vec::with_capacity(pubkeys.len());
+    for batch in pages.chunks(concurrency) {
+        // Single page batches skip the thread spawn
+        if batch.len() == 1 {
+            accounts.extend(spread_slot(provider.get_multiple_accounts(batch[0])?));
+            continue;
+        }
+
+        // Fetch every page of the batch on its own scoped thread, joining in page order
+        let batch_results: Vec<Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = batch.iter()
+                .map(|page| scope.spawn(move || provider.get_multiple_accounts(page)))
+                .collect();
+            handles.into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|payload| Err(OrcaQuoterError::ProviderPanic(
+                    format!("get_multiple_accounts page thread panicked: {}", panic_message(payload.as_ref()))
+                ))))
+                .collect()
+        });
+        for page_result in batch_results {
+            accounts.extend(spread_slot(page_result?));
+        }
+    }
+
+    Ok(accounts)
+}
+
+
+/// Attach a page's slot to every account of the page
+/// 
+/// Parameters:
+///     - page: the accounts of one get_multiple_accounts request, at the slot they were read at
+/// 
+/// Returns:
+///     - An iterator of per account AtSlot<Option<Account>>
+pub fn spread_slot(page: AtSlot<Vec<Option<Account>>>) -> impl Iterator<Item = AtSlot<Option<Account>>> {
+    let slot: u64 = page.slot;
+    page.value.into_iter().map(move |account| AtSlot { slot, value: account })
+}
+
+
+/// Message of a caught panic, panics raised with a format string carry a String, literals a &str
+/// 
+/// Parameters:
+///     - payload: the payload returned by a failed thread join
+/// 
+/// Returns:
+///     - The panic message, or a placeholder for non string payloads
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "non string panic payload".to_string(),
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{
+    Path, PathBuf
+};
+use std::str::FromStr;
+
+use serde::Deserialize;
+use solana_account_decoder::UiAccount;
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+
+use error::error::OrcaQuoterError;
+
+use crate::provider::AccountProvider;
+
+/// A value read by an account provider, with the slot it was read at
+///     - For RPC providers this is the context.slot of the response
+#[derive(Clone, Debug, PartialEq)]
+pub struct AtSlot<T> {
+    pub slot: u64,
+    pub value: T,
+}
+
+/// In-memory account provider, e.g. for unit tests with hand built or recorded accounts
+///     - Missing pubkeys are reported as non-existent accounts, every read is reported at the fixed 
+///       slot and commitment
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryAccountProvider {
+    pub accounts: HashMap<Pubkey, Account>,
+    pub slot: u64,
+    pub commitment: CommitmentConfig,
+}
+
+impl From<HashMap<Pubkey, Account>> for InMemoryAccountProvider {
+    fn from(accounts: HashMap<Pubkey, Account>) -> Self {
+        InMemoryAccountProvider { accounts, slot: 0, commitment: CommitmentConfig::default() }
+    }
+}
+
+impl AccountProvider for InMemoryAccountProvider {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        Ok(AtSlot { slot: self.slot, value: self.accounts.get(pubkey).cloned() })
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        Ok(AtSlot { 
+            slot: self.slot, 
+            value: pubkeys.iter().map(|pubkey| self.accounts.get(pubkey).cloned()).collect() 
+        })
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        Ok(self.slot)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.commitment
+    }
+
+    fn endpoint(&self) -> String {
+        "in-memory".to_string()
+    }
+}
+
+
+/// Account provider backed by a directory of recorded account fixtures
+///     - Every *.json file holds one account in the format written by 
+///       `solana account <PUBKEY> --output json`, i.e. {"pubkey": ..., "account": UiAccount}
+///     - All fixtures are loaded eagerly into an InMemoryAccountProvider
+#[derive(Clone, Debug, Default)]
+pub struct FileAccountProvider {
+    pub inner: InMemoryAccountProvider,
+    pub dir: PathBuf,
+}
+
+/// Serde layout of a single account fixture file
+#[derive(Deserialize)]
+struct AccountFixture {
+    pubkey: String,
+    account: UiAccount,
+}
+
+impl FileAccountProvider {
+    /// Load every *.json account fixture in a directory
+    /// 
+    /// Parameters: 
+    ///     - dir: The fixture directory
+    ///     - slot: The slot the fixtures were recorded at, every read is reported at this slot
+    /// 
+    /// Returns: 
+    ///     - A populated FileAccountProvider or an OrcaQuoterError
+    pub fn from_dir(dir: &Path, slot: u64) -> Result<FileAccountProvider, OrcaQuoterError> {
+        let entries = fs::read_dir(dir).map_err(|err| OrcaQuoterError::Io(
+            format!("Failed to read fixture directory {}: {}", dir.display(), err)
+        ))?;
+
+        let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+        for entry in entries {
+            let path = entry.map_err(|err| OrcaQuoterError::Io(err.to_string()))?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let (pubkey, account): (Pubkey, Account) = load_account_fixture(&path)?;
+            accounts.insert(pubkey, account);
+        }
+
+        Ok(FileAccountProvider { 
+            inner: InMemoryAccountProvider { accounts, slot, commitment: CommitmentConfig::default() }, 
+            dir: dir.to_path_buf(),
+        })
+    }
+}
+
+impl AccountProvider for FileAccountProvider {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        self.inner.get_account(pubkey)
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        self.inner.get_multiple_accounts(pubkeys)
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        self.inner.get_slot()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.inner.commitment()
+    }
+
+    fn endpoint(&self) -> String {
+        format!("file://{}", self.dir.display())
+    }
+}
+
+
+/// Load and decode a single account fixture file
+/// 
+/// Parameters: 
+///     - path: The fixture file's path
+/// 
+/// Returns: 
+///     - The (pubkey, Account) pair or an OrcaQuoterError
+fn load_account_fixture(path: &Path) -> Result<(Pubkey, Account), OrcaQuoterError> {
+    let contents: String = fs::read_to_string(path).map_err(|err| OrcaQuoterError::Io(
+        format!("Failed to read fixture {}: {}", path.display(), err)
+    ))?;
+    let fixture: AccountFixture = serde_json::from_str(&contents).map_err(|err| OrcaQuoterError::Deserialisation(
+        format!("Failed to parse fixture {}: {}", path.display(), err)
+    ))?;
+    let pubkey: Pubkey = Pubkey::from_str(&fixture.pubkey).map_err(|err| OrcaQuoterError::Deserialisation(
+        format!("Invalid pubkey in fixture {}: {}", path.display(), err)
+    ))?;
+    // Fixtures must be recorded with a binary encoding, jsonParsed data cannot be decoded
+    let account: Account = fixture.account.decode().ok_or(OrcaQuoterError::Deserialisation(
+        format!("Failed to decode account data in fixture {}", path.display())
+    ))?;
+    Ok((pubkey, account))
+}
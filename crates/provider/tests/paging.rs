@@ -0,0 +1,179 @@
+// get_multiple_accounts_paged must split any number of pubkeys into <= 100 key pages and
+// return the accounts in input order, whatever order the pages complete in
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::executor::block_on;
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, commitment_config::CommitmentConfig
+};
+
+use error::error::OrcaQuoterError;
+use provider::{
+    provider::{AccountProvider, MAX_ACCOUNTS_PER_REQUEST, get_multiple_accounts_paged},
+    nonblocking::AsyncAccountProvider,
+    structs::{AtSlot, InMemoryAccountProvider}
+};
+
+const ACCOUNT_COUNT: usize = 250;
+
+/// In-memory provider recording the size of every get_multiple_accounts request
+struct CountingProvider {
+    inner: InMemoryAccountProvider,
+    page_sizes: Mutex<Vec<usize>>,
+}
+
+impl CountingProvider {
+    fn page_sizes(&self) -> Vec<usize> {
+        let mut page_sizes: Vec<usize> = self.page_sizes.lock().unwrap().clone();
+        page_sizes.sort_unstable();
+        page_sizes
+    }
+}
+
+impl AccountProvider for CountingProvider {
+    fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        self.inner.get_account(pubkey)
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        // Reject oversized pages the way the RPC node does
+        if pubkeys.len() > MAX_ACCOUNTS_PER_REQUEST {
+            return Err(OrcaQuoterError::RpcRejected(format!("Too many inputs provided, max {}", MAX_ACCOUNTS_PER_REQUEST)));
+        }
+        self.page_sizes.lock().unwrap().push(pubkeys.len());
+        self.inner.get_multiple_accounts(pubkeys)
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        self.inner.get_slot()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        self.inner.commitment()
+    }
+
+    fn endpoint(&self) -> String {
+        "counting".to_string()
+    }
+}
+
+// Implemented directly, not through BlockingAccountProvider, so the trait's default paging is exercised
+impl AsyncAccountProvider for CountingProvider {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        AccountProvider::get_account(self, pubkey)
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        AccountProvider::get_multiple_accounts(self, pubkeys)
+    }
+
+    async fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        AccountProvider::get_slot(self)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        AccountProvider::commitment(self)
+    }
+
+    fn endpoint(&self) -> String {
+        AccountProvider::endpoint(self)
+    }
+}
+
+/// Provider whose every request panics, e.g. a buggy custom account source
+struct PanickingProvider;
+
+impl AccountProvider for PanickingProvider {
+    fn get_account(&self, _pubkey: &Pubkey) -> Result<AtSlot<Option<Account>>, OrcaQuoterError> {
+        panic!("get_account is not supported")
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<AtSlot<Vec<Option<Account>>>, OrcaQuoterError> {
+        panic!("page of {} pubkeys failed", pubkeys.len())
+    }
+
+    fn get_slot(&self) -> Result<u64, OrcaQuoterError> {
+        Ok(0)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        CommitmentConfig::default()
+    }
+
+    fn endpoint(&self) -> String {
+        "panicking".to_string()
+    }
+}
+
+/// ACCOUNT_COUNT pubkeys with every seventh account missing, each present account's lamports
+/// is its index in the pubkeys
+fn counting_provider() -> (CountingProvider, Vec<Pubkey>) {
+    let pubkeys: Vec<Pubkey> = (0..ACCOUNT_COUNT).map(|_| Pubkey::new_unique()).collect();
+    let accounts: HashMap<Pubkey, Account> = pubkeys.iter()
+        .enumerate()
+        .filter(|(idx, _)| idx % 7 != 0)
+        .map(|(idx, pubkey)| (*pubkey, Account { lamports: idx as u64, ..Account::default() }))
+        .collect();
+    let provider: CountingProvider = CountingProvider {
+        inner: InMemoryAccountProvider::from(accounts),
+        page_sizes: Mutex::new(Vec::new()),
+    };
+    (provider, pubkeys)
+}
+
+/// Check that the accounts line up with the pubkeys they were requested for
+fn assert_in_input_order(accounts: &[AtSlot<Option<Account>>]) {
+    assert_eq!(accounts.len(), ACCOUNT_COUNT);
+    for (idx, account) in accounts.iter().enumerate() {
+        match &account.value {
+            Some(account) => assert_eq!(account.lamports, idx as u64),
+            None => assert_eq!(idx % 7, 0, "account {} is missing", idx),
+        }
+    }
+}
+
+#[test]
+fn pages_sequentially_in_input_order() {
+    let (provider, pubkeys): (CountingProvider, Vec<Pubkey>) = counting_provider();
+    let accounts: Vec<AtSlot<Option<Account>>> = get_multiple_accounts_paged(&provider, &pubkeys, None).unwrap();
+    assert_in_input_order(&accounts);
+    assert_eq!(provider.page_sizes(), vec![50, 100, 100]);
+}
+
+#[test]
+fn pages_concurrently_in_input_order() {
+    let (provider, pubkeys): (CountingProvider, Vec<Pubkey>) = counting_provider();
+    let accounts: Vec<AtSlot<Option<Account>>> = get_multiple_accounts_paged(&provider, &pubkeys, Some(2)).unwrap();
+    assert_in_input_order(&accounts);
+    assert_eq!(provider.page_sizes(), vec![50, 100, 100]);
+}
+
+#[test]
+fn async_pages_in_input_order() {
+    let (provider, pubkeys): (CountingProvider, Vec<Pubkey>) = counting_provider();
+    let accounts: Vec<AtSlot<Option<Account>>> = block_on(
+        AsyncAccountProvider::get_multiple_accounts_paged(&provider, &pubkeys, Some(3))
+    ).unwrap();
+    assert_in_input_order(&accounts);
+    assert_eq!(provider.page_sizes(), vec![50, 100, 100]);
+}
+
+#[test]
+fn empty_pubkeys_send_no_request() {
+    let (provider, _): (CountingProvider, Vec<Pubkey>) = counting_provider();
+    assert!(get_multiple_accounts_paged(&provider, &[], Some(4)).unwrap().is_empty());
+    assert!(provider.page_sizes().is_empty());
+}
+
+#[test]
+fn a_panicking_page_thread_is_an_error() {
+    let pubkeys: Vec<Pubkey> = (0..ACCOUNT_COUNT).map(|_| Pubkey::new_unique()).collect();
+    let err: OrcaQuoterError = get_multiple_accounts_paged(&PanickingProvider, &pubkeys, Some(3)).unwrap_err();
+    match err {
+        OrcaQuoterError::ProviderPanic(message) => assert!(message.contains("page of 100 pubkeys failed"), "{}", message),
+        err => panic!("expected ProviderPanic, found {:?}", err),
+    }
+    assert!(!OrcaQuoterError::ProviderPanic(String::new()).is_transient());
+}
@@ -0,0 +1,452 @@
+// Depth ladders, inverse quotes and post swap prices over a pool's loaded tick arrays ///
+
+use std::fmt;
+
+use serde::{
+    Deserialize, Serialize
+};
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::{
+    sqrt_price_to_price, sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_get_amount_delta_a, 
+    try_get_amount_delta_b, try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b, 
+    try_apply_transfer_fee, try_reverse_apply_transfer_fee, TickArraySequence, TickFacade, MIN_TICK_INDEX, 
+    MAX_TICK_INDEX, MIN_SQRT_PRICE, MAX_SQRT_PRICE, ARITHMETIC_OVERFLOW, INVALID_TICK_ARRAY_SEQUENCE
+};
+
+use pool::structs::PoolState;
+use mint::{
+    structs::MintData, mint::format_ui_amount
+};
+
+use crate::structs::swap_direction;
+
+use error::error::OrcaQuoterError;
+
+// Core fee rates are in millionths
+const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+
+/// A price level of a DepthSide, reached by moving the pool price to a tick
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub tick_index: i32,
+    pub sqrt_price: u128,
+    // Price of token A in UI units of token B, as for sqrt_price_to_price
+    pub price: f64,
+    // liquidity_net of the tick, 0 at the window boundary where no tick is crossed
+    pub liquidity_net: i128,
+    // Active liquidity once the price has moved past the tick
+    pub liquidity_after: u128,
+    // Input reaching the curve to move the price here, excluding fees
+    pub cumulative_in: u64,
+    // cumulative_in grossed up by the pool's static fee rate, adaptive fees come on top
+    pub cumulative_in_with_fee: u64,
+    pub cumulative_out: u64,
+}
+
+/// One direction of a DepthLadder
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DepthSide {
+    // true if token A is swapped in and the price moves down, false if token B is swapped in
+    pub a_to_b: bool,
+    // One level per initialised tick, in the order the price reaches them
+    pub levels: Vec<DepthLevel>,
+    // The edge of the loaded tick arrays (or of the tick range), cumulative_in here is the largest 
+    // input the loaded window can quote in this direction
+    pub exhausted_at: DepthLevel,
+}
+
+/// Depth of a pool in both directions over its loaded tick arrays
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DepthLadder {
+    pub pool_label: String,
+    pub mint_a_label: String,
+    pub mint_b_label: String,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    pub tick_current_index: i32,
+    // Price of token A in UI units of token B before any swap
+    pub mid_price: f64,
+    pub a_to_b: DepthSide,
+    pub b_to_a: DepthSide,
+}
+
+/// Pretty printing for DepthLadder, one line per level, selling A first
+impl fmt::Display for DepthLadder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "DepthLadder {} {{", self.pool_label)?;
+        writeln!(
+            f, "  mid_price:    {:.6} {} per {} (tick {})", 
+            self.mid_price, self.mint_b_label, self.mint_a_label, self.tick_current_index
+        )?;
+        for side in [&self.a_to_b, &self.b_to_a] {
+            let (input_label, input_decimals, output_label, output_decimals) = match side.a_to_b {
+                true => (&self.mint_a_label, self.decimals_a, &self.mint_b_label, self.decimals_b),
+                false => (&self.mint_b_label, self.decimals_b, &self.mint_a_label, self.decimals_a),
+            };
+            writeln!(f, "  sell {} for {}:", input_label, output_label)?;
+            for level in side.levels.iter().chain([&side.exhausted_at]) {
+                writeln!(
+                    f, "    tick {:>7}  price {:<14.6} in {:>20} {} (with fee {}) out {:>20} {}  liquidity {}{}", 
+                    level.tick_index, level.price, 
+                    format_ui_amount(level.cumulative_in, input_decimals), input_label, 
+                    format_ui_amount(level.cumulative_in_with_fee, input_decimals),
+                    format_ui_amount(level.cumulative_out, output_decimals), output_label, 
+                    level.liquidity_after, 
+                    if std::ptr::eq(level, &side.exhausted_at) { "  (window exhausted)" } else { "" }
+                )?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// The largest input a pool takes before its price reaches a target, see max_input_for_price_impact
+///     - Adaptive fees are not included, pools with an oracle may charge more than max_amount_in
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InverseQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub a_to_b: bool,
+    // The target sqrt price and its price of token A in UI units of token B
+    pub target_sqrt_price: u128,
+    pub target_price: f64,
+    // Input reaching the curve, excluding fees
+    pub curve_amount_in: u64,
+    // Input to send, including the static trade fee and the input mint's transfer fee
+    pub max_amount_in: u64,
+    // Estimated output received, after the output mint's transfer fee
+    pub amount_out_est: u64,
+    // true if the loaded tick arrays end before the target, i.e. the pool may take more than 
+    // max_amount_in but the window cannot tell
+    pub limited_by_window: bool,
+    pub input_label: String,
+    pub output_label: String,
+    pub input_decimals: u8,
+    pub output_decimals: u8,
+}
+
+/// Pretty printing for InverseQuote
+impl fmt::Display for InverseQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "InverseQuote {{")?;
+        writeln!(f, "  input_mint:   {} ({})", self.input_label, self.input_mint)?;
+        writeln!(f, "  output_mint:  {} ({})", self.output_label, self.output_mint)?;
+        writeln!(f, "  target_price: {:.6} (sqrt price {})", self.target_price, self.target_sqrt_price)?;
+        writeln!(
+            f, "  max size:     sell {} {} → {} {}{}", 
+            format_ui_amount(self.max_amount_in, self.input_decimals), self.input_label, 
+            format_ui_amount(self.amount_out_est, self.output_decimals), self.output_label, 
+            if self.limited_by_window { " (tick array window exhausted first)" } else { "" }
+        )?;
+        writeln!(f, "  curve in:     {} raw, {} raw with fees", self.curve_amount_in, self.max_amount_in)?;
+        writeln!(f, "}}")
+    }
+}
+
+
+/// Build the depth ladder of a pool in both directions
+///     - Walks the loaded tick arrays from the current price, recording the cumulative input and 
+///       output needed to move the price to each initialised tick, crossing ticks as a swap would
+///
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///
+/// Returns:
+///     - The DepthLadder or OrcaQuoterError::Quote if the tick arrays do not form a valid sequence
+pub fn depth_ladder(pool: &PoolState) -> Result<DepthLadder, OrcaQuoterError> {
+    Ok(DepthLadder {
+        pool_label: pool.pair_label(),
+        mint_a_label: pool.mint_a_data.label(),
+        mint_b_label: pool.mint_b_data.label(),
+        decimals_a: pool.mint_a_data.decimals,
+        decimals_b: pool.mint_b_data.decimals,
+        tick_current_index: pool.whirlpool.tick_current_index,
+        mid_price: sqrt_price_to_price(pool.whirlpool.sqrt_price, pool.mint_a_data.decimals, pool.mint_b_data.decimals),
+        a_to_b: depth_side(pool, true, None)?,
+        b_to_a: depth_side(pool, false, None)?,
+    })
+}
+
+
+/// The largest input a pool takes before its price moves by a given number of basis points
+///     - Computed in closed form tick by tick from the active liquidity, rather than by searching 
+///       over repeated quotes
+///     - The target sqrt price is scaled in f64, which is exact to about 1e-15 relative, far below 
+///       a basis point, use max_input_for_sqrt_price to stop at an exact sqrt price
+///
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - input_mint: a pointer to the mint swapped in, selling mint A moves the price down
+///     - price_impact_bps: the move of the pool price of token A in token B, below 10_000 when selling 
+///       mint A moves it down
+///
+/// Returns:
+///     - The InverseQuote or OrcaQuoterError::InvalidArgument if input_mint is not one of the pool's 
+///       mints or the target price is out of range
+pub fn max_input_for_price_impact(
+    pool: &PoolState, input_mint: &Pubkey, price_impact_bps: u32
+) -> Result<InverseQuote, OrcaQuoterError> {
+    let (a_to_b, _): (bool, Pubkey) = swap_direction(pool, input_mint)?;
+    if a_to_b && price_impact_bps >= 10_000 {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "A price impact of {} bps moves the price to or below zero", price_impact_bps
+        )));
+    }
+    // The price scales with the square of the sqrt price
+    let price_factor: f64 = match a_to_b {
+        true => 1.0 - price_impact_bps as f64 / 10_000.0,
+        false => 1.0 + price_impact_bps as f64 / 10_000.0,
+    };
+    let target_sqrt_price: f64 = pool.whirlpool.sqrt_price as f64 * price_factor.sqrt();
+    let target_sqrt_price: u128 = (target_sqrt_price as u128).clamp(MIN_SQRT_PRICE, MAX_SQRT_PRICE);
+    max_input_for_sqrt_price(pool, input_mint, target_sqrt_price)
+}
+
+
+/// The largest input a pool takes before its sqrt price reaches a target
+///
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - input_mint: a pointer to the mint swapped in, selling mint A moves the price down
+///     - target_sqrt_price: the Q64.64 sqrt price to stop at, below the current one when selling 
+///       mint A and above it when selling mint B
+///
+/// Returns:
+///     - The InverseQuote or OrcaQuoterError::InvalidArgument if input_mint is not one of the pool's 
+///       mints or the target lies on the wrong side of the current price
+pub fn max_input_for_sqrt_price(
+    pool: &PoolState, input_mint: &Pubkey, target_sqrt_price: u128
+) -> Result<InverseQuote, OrcaQuoterError> {
+    // Phase 1: Validate the target against the swap direction
+    let (a_to_b, output_mint): (bool, Pubkey) = swap_direction(pool, input_mint)?;
+    let wrong_side: bool = match a_to_b {
+        true => target_sqrt_price > pool.whirlpool.sqrt_price,
+        false => target_sqrt_price < pool.whirlpool.sqrt_price,
+    };
+    if wrong_side || !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&target_sqrt_price) {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "Target sqrt price {} is out of range for a swap from the current sqrt price {} with a_to_b = {}", 
+            target_sqrt_price, pool.whirlpool.sqrt_price, a_to_b
+        )));
+    }
+
+    // Phase 2: Walk the ticks up to the target, the final level holds the cumulative amounts
+    let side: DepthSide = depth_side(pool, a_to_b, Some(target_sqrt_price))?;
+    let last: DepthLevel = side.exhausted_at;
+
+    // Phase 3: Gross the input up by the input mint's transfer fee, net the output of the output's
+    let (input_data, output_data): (&MintData, &MintData) = match a_to_b {
+        true => (&pool.mint_a_data, &pool.mint_b_data),
+        false => (&pool.mint_b_data, &pool.mint_a_data),
+    };
+    let max_amount_in: u64 = try_reverse_apply_transfer_fee(
+        last.cumulative_in_with_fee, input_data.core_transfer_fee().unwrap_or_default()
+    ).map_err(OrcaQuoterError::Quote)?;
+    let amount_out_est: u64 = try_apply_transfer_fee(
+        last.cumulative_out, output_data.core_transfer_fee().unwrap_or_default()
+    ).map_err(OrcaQuoterError::Quote)?;
+
+    Ok(InverseQuote {
+        input_mint: *input_mint,
+        output_mint,
+        a_to_b,
+        target_sqrt_price,
+        target_price: sqrt_price_to_price(target_sqrt_price, pool.mint_a_data.decimals, pool.mint_b_data.decimals),
+        curve_amount_in: last.cumulative_in,
+        max_amount_in,
+        amount_out_est,
+        limited_by_window: last.sqrt_price != target_sqrt_price,
+        input_label: input_data.label(),
+        output_label: output_data.label(),
+        input_decimals: input_data.decimals,
+        output_decimals: output_data.decimals,
+    })
+}
+
+
+/// Replay a swap over the pool's tick arrays to find the sqrt price it leaves the pool at
+///     - The core quote does not expose its final sqrt price, so the input is walked tick by tick, 
+///       crossing initialised ticks the same way compute_swap does
+///     - Adaptive fees only change how the input splits into fee and curve amount, which is 
+///       already settled by passing the curve amount
+/// 
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - a_to_b: the swap direction
+///     - curve_amount_in: the input reaching the curve, i.e. net of transfer and trade fees
+/// 
+/// Returns:
+///     - The post swap sqrt price, or OrcaQuoterError::Quote with INVALID_TICK_ARRAY_SEQUENCE if the 
+///       input runs past the end of the loaded tick arrays, as the core quote fails there too
+#[inline(never)]
+pub fn post_swap_sqrt_price(pool: &PoolState, a_to_b: bool, curve_amount_in: u64) -> Result<u128, OrcaQuoterError> {
+    let tick_sequence: TickArraySequence<6> = TickArraySequence::new(
+        pool.current_tick_array.clone().into(), pool.whirlpool.tick_spacing
+    ).map_err(OrcaQuoterError::Quote)?;
+
+    let mut amount_remaining: u64 = curve_amount_in;
+    let mut current_sqrt_price: u128 = pool.whirlpool.sqrt_price;
+    let mut current_tick_index: i32 = pool.whirlpool.tick_current_index;
+    let mut current_liquidity: u128 = pool.whirlpool.liquidity;
+
+    while amount_remaining > 0 {
+        let (next_tick, next_tick_index): (Option<&TickFacade>, i32) = match a_to_b {
+            true => tick_sequence.prev_initialized_tick(current_tick_index),
+            false => tick_sequence.next_initialized_tick(current_tick_index),
+        }.map_err(OrcaQuoterError::Quote)?;
+        let target_sqrt_price: u128 = tick_index_to_sqrt_price(next_tick_index);
+
+        // Input needed to reach the next initialised tick, an overflow means it is out of reach
+        let amount_to_target: Option<u64> = match a_to_b {
+            true => try_get_amount_delta_a(current_sqrt_price, target_sqrt_price, current_liquidity, true),
+            false => try_get_amount_delta_b(current_sqrt_price, target_sqrt_price, current_liquidity, true),
+        }.ok();
+        match amount_to_target {
+            Some(amount_to_target) if amount_to_target <= amount_remaining => {
+                amount_remaining -= amount_to_target;
+                current_sqrt_price = target_sqrt_price;
+                // The end of the tick sequence has no tick to cross, input left over cannot be swapped
+                let Some(next_tick) = next_tick else {
+                    if amount_remaining > 0 {
+                        return Err(OrcaQuoterError::Quote(INVALID_TICK_ARRAY_SEQUENCE));
+                    }
+                    break;
+                };
+                let liquidity_net: i128 = match a_to_b {
+                    true => -next_tick.liquidity_net,
+                    false => next_tick.liquidity_net,
+                };
+                current_liquidity = current_liquidity.checked_add_signed(liquidity_net)
+                    .ok_or(OrcaQuoterError::Quote(ARITHMETIC_OVERFLOW))?;
+                current_tick_index = match a_to_b {
+                    true => next_tick_index - 1,
+                    false => next_tick_index,
+                };
+            },
+            _ => {
+                current_sqrt_price = match a_to_b {
+                    true => try_get_next_sqrt_price_from_a(current_sqrt_price, current_liquidity, amount_remaining, true),
+                    false => try_get_next_sqrt_price_from_b(current_sqrt_price, current_liquidity, amount_remaining, true),
+                }.map_err(OrcaQuoterError::Quote)?;
+                amount_remaining = 0;
+            },
+        }
+    }
+    Ok(current_sqrt_price)
+}
+
+
+/// Walk one direction of the loaded tick arrays
+///
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - a_to_b: the swap direction, true walks the price down
+///     - sqrt_price_limit: a sqrt price to stop at, None walks to the edge of the window
+///
+/// Returns:
+///     - The DepthSide, its exhausted_at level lies at the limit if it is reached before the edge 
+///       of the window, or OrcaQuoterError::Quote
+#[inline(never)]
+fn depth_side(pool: &PoolState, a_to_b: bool, sqrt_price_limit: Option<u128>) -> Result<DepthSide, OrcaQuoterError> {
+    let tick_sequence: TickArraySequence<6> = TickArraySequence::new(
+        pool.current_tick_array.clone().into(), pool.whirlpool.tick_spacing
+    ).map_err(OrcaQuoterError::Quote)?;
+
+    let mut levels: Vec<DepthLevel> = Vec::new();
+    let mut current_sqrt_price: u128 = pool.whirlpool.sqrt_price;
+    let mut current_tick_index: i32 = pool.whirlpool.tick_current_index;
+    let mut current_liquidity: u128 = pool.whirlpool.liquidity;
+    let mut cumulative_in: u64 = 0;
+    let mut cumulative_out: u64 = 0;
+
+    loop {
+        // Phase 1: Find the next initialised tick, the end of the sequence has no tick to cross
+        let (next_tick, next_tick_index): (Option<&TickFacade>, i32) = match a_to_b {
+            true => tick_sequence.prev_initialized_tick(current_tick_index),
+            false => tick_sequence.next_initialized_tick(current_tick_index),
+        }.map_err(OrcaQuoterError::Quote)?;
+        let next_tick_index: i32 = next_tick_index.clamp(MIN_TICK_INDEX, MAX_TICK_INDEX);
+        let tick_sqrt_price: u128 = tick_index_to_sqrt_price(next_tick_index);
+
+        // A limit before the next tick ends the walk there, without crossing the tick
+        let limit_before_tick: Option<u128> = sqrt_price_limit.filter(|limit| match a_to_b {
+            true => *limit > tick_sqrt_price,
+            false => *limit < tick_sqrt_price,
+        });
+        let target_sqrt_price: u128 = limit_before_tick.unwrap_or(tick_sqrt_price);
+
+        // Phase 2: Accumulate the amounts of moving the price there, input rounds up and output down
+            // A step beyond u64 can never be quoted, so it saturates
+        let (step_in, step_out): (u64, u64) = match a_to_b {
+            true => (
+                try_get_amount_delta_a(current_sqrt_price, target_sqrt_price, current_liquidity, true).unwrap_or(u64::MAX),
+                try_get_amount_delta_b(current_sqrt_price, target_sqrt_price, current_liquidity, false).unwrap_or(u64::MAX),
+            ),
+            false => (
+                try_get_amount_delta_b(current_sqrt_price, target_sqrt_price, current_liquidity, true).unwrap_or(u64::MAX),
+                try_get_amount_delta_a(current_sqrt_price, target_sqrt_price, current_liquidity, false).unwrap_or(u64::MAX),
+            ),
+        };
+        cumulative_in = cumulative_in.saturating_add(step_in);
+        cumulative_out = cumulative_out.saturating_add(step_out);
+        current_sqrt_price = target_sqrt_price;
+        if limit_before_tick.is_some() {
+            let exhausted_at: DepthLevel = DepthLevel {
+                tick_index: sqrt_price_to_tick_index(current_sqrt_price),
+                sqrt_price: current_sqrt_price,
+                price: sqrt_price_to_price(current_sqrt_price, pool.mint_a_data.decimals, pool.mint_b_data.decimals),
+                liquidity_net: 0,
+                liquidity_after: current_liquidity,
+                cumulative_in,
+                cumulative_in_with_fee: with_static_fee(cumulative_in, pool.whirlpool.fee_rate),
+                cumulative_out,
+            };
+            return Ok(DepthSide { a_to_b, levels, exhausted_at });
+        }
+
+        // Phase 3: Cross the tick, or stop at the edge of the window or the tick range
+        let at_tick_range_edge: bool = next_tick_index == MIN_TICK_INDEX || next_tick_index == MAX_TICK_INDEX;
+        let liquidity_net: i128 = next_tick.map(|tick| tick.liquidity_net).unwrap_or_default();
+        if next_tick.is_some() && !at_tick_range_edge {
+            current_liquidity = match a_to_b {
+                true => current_liquidity.checked_add_signed(-liquidity_net),
+                false => current_liquidity.checked_add_signed(liquidity_net),
+            }.ok_or(OrcaQuoterError::Quote(ARITHMETIC_OVERFLOW))?;
+        }
+        let level: DepthLevel = DepthLevel {
+            tick_index: next_tick_index,
+            sqrt_price: current_sqrt_price,
+            price: sqrt_price_to_price(current_sqrt_price, pool.mint_a_data.decimals, pool.mint_b_data.decimals),
+            liquidity_net,
+            liquidity_after: current_liquidity,
+            cumulative_in,
+            cumulative_in_with_fee: with_static_fee(cumulative_in, pool.whirlpool.fee_rate),
+            cumulative_out,
+        };
+        if next_tick.is_none() || at_tick_range_edge {
+            return Ok(DepthSide { a_to_b, levels, exhausted_at: level });
+        }
+        levels.push(level);
+        current_tick_index = match a_to_b {
+            true => next_tick_index - 1,
+            false => next_tick_index,
+        };
+    }
+}
+
+
+/// Gross a curve input up by the pool's static fee rate
+///
+/// Parameters:
+///     - amount: the input reaching the curve
+///     - fee_rate: the static fee rate in millionths
+///
+/// Returns:
+///     - The input including the fee, rounded up and saturating at u64::MAX
+fn with_static_fee(amount: u64, fee_rate: u16) -> u64 {
+    let numerator: u128 = amount as u128 * FEE_RATE_DENOMINATOR;
+    let denominator: u128 = FEE_RATE_DENOMINATOR - fee_rate as u128;
+    u64::try_from(numerator.div_ceil(denominator)).unwrap_or(u64::MAX)
+}
@@ -1 +1,3 @@
+pub mod depth;
+pub mod router;
 pub mod structs;
\ No newline at end of file
@@ -0,0 +1,537 @@
+// Route quoting across whirlpools, multi-hop routes and split orders ///
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{
+    Deserialize, Serialize
+};
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::{
+    try_get_max_amount_with_slippage_tolerance, try_get_min_amount_with_slippage_tolerance
+};
+
+use pool::structs::PoolState;
+use mint::mint::{
+    ui_amount, format_ui_amount
+};
+
+use crate::structs::{
+    DirectedSwapQuote, SwapMode
+};
+
+use error::error::OrcaQuoterError;
+
+
+/// Maximum number of pools a RouteQuote swaps through
+pub const MAX_ROUTE_HOPS: usize = 3;
+
+/// A swap through one pool of a route
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteHop {
+    // Index of the pool in the (pool pubkey, PoolState) slice the TokenGraph was built from
+    pub pool_index: usize,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+}
+
+/// Undirected graph of mints, with one edge per pool joining its mint A and mint B
+#[derive(Clone, Debug, Default)]
+pub struct TokenGraph {
+    // Mint to every (pool index, other mint) edge leaving it
+    pub edges: HashMap<Pubkey, Vec<(usize, Pubkey)>>,
+}
+
+impl TokenGraph {
+    /// Build the graph of a set of loaded pools
+    ///     - Pools with a blocking MintRisk on either mint are left out, see MintData::is_untradeable
+    /// 
+    /// Parameters:
+    ///     - pool_states: a pointer to a slice of (pool pubkey, PoolState) tuples
+    /// 
+    /// Returns:
+    ///     - The TokenGraph, edges reference pools by their index in pool_states
+    pub fn from_pool_states(pool_states: &[(Pubkey, PoolState)]) -> TokenGraph {
+        let mut edges: HashMap<Pubkey, Vec<(usize, Pubkey)>> = HashMap::new();
+        for (pool_index, (_, pool)) in pool_states.iter().enumerate() {
+            if pool.mint_a_data.is_untradeable() || pool.mint_b_data.is_untradeable() {
+                continue;
+            }
+            let (mint_a, mint_b): (Pubkey, Pubkey) = (pool.mint_a_data.pubkey, pool.mint_b_data.pubkey);
+            edges.entry(mint_a).or_default().push((pool_index, mint_b));
+            edges.entry(mint_b).or_default().push((pool_index, mint_a));
+        }
+        TokenGraph { edges }
+    }
+
+    /// Every simple path between two mints, i.e. no mint is visited twice
+    /// 
+    /// Parameters:
+    ///     - input_mint: a pointer to the mint swapped in
+    ///     - output_mint: a pointer to the mint swapped out
+    ///     - max_hops: the maximum number of pools per path
+    /// 
+    /// Returns:
+    ///     - A vector of paths, each a vector of RouteHops from input_mint to output_mint
+    pub fn paths(&self, input_mint: &Pubkey, output_mint: &Pubkey, max_hops: usize) -> Vec<Vec<RouteHop>> {
+        let mut paths: Vec<Vec<RouteHop>> = Vec::new();
+        let mut path: Vec<RouteHop> = Vec::new();
+        self.extend_paths(input_mint, output_mint, max_hops, &mut path, &mut paths);
+        paths
+    }
+
+    // Depth first search from the last mint of path, pushing every path reaching output_mint
+    fn extend_paths(
+        &self, mint: &Pubkey, output_mint: &Pubkey, max_hops: usize, path: &mut Vec<RouteHop>, paths: &mut Vec<Vec<RouteHop>>
+    ) {
+        if path.len() == max_hops {
+            return;
+        }
+        for (pool_index, next_mint) in self.edges.get(mint).map(Vec::as_slice).unwrap_or_default() {
+            let visited: bool = path.iter().any(|hop| hop.input_mint == *next_mint);
+            if visited {
+                continue;
+            }
+            path.push(RouteHop { pool_index: *pool_index, input_mint: *mint, output_mint: *next_mint });
+            match next_mint == output_mint {
+                true => paths.push(path.clone()),
+                false => self.extend_paths(next_mint, output_mint, max_hops, path, paths),
+            }
+            path.pop();
+        }
+    }
+}
+
+/// One leg of a RouteQuote
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteLeg {
+    pub pool: Pubkey,
+    pub quote: DirectedSwapQuote,
+}
+
+/// A quote swapping through one to MAX_ROUTE_HOPS pools
+///     - Legs are chained on estimated amounts, slippage is only applied once to the route's 
+///       final amount as a multi-hop swap checks a single threshold
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub mode: SwapMode,
+    pub amount: u64,
+    pub slippage_bps: u16,
+    pub legs: Vec<RouteLeg>,
+    pub token_in_est: u64,
+    pub token_out_est: u64,
+    // Minimum output for ExactIn routes, maximum input for ExactOut routes
+    pub other_amount_threshold: u64,
+}
+
+impl RouteQuote {
+    /// Effective execution price of the route's estimated amounts
+    /// 
+    /// Returns:
+    ///     - The UI units of the output token received per UI unit of the input token paid, 
+    ///       None if no input is paid
+    pub fn execution_price(&self) -> Option<f64> {
+        let first: &DirectedSwapQuote = &self.legs.first()?.quote;
+        let last: &DirectedSwapQuote = &self.legs.last()?.quote;
+        let token_in: f64 = ui_amount(self.token_in_est, first.input_decimals);
+        match token_in > 0.0 {
+            true => Some(ui_amount(self.token_out_est, last.output_decimals) / token_in),
+            false => None,
+        }
+    }
+}
+
+/// Pretty printing for RouteQuote, e.g. "BONK → SOL → USDC" followed by one line per leg
+impl fmt::Display for RouteQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Some(first), Some(last)) = (self.legs.first(), self.legs.last()) else {
+            return writeln!(f, "RouteQuote {{ no legs }}");
+        };
+        let input_ui = format!("{} {}", format_ui_amount(self.token_in_est, first.quote.input_decimals), first.quote.input_label);
+        let output_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, last.quote.output_decimals), last.quote.output_label);
+        let mut mints: Vec<String> = vec![first.quote.input_label.clone()];
+        mints.extend(self.legs.iter().map(|leg| leg.quote.output_label.clone()));
+
+        writeln!(f, "RouteQuote {} {{", mints.join(" → "))?;
+        writeln!(f, "  mode:         {:?}", self.mode)?;
+        writeln!(f, "  slippage_bps: {}", self.slippage_bps)?;
+        match self.mode {
+            SwapMode::ExactIn => writeln!(
+                f, "  swap:         sell {} → {} (min {})", input_ui, output_ui(self.token_out_est), output_ui(self.other_amount_threshold)
+            )?,
+            SwapMode::ExactOut => writeln!(
+                f, "  swap:         buy {} ← {} (max {} {})", output_ui(self.token_out_est), input_ui, 
+                format_ui_amount(self.other_amount_threshold, first.quote.input_decimals), first.quote.input_label
+            )?,
+        }
+        for (index, leg) in self.legs.iter().enumerate() {
+            writeln!(
+                f, "  leg {}:        {} ({}): {} {} → {} {}", 
+                index + 1, leg.quote.pool_label, leg.pool, 
+                format_ui_amount(leg.quote.token_in_est(), leg.quote.input_decimals), leg.quote.input_label,
+                format_ui_amount(leg.quote.token_out_est(), leg.quote.output_decimals), leg.quote.output_label
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// Default number of chunks best_split_quote allocates an order in
+pub const DEFAULT_SPLIT_STEPS: u64 = 20;
+
+/// The share of a SplitQuote swapped through one pool
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SplitAllocation {
+    pub pool: Pubkey,
+    pub amount_in: u64,
+    pub quote: DirectedSwapQuote,
+}
+
+/// An exact-in order split across several pools of the same pair
+///     - Each allocation is executed as its own swap with its own minimum output, so the aggregate 
+///       minimum is the sum of the allocations' minimums
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SplitQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount: u64,
+    pub slippage_bps: u16,
+    // Pools receiving a non zero share of the order, in descending order of amount_in
+    pub allocations: Vec<SplitAllocation>,
+    pub token_out_est: u64,
+    pub token_min_out: u64,
+    // Estimated output of the whole amount through the best single pool, 0 if no pool could take it
+    pub best_single_pool_out: u64,
+}
+
+impl SplitQuote {
+    /// Output gained by splitting, relative to the best single pool
+    /// 
+    /// Returns:
+    ///     - (token_out_est - best_single_pool_out) / best_single_pool_out in basis points, None if 
+    ///       no single pool could take the whole amount
+    pub fn improvement_bps(&self) -> Option<f64> {
+        match self.best_single_pool_out > 0 {
+            true => Some(
+                (self.token_out_est as f64 - self.best_single_pool_out as f64) / self.best_single_pool_out as f64 * 10_000.0
+            ),
+            false => None,
+        }
+    }
+}
+
+/// Pretty printing for SplitQuote, one line per allocation
+impl fmt::Display for SplitQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(first) = self.allocations.first() else {
+            return writeln!(f, "SplitQuote {{ no allocations }}");
+        };
+        let input_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, first.quote.input_decimals), first.quote.input_label);
+        let output_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, first.quote.output_decimals), first.quote.output_label);
+
+        writeln!(f, "SplitQuote {} → {} {{", first.quote.input_label, first.quote.output_label)?;
+        writeln!(f, "  slippage_bps: {}", self.slippage_bps)?;
+        writeln!(
+            f, "  swap:         sell {} → {} (min {})", 
+            input_ui(self.amount), output_ui(self.token_out_est), output_ui(self.token_min_out)
+        )?;
+        match self.improvement_bps() {
+            Some(improvement) => writeln!(
+                f, "  vs single:    {} ({:+.2} bps)", output_ui(self.best_single_pool_out), improvement
+            )?,
+            None => writeln!(f, "  vs single:    no single pool takes the whole amount")?,
+        }
+        for allocation in self.allocations.iter() {
+            writeln!(
+                f, "  {:>5.1}%:       {} ({}): {} → {}", 
+                allocation.amount_in as f64 / self.amount as f64 * 100.0, allocation.quote.pool_label, allocation.pool, 
+                input_ui(allocation.amount_in), output_ui(allocation.quote.token_out_est())
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+
+/// Quote the best route between two mints over a set of loaded pools
+///     - Every simple path of 1 to max_hops pools is quoted, ExactIn routes maximise the estimated
+///       output and ExactOut routes minimise the estimated input
+///
+/// Parameters:
+///     - pool_states: a pointer to a slice of (pool pubkey, PoolState) tuples, e.g. from populate_pool_states
+///     - input_mint: a pointer to the mint swapped in
+///     - output_mint: a pointer to the mint swapped out
+///     - mode: whether amount is the exact input or the exact output
+///     - amount: the exact input or output amount, in base units
+///     - slippage_bps: the slippage tolerance in basis points, applied once to the route
+///     - max_hops: the maximum number of pools per route, at most MAX_ROUTE_HOPS
+///
+/// Returns:
+///     - The best RouteQuote, OrcaQuoterError::InvalidArgument if no path exists or max_hops is out
+///       of range, or the last quote error if every path failed to quote
+pub fn best_route_quote(
+    pool_states: &[(Pubkey, PoolState)], input_mint: &Pubkey, output_mint: &Pubkey, mode: SwapMode,
+    amount: u64, slippage_bps: u16, max_hops: usize
+) -> Result<RouteQuote, OrcaQuoterError> {
+    // Phase 1: Validate arguments and enumerate the candidate paths
+    if max_hops == 0 || max_hops > MAX_ROUTE_HOPS {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "max_hops must be between 1 and {}, got {}", MAX_ROUTE_HOPS, max_hops
+        )));
+    }
+    if input_mint == output_mint {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "Input and output mint are both {}", input_mint
+        )));
+    }
+    let graph: TokenGraph = TokenGraph::from_pool_states(pool_states);
+    let paths: Vec<Vec<RouteHop>> = graph.paths(input_mint, output_mint, max_hops);
+    if paths.is_empty() {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "No route from {} to {} within {} hops", input_mint, output_mint, max_hops
+        )));
+    }
+
+    // Phase 2: Quote every path and keep the best, a path failing to quote (e.g. running out of
+    // loaded tick arrays) is skipped
+    let mut best: Option<RouteQuote> = None;
+    let mut last_error: Option<OrcaQuoterError> = None;
+    for path in paths.iter() {
+        let route: RouteQuote = match quote_route(pool_states, path, mode, amount, slippage_bps) {
+            Ok(route) => route,
+            Err(err) => {
+                last_error = Some(err);
+                continue;
+            }
+        };
+        let is_better: bool = match (&best, mode) {
+            (None, _) => true,
+            (Some(best), SwapMode::ExactIn) => route.token_out_est > best.token_out_est,
+            (Some(best), SwapMode::ExactOut) => route.token_in_est < best.token_in_est,
+        };
+        if is_better {
+            best = Some(route);
+        }
+    }
+    best.ok_or_else(|| last_error.unwrap_or(OrcaQuoterError::InvalidArgument(format!(
+        "No route from {} to {} could be quoted", input_mint, output_mint
+    ))))
+}
+
+
+/// Quote a single path by chaining per pool quotes
+///     - ExactIn paths run forwards, each leg swapping the previous leg's estimated output
+///     - ExactOut paths run backwards, each leg buying the next leg's estimated input
+///
+/// Parameters:
+///     - pool_states: a pointer to the slice of (pool pubkey, PoolState) tuples the path indexes into
+///     - path: a pointer to the RouteHops, e.g. from TokenGraph::paths
+///     - mode: whether amount is the exact input or the exact output
+///     - amount: the exact input or output amount, in base units
+///     - slippage_bps: the slippage tolerance in basis points, applied once to the route
+///
+/// Returns:
+///     - The RouteQuote or an OrcaQuoterError
+pub fn quote_route(
+    pool_states: &[(Pubkey, PoolState)], path: &[RouteHop], mode: SwapMode, amount: u64, slippage_bps: u16
+) -> Result<RouteQuote, OrcaQuoterError> {
+    let (Some(first_hop), Some(last_hop)) = (path.first(), path.last()) else {
+        return Err(OrcaQuoterError::InvalidArgument("Route has no hops".to_string()));
+    };
+
+    // Phase 1: Chain the legs on estimated amounts
+    let mut legs: Vec<RouteLeg> = Vec::with_capacity(path.len());
+    let mut leg_amount: u64 = amount;
+    match mode {
+        SwapMode::ExactIn => for hop in path.iter() {
+            let leg: RouteLeg = quote_leg(pool_states, hop, mode, leg_amount, slippage_bps)?;
+            leg_amount = leg.quote.token_out_est();
+            legs.push(leg);
+        },
+        SwapMode::ExactOut => {
+            for hop in path.iter().rev() {
+                let leg: RouteLeg = quote_leg(pool_states, hop, mode, leg_amount, slippage_bps)?;
+                leg_amount = leg.quote.token_in_est();
+                legs.push(leg);
+            }
+            legs.reverse();
+        },
+    }
+
+    // Phase 2: Apply slippage once to the route's unfixed side
+    let token_in_est: u64 = legs.first().map(|leg| leg.quote.token_in_est()).unwrap_or_default();
+    let token_out_est: u64 = legs.last().map(|leg| leg.quote.token_out_est()).unwrap_or_default();
+    let other_amount_threshold: u64 = match mode {
+        SwapMode::ExactIn => try_get_min_amount_with_slippage_tolerance(token_out_est, slippage_bps),
+        SwapMode::ExactOut => try_get_max_amount_with_slippage_tolerance(token_in_est, slippage_bps),
+    }.map_err(OrcaQuoterError::Quote)?;
+
+    Ok(RouteQuote {
+        input_mint: first_hop.input_mint,
+        output_mint: last_hop.output_mint,
+        mode,
+        amount,
+        slippage_bps,
+        legs,
+        token_in_est,
+        token_out_est,
+        other_amount_threshold,
+    })
+}
+
+
+/// Quote one hop of a route
+///
+/// Parameters:
+///     - pool_states: a pointer to the slice of (pool pubkey, PoolState) tuples the hop indexes into
+///     - hop: a pointer to the RouteHop
+///     - mode: whether amount is the exact input or the exact output of the leg
+///     - amount: the leg's exact input or output amount, in base units
+///     - slippage_bps: the slippage tolerance in basis points
+///
+/// Returns:
+///     - The RouteLeg or an OrcaQuoterError
+fn quote_leg(
+    pool_states: &[(Pubkey, PoolState)], hop: &RouteHop, mode: SwapMode, amount: u64, slippage_bps: u16
+) -> Result<RouteLeg, OrcaQuoterError> {
+    let (pool_pubkey, pool): &(Pubkey, PoolState) = pool_states.get(hop.pool_index).ok_or_else(||
+        OrcaQuoterError::InvalidArgument(format!("Route references missing pool index {}", hop.pool_index))
+    )?;
+    Ok(RouteLeg {
+        pool: *pool_pubkey,
+        quote: DirectedSwapQuote::from_pool_state(pool, &hop.input_mint, mode, amount, slippage_bps)?,
+    })
+}
+
+
+/// Split an exact-in order across every loaded pool of a pair to maximise the output
+///     - Iterative marginal allocation: the amount is cut into steps equal chunks and each chunk goes 
+///       to the pool whose output grows most by taking it, i.e. the pool with the best marginal price 
+///       after the chunks it already holds
+///     - Pools with a blocking MintRisk are left out, see TokenGraph::from_pool_states
+///
+/// Parameters:
+///     - pool_states: a pointer to a slice of (pool pubkey, PoolState) tuples, e.g. from populate_pool_states
+///     - input_mint: a pointer to the mint swapped in
+///     - output_mint: a pointer to the mint swapped out
+///     - amount: the exact input amount, in base units
+///     - slippage_bps: the slippage tolerance in basis points, applied to every allocation
+///     - steps: the number of chunks to allocate, e.g. DEFAULT_SPLIT_STEPS, more steps allocate more 
+///       finely at the cost of steps x pools quotes
+///
+/// Returns:
+///     - The SplitQuote, OrcaQuoterError::InvalidArgument if amount or steps is zero or no pool trades 
+///       the pair, or the last quote error if no pool could take a chunk
+pub fn best_split_quote(
+    pool_states: &[(Pubkey, PoolState)], input_mint: &Pubkey, output_mint: &Pubkey, amount: u64,
+    slippage_bps: u16, steps: u64
+) -> Result<SplitQuote, OrcaQuoterError> {
+    // Phase 1: Validate arguments and collect the pools trading the pair
+    if amount == 0 || steps == 0 {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "Split amount and steps must be non zero, got amount {} and steps {}", amount, steps
+        )));
+    }
+    let graph: TokenGraph = TokenGraph::from_pool_states(pool_states);
+    let pool_indexes: Vec<usize> = graph.edges.get(input_mint)
+        .map(|edges| edges.iter()
+            .filter(|(_, next_mint)| next_mint == output_mint)
+            .map(|(pool_index, _)| *pool_index)
+            .collect())
+        .unwrap_or_default();
+    if pool_indexes.is_empty() {
+        return Err(OrcaQuoterError::InvalidArgument(format!(
+            "No pool trades {} for {}", input_mint, output_mint
+        )));
+    }
+
+    // Phase 2: Allocate the chunks greedily by marginal output
+        // Fewer steps than base units would leave zero sized chunks, the last chunk takes the remainder
+    let steps: u64 = steps.min(amount);
+    let chunk: u64 = amount / steps;
+    let mut allocated: Vec<u64> = vec![0; pool_indexes.len()];
+    let mut outputs: Vec<u64> = vec![0; pool_indexes.len()];
+    let mut last_error: Option<OrcaQuoterError> = None;
+    for step in 0..steps {
+        let step_amount: u64 = match step == steps - 1 {
+            true => amount - chunk * (steps - 1),
+            false => chunk,
+        };
+        // (position in pool_indexes, output with the chunk added)
+        let mut best: Option<(usize, u64)> = None;
+        for (position, pool_index) in pool_indexes.iter().enumerate() {
+            let output: u64 = match quote_exact_in_output(pool_states, *pool_index, input_mint, allocated[position] + step_amount) {
+                Ok(output) => output,
+                Err(err) => {
+                    last_error = Some(err);
+                    continue;
+                }
+            };
+            let gain: u64 = output.saturating_sub(outputs[position]);
+            let best_gain: Option<u64> = best.map(|(best_position, best_output)| best_output.saturating_sub(outputs[best_position]));
+            if best_gain.is_none_or(|best_gain| gain > best_gain) {
+                best = Some((position, output));
+            }
+        }
+        let Some((position, output)) = best else {
+            return Err(last_error.unwrap_or(OrcaQuoterError::InvalidArgument(format!(
+                "No pool trading {} for {} could take {}", input_mint, output_mint, step_amount
+            ))));
+        };
+        allocated[position] += step_amount;
+        outputs[position] = output;
+    }
+
+    // Phase 3: Quote the final allocations and the best single pool for comparison
+    let mut allocations: Vec<SplitAllocation> = Vec::new();
+    for (position, pool_index) in pool_indexes.iter().enumerate() {
+        if allocated[position] == 0 {
+            continue;
+        }
+        let (pool_pubkey, pool): &(Pubkey, PoolState) = &pool_states[*pool_index];
+        allocations.push(SplitAllocation {
+            pool: *pool_pubkey,
+            amount_in: allocated[position],
+            quote: DirectedSwapQuote::from_pool_state(pool, input_mint, SwapMode::ExactIn, allocated[position], slippage_bps)?,
+        });
+    }
+    allocations.sort_by_key(|allocation| Reverse(allocation.amount_in));
+    let best_single_pool_out: u64 = pool_indexes.iter()
+        .filter_map(|pool_index| quote_exact_in_output(pool_states, *pool_index, input_mint, amount).ok())
+        .max()
+        .unwrap_or_default();
+
+    Ok(SplitQuote {
+        input_mint: *input_mint,
+        output_mint: *output_mint,
+        amount,
+        slippage_bps,
+        token_out_est: allocations.iter().map(|allocation| allocation.quote.token_out_est()).sum(),
+        token_min_out: allocations.iter().map(|allocation| allocation.quote.other_amount_threshold()).sum(),
+        allocations,
+        best_single_pool_out,
+    })
+}
+
+
+/// Estimated output of an exact-in swap through one pool, slippage is irrelevant to the estimate
+///
+/// Parameters:
+///     - pool_states: a pointer to the slice of (pool pubkey, PoolState) tuples
+///     - pool_index: the index of the pool in pool_states
+///     - input_mint: a pointer to the mint swapped in
+///     - amount: the exact input amount, in base units
+///
+/// Returns:
+///     - The estimated output or an OrcaQuoterError
+fn quote_exact_in_output(
+    pool_states: &[(Pubkey, PoolState)], pool_index: usize, input_mint: &Pubkey, amount: u64
+) -> Result<u64, OrcaQuoterError> {
+    let (_, pool): &(Pubkey, PoolState) = &pool_states[pool_index];
+    DirectedSwapQuote::from_pool_state(pool, input_mint, SwapMode::ExactIn, amount, 0)
+        .map(|quote| quote.token_out_est())
+}
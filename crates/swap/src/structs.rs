@@ -1,99 +1,316 @@
-use std::time::{
-    SystemTime, UNIX_EPOCH
-};
 use std::fmt;
-use std::boxed::{
-    Box
+
+use serde::{
+    Deserialize, Serialize
 };
+use solana_sdk::pubkey::Pubkey;
 
 use orca_whirlpools_core::{
-    swap_quote_by_input_token, swap_quote_by_output_token,
+    swap_quote_by_input_token, swap_quote_by_output_token, sqrt_price_to_price, try_apply_transfer_fee,
     ExactInSwapQuote,    
-    ExactOutSwapQuote,   
-    WhirlpoolFacade,     
-    OracleFacade,   
-    TickArrays     
+    ExactOutSwapQuote
 };
 
 use pool::structs::PoolState;
+use mint::{
+    structs::MintData, mint::{ui_amount, format_ui_amount}
+};
+
+use crate::depth::post_swap_sqrt_price;
+
+use error::{
+    error::OrcaQuoterError, clock::current_unix_timestamp_ms
+};
 
 /// Bid‐Ask information for a single "swap" quote against a whirlpool.
 ///
-/// - **ask**: How much token B you must pay to receive _exactly_ `amount_a` units of token A  
-///           (i.e. an _exact‐output_ quote).  
-/// - **bid**: How much token B you would receive if you _sold_ `amount_a` units of token A  
-///           (i.e. an _exact‐input_ quote).
+/// - **ask**: How much of the other token you must pay to receive _exactly_ `amount` units of the 
+///   base token (i.e. an _exact‐output_ quote).  
+/// - **bid**: How much of the other token you would receive if you _sold_ `amount` units of the 
+///   base token (i.e. an _exact‐input_ quote).
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SwapQuote {
+    pub base_mint:    Pubkey,
+    // The pool's other mint, bid and ask are paid in it
+    pub quote_mint:   Pubkey,
+    // Display labels, e.g. "SOL" and "SOL/USDC ts=4", see MintData::label and PoolState::pair_label
+    pub base_label:   String,
+    pub quote_label:  String,
+    pub pool_label:   String,
+    pub base_decimals:  u8,
+    pub quote_decimals: u8,
+    // Pre-trade pool price from sqrt_price, in UI units of the quote token per base token
+    pub mid_price:    f64,
+    // Pool prices after the bid and ask swaps, replayed over the tick arrays, same units as mid_price
+    pub bid_post_price: f64,
+    pub ask_post_price: f64,
+    // Whether the pool charges an adaptive fee on top of its static fee, i.e. has an oracle
+    pub adaptive_fee: bool,
     pub amount:       u64,
     pub slippage_bps:   u16,
+    #[serde(with = "ExactInSwapQuoteDef")]
     pub bid:            ExactInSwapQuote,
+    #[serde(with = "ExactOutSwapQuoteDef")]
     pub ask:            ExactOutSwapQuote,
 }
 
-pub struct SwapParams {
-    pub pool: PoolState,
+/// Serde remote definition of ExactInSwapQuote, orca_whirlpools_core only derives serde behind its wasm feature
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ExactInSwapQuote")]
+pub struct ExactInSwapQuoteDef {
+    pub token_in: u64,
+    pub token_est_out: u64,
+    pub token_min_out: u64,
+    pub trade_fee: u64,
+    pub trade_fee_rate_min: u32,
+    pub trade_fee_rate_max: u32,
+}
+
+/// Serde remote definition of ExactOutSwapQuote
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ExactOutSwapQuote")]
+pub struct ExactOutSwapQuoteDef {
+    pub token_out: u64,
+    pub token_est_in: u64,
+    pub token_max_in: u64,
+    pub trade_fee: u64,
+    pub trade_fee_rate_min: u32,
+    pub trade_fee_rate_max: u32,
+}
+
+/// Whether the swap amount is the exact input or the exact output of the swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapMode {
+    ExactIn,
+    ExactOut,
+}
+
+/// The core quote of a DirectedSwapQuote, matching its SwapMode
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum SwapModeQuote {
+    ExactIn(#[serde(with = "ExactInSwapQuoteDef")] ExactInSwapQuote),
+    ExactOut(#[serde(with = "ExactOutSwapQuoteDef")] ExactOutSwapQuote),
+}
+
+/// A one sided quote for any of the four swap shapes on a whirlpool
+///     - exact in selling A, exact in selling B, exact out buying A, exact out buying B
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DirectedSwapQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    // Display labels, see MintData::label and PoolState::pair_label
+    pub input_label: String,
+    pub output_label: String,
+    pub pool_label: String,
+    pub input_decimals: u8,
+    pub output_decimals: u8,
+    // true if token A is swapped for token B, i.e. the price moves down
+    pub a_to_b: bool,
+    pub mode: SwapMode,
     pub amount: u64,
     pub slippage_bps: u16,
+    pub quote: SwapModeQuote,
 }
 
-/// TryFrom implementation for SwapParams to SwapQuote
-///
-/// Note: This abuses the stack, still looking into a better approach here. Running in a 8MiB stack thread works, 
-/// but otherwise we get stack overflow. (In fact, a 2MiB stack thread is enough for the swap quote to be computed)
-/// 
-/// 
-impl<'a> TryFrom<SwapParams> for SwapQuote {
-    type Error = &'static str;
+impl DirectedSwapQuote {
+    /// Estimated amount of the input token paid
+    /// 
+    /// Returns:
+    ///     - The exact input for ExactIn quotes, the estimated input for ExactOut quotes
+    pub fn token_in_est(&self) -> u64 {
+        match self.quote {
+            SwapModeQuote::ExactIn(quote) => quote.token_in,
+            SwapModeQuote::ExactOut(quote) => quote.token_est_in,
+        }
+    }
+
+    /// Estimated amount of the output token received
+    /// 
+    /// Returns:
+    ///     - The estimated output for ExactIn quotes, the exact output for ExactOut quotes
+    pub fn token_out_est(&self) -> u64 {
+        match self.quote {
+            SwapModeQuote::ExactIn(quote) => quote.token_est_out,
+            SwapModeQuote::ExactOut(quote) => quote.token_out,
+        }
+    }
+
+    /// Slippage bound of the side that is not fixed by the SwapMode
+    /// 
+    /// Returns:
+    ///     - The minimum output for ExactIn quotes, the maximum input for ExactOut quotes
+    pub fn other_amount_threshold(&self) -> u64 {
+        match self.quote {
+            SwapModeQuote::ExactIn(quote) => quote.token_min_out,
+            SwapModeQuote::ExactOut(quote) => quote.token_max_in,
+        }
+    }
+
+    /// Trade fee paid, in the input token
+    /// 
+    /// Returns:
+    ///     - The trade fee of the core quote
+    pub fn trade_fee(&self) -> u64 {
+        match self.quote {
+            SwapModeQuote::ExactIn(quote) => quote.trade_fee,
+            SwapModeQuote::ExactOut(quote) => quote.trade_fee,
+        }
+    }
+
+    /// Effective execution price of the estimated amounts
+    /// 
+    /// Returns:
+    ///     - The UI units of the output token received per UI unit of the input token paid, 
+    ///       None if no input is paid
+    pub fn execution_price(&self) -> Option<f64> {
+        let token_in: f64 = ui_amount(self.token_in_est(), self.input_decimals);
+        match token_in > 0.0 {
+            true => Some(ui_amount(self.token_out_est(), self.output_decimals) / token_in),
+            false => None,
+        }
+    }
+}
 
-    fn try_from(params: SwapParams) -> Result<Self, Self::Error> {
-        let SwapParams {
-            pool,
+/// Parameters of a quote against a borrowed PoolState
+pub struct SwapParams<'a> {
+    pub pool: &'a PoolState,
+    pub amount: u64,
+    pub slippage_bps: u16,
+    // The mint swapped into the pool, must be the pool's mint A or mint B
+    pub input_mint: Pubkey,
+    // Whether amount is the exact input (of input_mint) or the exact output (of the other mint)
+    pub mode: SwapMode,
+}
+
+impl SwapParams<'_> {
+    /// Resolve the swap direction of input_mint against the pool's mints
+    /// 
+    /// Returns:
+    ///     - The (a_to_b, output mint) pair or OrcaQuoterError::InvalidArgument if input_mint is not 
+    ///       one of the pool's mints
+    pub fn direction(&self) -> Result<(bool, Pubkey), OrcaQuoterError> {
+        swap_direction(self.pool, &self.input_mint)
+    }
+}
+
+impl DirectedSwapQuote {
+    /// Quote exactly the requested swap shape against a borrowed PoolState
+    /// 
+    /// Parameters:
+    ///     - pool: a pointer to the PoolState
+    ///     - input_mint: a pointer to the mint swapped into the pool, the pool's mint A or mint B
+    ///     - mode: whether amount is the exact input or the exact output
+    ///     - amount: the exact input or output amount, in base units
+    ///     - slippage_bps: the slippage tolerance in basis points
+    /// 
+    /// Returns:
+    ///     - The DirectedSwapQuote or an OrcaQuoterError
+    pub fn from_pool_state(
+        pool: &PoolState, input_mint: &Pubkey, mode: SwapMode, amount: u64, slippage_bps: u16
+    ) -> Result<DirectedSwapQuote, OrcaQuoterError> {
+        // 1) Validate the input mint against the pool's mints
+        let (a_to_b, output_mint): (bool, Pubkey) = swap_direction(pool, input_mint)?;
+        // The adaptive fee of pools with an oracle is keyed on UNIX seconds
+        let timestamp: u64 = current_unix_timestamp_ms()? / 1000;
+
+        // 2) specified_token_a names the input token for exact-in quotes and the output token 
+        // for exact-out quotes
+        let quote: SwapModeQuote = match mode {
+            SwapMode::ExactIn => SwapModeQuote::ExactIn(
+                quote_exact_in(pool, amount, a_to_b, slippage_bps, timestamp)?
+            ),
+            SwapMode::ExactOut => SwapModeQuote::ExactOut(
+                quote_exact_out(pool, amount, !a_to_b, slippage_bps, timestamp)?
+            ),
+        };
+
+        let (input_data, output_data): (&MintData, &MintData) = match a_to_b {
+            true => (&pool.mint_a_data, &pool.mint_b_data),
+            false => (&pool.mint_b_data, &pool.mint_a_data),
+        };
+
+        Ok(DirectedSwapQuote {
+            input_mint: *input_mint,
+            output_mint,
+            input_label: input_data.label(),
+            output_label: output_data.label(),
+            pool_label: pool.pair_label(),
+            input_decimals: input_data.decimals,
+            output_decimals: output_data.decimals,
+            a_to_b,
+            mode,
             amount,
             slippage_bps,
-        } = params;
-
-        // 1) Convert on-chain types into the core SDK "facade" types
-        // Box as early as possible to avoid large stack allocations
-        let whirlpool_f_box = Box::new(WhirlpoolFacade::from(pool.whirlpool.clone()));
-        let oracle_f_clone = pool.oracle.clone();
-        let tick_array_box_1 = Box::new(pool.current_tick_array.clone());
-        let tick_array_box_2 = Box::new(pool.current_tick_array.clone());
-
-        // 2) Current UNIX timestamp
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|_| "system clock error")?
-            .as_secs();
-        
-
-        let bid = swap_quote_by_input_token(
-            amount,        // token_in amount
-            true,            // specified_token_a = true
-            slippage_bps,
-            *whirlpool_f_box,
-            oracle_f_clone,
-            *tick_array_box_1,
-            timestamp,
-            None,            // no transfer fee on A
-            None,            // no transfer fee on B
-        )
-        .map_err(|_| "failed to compute bid quote")?;
+            quote,
+        })
+    }
+}
 
-        // 4) Exact-out (ask): want `amount_a` of A → pay B
-        let ask = swap_quote_by_output_token(
-            amount,        // token_out amount
-            true,            // specified_token_a = true
-            slippage_bps,
-            *whirlpool_f_box,
-            oracle_f_clone,
-            *tick_array_box_2,
-            timestamp,
-            None, 
-            None,
+/// TryFrom implementation for SwapParams to DirectedSwapQuote, quotes exactly the requested swap shape
+impl TryFrom<SwapParams<'_>> for DirectedSwapQuote {
+    type Error = OrcaQuoterError;
+
+    fn try_from(params: SwapParams<'_>) -> Result<Self, Self::Error> {
+        DirectedSwapQuote::from_pool_state(
+            params.pool, &params.input_mint, params.mode, params.amount, params.slippage_bps
         )
-        .map_err(|_| "failed to compute ask quote")?;
+    }
+}
+
+impl SwapQuote {
+    /// Quote both sides of a base token against a borrowed PoolState
+    ///     - The bid sells amount of base_mint, the ask buys amount of base_mint
+    /// 
+    /// Parameters:
+    ///     - pool: a pointer to the PoolState
+    ///     - base_mint: a pointer to the base token's mint, the pool's mint A or mint B
+    ///     - amount: the amount of the base token, in base units
+    ///     - slippage_bps: the slippage tolerance in basis points
+    /// 
+    /// Returns:
+    ///     - The SwapQuote or an OrcaQuoterError
+    pub fn from_pool_state(
+        pool: &PoolState, base_mint: &Pubkey, amount: u64, slippage_bps: u16
+    ) -> Result<SwapQuote, OrcaQuoterError> {
+        // 1) Validate the base mint against the pool's mints
+        let (base_is_a, quote_mint): (bool, Pubkey) = swap_direction(pool, base_mint)?;
+        // The adaptive fee of pools with an oracle is keyed on UNIX seconds
+        let timestamp: u64 = current_unix_timestamp_ms()? / 1000;
+
+        // 2) Exact-in (bid): sell `amount` of the base token
+        let bid: ExactInSwapQuote = quote_exact_in(pool, amount, base_is_a, slippage_bps, timestamp)?;
+
+        // 3) Exact-out (ask): want `amount` of the base token → pay the other token
+        let ask: ExactOutSwapQuote = quote_exact_out(pool, amount, base_is_a, slippage_bps, timestamp)?;
+
+        // 4) Mid price before and pool prices after each side, the bid swaps the base token in 
+        // and the ask swaps the quote token in, net of transfer and trade fees
+        let (base_data, quote_data): (&MintData, &MintData) = match base_is_a {
+            true => (&pool.mint_a_data, &pool.mint_b_data),
+            false => (&pool.mint_b_data, &pool.mint_a_data),
+        };
+        let mid_price: f64 = base_price(pool, pool.whirlpool.sqrt_price, base_is_a);
+        let bid_curve_in: u64 = try_apply_transfer_fee(bid.token_in, base_data.core_transfer_fee().unwrap_or_default())
+            .map_err(OrcaQuoterError::Quote)?
+            .saturating_sub(bid.trade_fee);
+        let ask_curve_in: u64 = try_apply_transfer_fee(ask.token_est_in, quote_data.core_transfer_fee().unwrap_or_default())
+            .map_err(OrcaQuoterError::Quote)?
+            .saturating_sub(ask.trade_fee);
+        let bid_post_price: f64 = base_price(pool, post_swap_sqrt_price(pool, base_is_a, bid_curve_in)?, base_is_a);
+        let ask_post_price: f64 = base_price(pool, post_swap_sqrt_price(pool, !base_is_a, ask_curve_in)?, base_is_a);
 
         Ok(SwapQuote {
+            base_mint: *base_mint,
+            quote_mint,
+            base_label: base_data.label(),
+            quote_label: quote_data.label(),
+            pool_label: pool.pair_label(),
+            base_decimals: base_data.decimals,
+            quote_decimals: quote_data.decimals,
+            mid_price,
+            bid_post_price,
+            ask_post_price,
+            adaptive_fee: pool.oracle.is_some(),
             amount,
             slippage_bps,
             bid,
@@ -102,15 +319,284 @@ impl<'a> TryFrom<SwapParams> for SwapQuote {
     }
 }
 
+impl SwapQuote {
+    /// The quoted amount of the base token in UI units
+    /// 
+    /// Returns:
+    ///     - amount divided by 10^base_decimals
+    pub fn amount_ui(&self) -> f64 {
+        ui_amount(self.amount, self.base_decimals)
+    }
+
+    /// Effective price of selling amount of the base token
+    /// 
+    /// Returns:
+    ///     - The UI units of the quote token received per base token, None for a zero amount
+    pub fn bid_price(&self) -> Option<f64> {
+        self.price_of(self.bid.token_est_out)
+    }
+
+    /// Effective price of buying amount of the base token
+    /// 
+    /// Returns:
+    ///     - The UI units of the quote token paid per base token, None for a zero amount
+    pub fn ask_price(&self) -> Option<f64> {
+        self.price_of(self.ask.token_est_in)
+    }
+
+    /// Move of the pool price caused by the bid, relative to the mid price
+    /// 
+    /// Returns:
+    ///     - The absolute price impact in basis points
+    pub fn bid_price_impact_bps(&self) -> f64 {
+        price_change_bps(self.mid_price, self.bid_post_price)
+    }
+
+    /// Move of the pool price caused by the ask, relative to the mid price
+    /// 
+    /// Returns:
+    ///     - The absolute price impact in basis points
+    pub fn ask_price_impact_bps(&self) -> f64 {
+        price_change_bps(self.mid_price, self.ask_post_price)
+    }
+
+    /// Round trip cost of buying and selling amount of the base token, relative to the mid price
+    /// 
+    /// Returns:
+    ///     - (ask price - bid price) / mid price in basis points, None for a zero amount
+    pub fn spread_bps(&self) -> Option<f64> {
+        Some((self.ask_price()? - self.bid_price()?) / self.mid_price * 10_000.0)
+    }
+
+    /// Trade fee of the bid in UI units, the bid pays its fee in the base token
+    /// 
+    /// Returns:
+    ///     - bid.trade_fee in UI units of the base token
+    pub fn bid_fee_ui(&self) -> f64 {
+        ui_amount(self.bid.trade_fee, self.base_decimals)
+    }
+
+    /// Trade fee of the ask in UI units, the ask pays its fee in the quote token
+    /// 
+    /// Returns:
+    ///     - ask.trade_fee in UI units of the quote token
+    pub fn ask_fee_ui(&self) -> f64 {
+        ui_amount(self.ask.trade_fee, self.quote_decimals)
+    }
+
+    // Quote token per base token for a raw quote token amount exchanged for amount
+    fn price_of(&self, quote_amount: u64) -> Option<f64> {
+        match self.amount > 0 {
+            true => Some(ui_amount(quote_amount, self.quote_decimals) / self.amount_ui()),
+            false => None,
+        }
+    }
+}
+
+// Private Functions
+
+// The PoolState is only ever borrowed, its TickArrays (up to six 88 tick arrays, ~70KB) are cloned 
+// straight into the core call, and each side is quoted in its own non-inlined frame, so only one 
+// tick array copy of ours is on the stack at any time
+
+/// Resolve the swap direction of a mint against the pool's mints
+/// 
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - input_mint: a pointer to the mint swapped into the pool
+/// 
+/// Returns:
+///     - The (a_to_b, output mint) pair or OrcaQuoterError::InvalidArgument if input_mint is not 
+///       one of the pool's mints
+pub(crate) fn swap_direction(pool: &PoolState, input_mint: &Pubkey) -> Result<(bool, Pubkey), OrcaQuoterError> {
+    let mint_a: Pubkey = pool.mint_a_data.pubkey;
+    let mint_b: Pubkey = pool.mint_b_data.pubkey;
+    if *input_mint == mint_a {
+        Ok((true, mint_b))
+    } else if *input_mint == mint_b {
+        Ok((false, mint_a))
+    } else {
+        Err(OrcaQuoterError::InvalidArgument(format!(
+            "Input mint {} is neither mint A {} nor mint B {} of the pool", input_mint, mint_a, mint_b
+        )))
+    }
+}
+
+/// Exact-in quote against a borrowed PoolState
+/// 
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - token_in: the exact input amount
+///     - specified_token_a: whether the input token is token A
+///     - slippage_bps: the slippage tolerance in basis points
+///     - timestamp: the current UNIX timestamp in seconds
+/// 
+/// Returns:
+///     - The ExactInSwapQuote or OrcaQuoterError::Quote
+#[inline(never)]
+fn quote_exact_in(
+    pool: &PoolState, token_in: u64, specified_token_a: bool, slippage_bps: u16, timestamp: u64
+) -> Result<ExactInSwapQuote, OrcaQuoterError> {
+    swap_quote_by_input_token(
+        token_in,
+        specified_token_a,
+        slippage_bps,
+        pool.whirlpool,
+        pool.oracle,
+        pool.current_tick_array.clone(),
+        timestamp,
+        pool.mint_a_data.core_transfer_fee(),
+        pool.mint_b_data.core_transfer_fee(),
+    )
+    .map_err(OrcaQuoterError::Quote)
+}
+
+/// Exact-out quote against a borrowed PoolState
+/// 
+/// Parameters:
+///     - pool: a pointer to the PoolState
+///     - token_out: the exact output amount
+///     - specified_token_a: whether the output token is token A
+///     - slippage_bps: the slippage tolerance in basis points
+///     - timestamp: the current UNIX timestamp in seconds
+/// 
+/// Returns:
+///     - The ExactOutSwapQuote or OrcaQuoterError::Quote
+#[inline(never)]
+fn quote_exact_out(
+    pool: &PoolState, token_out: u64, specified_token_a: bool, slippage_bps: u16, timestamp: u64
+) -> Result<ExactOutSwapQuote, OrcaQuoterError> {
+    swap_quote_by_output_token(
+        token_out,
+        specified_token_a,
+        slippage_bps,
+        pool.whirlpool,
+        pool.oracle,
+        pool.current_tick_array.clone(),
+        timestamp,
+        pool.mint_a_data.core_transfer_fee(),
+        pool.mint_b_data.core_transfer_fee(),
+    )
+    .map_err(OrcaQuoterError::Quote)
+}
+
+/// Pool price of the base token at a sqrt price
+///     - sqrt_price prices token A in token B, so the price is inverted for a base token B
+/// 
+/// Parameters:
+///     - pool: a pointer to the PoolState, for the mints' decimals
+///     - sqrt_price: the Q64.64 sqrt price
+///     - base_is_a: whether the base token is token A
+/// 
+/// Returns:
+///     - The price in UI units of the quote token per base token
+fn base_price(pool: &PoolState, sqrt_price: u128, base_is_a: bool) -> f64 {
+    let price_a_in_b: f64 = sqrt_price_to_price(sqrt_price, pool.mint_a_data.decimals, pool.mint_b_data.decimals);
+    match base_is_a {
+        true => price_a_in_b,
+        false => 1.0 / price_a_in_b,
+    }
+}
+
+/// Relative change between two prices
+/// 
+/// Parameters:
+///     - before: the reference price
+///     - after: the changed price
+/// 
+/// Returns:
+///     - |after - before| / before in basis points
+fn price_change_bps(before: f64, after: f64) -> f64 {
+    ((after - before) / before).abs() * 10_000.0
+}
+
+/// Format a core fee rate range, fee rates are in millionths so 3000 is 30 bps
+/// 
+/// Parameters:
+///     - fee_rate_min: the lowest fee rate applied during the swap
+///     - fee_rate_max: the highest fee rate applied during the swap
+/// 
+/// Returns:
+///     - e.g. "30.00 bps" or "30.00-42.50 bps" if an adaptive fee varied during the swap
+fn fee_rate_range(fee_rate_min: u32, fee_rate_max: u32) -> String {
+    match fee_rate_min == fee_rate_max {
+        true => format!("{:.2} bps", fee_rate_min as f64 / 100.0),
+        false => format!("{:.2}-{:.2} bps", fee_rate_min as f64 / 100.0, fee_rate_max as f64 / 100.0),
+    }
+}
+
 /// Pretty printing for SwapQuote str
 /// Prints as follows if we just run a "println!("{}", quote);"
 impl fmt::Display for SwapQuote {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "SwapQuote {{")?;
-        writeln!(f, "  amount_a:     {}", self.amount)?;
+        let base_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, self.base_decimals), self.base_label);
+        let quote_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, self.quote_decimals), self.quote_label);
+        let price = |price: Option<f64>| price.map_or("n/a".to_string(), |price| format!("{:.6}", price));
+
+        writeln!(f, "SwapQuote {} {{", self.pool_label)?;
+        writeln!(f, "  base_mint:    {} ({})", self.base_label, self.base_mint)?;
+        writeln!(f, "  quote_mint:   {} ({})", self.quote_label, self.quote_mint)?;
+        writeln!(f, "  amount:       {} ({})", base_ui(self.amount), self.amount)?;
         writeln!(f, "  slippage_bps: {}", self.slippage_bps)?;
-        writeln!(f, "  bid:          {:?}", self.bid)?;
-        write!(f,   "  ask:          {:?}", self.ask)?;
+        writeln!(f, "  mid_price:    {:.6} {} per {}", self.mid_price, self.quote_label, self.base_label)?;
+        writeln!(
+            f, "  bid:          sell {} → {} (min {}), price {}", 
+            base_ui(self.amount), quote_ui(self.bid.token_est_out), quote_ui(self.bid.token_min_out), 
+            price(self.bid_price())
+        )?;
+        writeln!(
+            f, "  ask:          buy {} ← {} (max {}), price {}", 
+            base_ui(self.amount), quote_ui(self.ask.token_est_in), quote_ui(self.ask.token_max_in), 
+            price(self.ask_price())
+        )?;
+        writeln!(
+            f, "  bid impact:   {:.2} bps (post price {:.6}), fee {} ({} raw) at {}", 
+            self.bid_price_impact_bps(), self.bid_post_price, base_ui(self.bid.trade_fee), self.bid.trade_fee, 
+            fee_rate_range(self.bid.trade_fee_rate_min, self.bid.trade_fee_rate_max)
+        )?;
+        writeln!(
+            f, "  ask impact:   {:.2} bps (post price {:.6}), fee {} ({} raw) at {}", 
+            self.ask_price_impact_bps(), self.ask_post_price, quote_ui(self.ask.trade_fee), self.ask.trade_fee, 
+            fee_rate_range(self.ask.trade_fee_rate_min, self.ask.trade_fee_rate_max)
+        )?;
+        writeln!(
+            f, "  spread:       {}{}", 
+            self.spread_bps().map_or("n/a".to_string(), |spread| format!("{:.2} bps", spread)),
+            if self.adaptive_fee { ", adaptive fee" } else { "" }
+        )?;
+        writeln!(f, "  raw bid:      {:?}", self.bid)?;
+        writeln!(f, "  raw ask:      {:?}", self.ask)?;
         writeln!(f, "}}")
     }
-}
\ No newline at end of file
+}
+
+/// Pretty printing for DirectedSwapQuote
+impl fmt::Display for DirectedSwapQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "DirectedSwapQuote {} {{", self.pool_label)?;
+        writeln!(f, "  input_mint:   {} ({})", self.input_label, self.input_mint)?;
+        writeln!(f, "  output_mint:  {} ({})", self.output_label, self.output_mint)?;
+        writeln!(f, "  a_to_b:       {}", self.a_to_b)?;
+        writeln!(f, "  mode:         {:?}", self.mode)?;
+        let input_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, self.input_decimals), self.input_label);
+        let output_ui = |raw: u64| format!("{} {}", format_ui_amount(raw, self.output_decimals), self.output_label);
+        writeln!(f, "  amount:       {}", self.amount)?;
+        writeln!(f, "  slippage_bps: {}", self.slippage_bps)?;
+        match self.mode {
+            SwapMode::ExactIn => writeln!(
+                f, "  swap:         sell {} → {} (min {})", 
+                input_ui(self.token_in_est()), output_ui(self.token_out_est()), output_ui(self.other_amount_threshold())
+            )?,
+            SwapMode::ExactOut => writeln!(
+                f, "  swap:         buy {} ← {} (max {})", 
+                output_ui(self.token_out_est()), input_ui(self.token_in_est()), input_ui(self.other_amount_threshold())
+            )?,
+        }
+        match self.execution_price() {
+            Some(price) => writeln!(f, "  price:        {:.6} {} per {}", price, self.output_label, self.input_label)?,
+            None => writeln!(f, "  price:        n/a")?,
+        }
+        writeln!(f, "  raw quote:    {:?}", self.quote)?;
+        writeln!(f, "}}")
+    }
+}
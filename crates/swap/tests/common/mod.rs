@@ -0,0 +1,115 @@
+// Shared PoolState builders for the swap integration tests, pools are built directly from facades so
+// every quote can be checked against orca_whirlpools_core by hand
+#![allow(dead_code)]
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey
+};
+
+use orca_whirlpools_core::{
+    TickArrayFacade, TickArrays, TickFacade, WhirlpoolFacade, TICK_ARRAY_SIZE
+};
+
+use mint::structs::MintData;
+use pool::structs::{
+    ComponentFetch, FetchMetadata, PoolState, TickArrayWindow
+};
+
+pub const TICK_SPACING: u16 = 64;
+pub const TICK_ARRAY_WIDTH: i32 = TICK_SPACING as i32 * TICK_ARRAY_SIZE as i32;
+pub const POOL_LIQUIDITY: u128 = 1_000_000_000_000;
+// 0.3%, in hundredths of a basis point
+pub const FEE_RATE: u16 = 3000;
+
+
+/// Tick array of uninitialised ticks, offset arrays away from the one starting at tick 0
+pub fn empty_tick_array(offset: i32) -> TickArrayFacade {
+    TickArrayFacade {
+        start_tick_index: offset * TICK_ARRAY_WIDTH,
+        ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+    }
+}
+
+
+/// The window of six tick arrays from offset -2 to 3, with the given ticks initialised
+/// 
+/// Parameters:
+///     - initialized_ticks: (tick index, liquidity_net) of each tick to initialise, on TICK_SPACING
+pub fn tick_arrays_with(initialized_ticks: &[(i32, i128)]) -> TickArrays {
+    let mut tick_arrays: Vec<TickArrayFacade> = (-2..=3).map(empty_tick_array).collect();
+    for (tick_index, liquidity_net) in initialized_ticks {
+        let tick_array: &mut TickArrayFacade = tick_arrays.iter_mut()
+            .find(|tick_array| (tick_array.start_tick_index..tick_array.start_tick_index + TICK_ARRAY_WIDTH).contains(tick_index))
+            .unwrap();
+        let tick: &mut TickFacade = &mut tick_array.ticks[((tick_index - tick_array.start_tick_index) / TICK_SPACING as i32) as usize];
+        tick.initialized = true;
+        tick.liquidity_net = *liquidity_net;
+        tick.liquidity_gross = liquidity_net.unsigned_abs();
+    }
+    let [a, b, c, d, e, f]: [TickArrayFacade; 6] = tick_arrays.try_into().unwrap();
+    TickArrays::Six(a, b, c, d, e, f)
+}
+
+
+/// PoolState at tick 0 with POOL_LIQUIDITY, FEE_RATE, six empty tick arrays and fresh 6 decimal mints
+pub fn pool_state() -> PoolState {
+    pool_state_with(POOL_LIQUIDITY, tick_arrays_with(&[]))
+}
+
+
+/// PoolState at tick 0, i.e. a price of 1.0, with the given liquidity and tick arrays
+pub fn pool_state_with(liquidity: u128, tick_arrays: TickArrays) -> PoolState {
+    let fetch: ComponentFetch = ComponentFetch::from_missing_account(0, 0, "test");
+    PoolState {
+        whirlpool: WhirlpoolFacade {
+            tick_spacing: TICK_SPACING,
+            fee_tier_index_seed: TICK_SPACING.to_le_bytes(),
+            fee_rate: FEE_RATE,
+            liquidity,
+            // sqrt(1.0) in Q64.64
+            sqrt_price: 1 << 64,
+            tick_current_index: 0,
+            ..WhirlpoolFacade::default()
+        },
+        current_tick_array: tick_arrays,
+        oracle: None,
+        mint_a_data: mint_data(6),
+        mint_b_data: mint_data(6),
+        metadata: FetchMetadata {
+            commitment: CommitmentConfig::confirmed(),
+            tick_array_window: TickArrayWindow::new(2, 3).unwrap(),
+            whirlpool: fetch.clone(),
+            tick_arrays: vec![fetch; 6],
+            oracle: None,
+            mint_a: None,
+            mint_b: None,
+        },
+    }
+}
+
+
+/// PoolState like pool_state_with, trading the given mints over six empty tick arrays
+pub fn pool_between(mint_a: &MintData, mint_b: &MintData, liquidity: u128) -> PoolState {
+    PoolState {
+        mint_a_data: mint_a.clone(),
+        mint_b_data: mint_b.clone(),
+        ..pool_state_with(liquidity, tick_arrays_with(&[]))
+    }
+}
+
+
+/// Initialised mint with a fresh pubkey and no transfer fee
+pub fn mint_data(decimals: u8) -> MintData {
+    MintData {
+        decimals,
+        is_initialized: true,
+        token_program: spl_token_program_id(),
+        ..MintData::unfetched(Pubkey::new_unique())
+    }
+}
+
+
+/// The SPL Token program id
+pub fn spl_token_program_id() -> Pubkey {
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".parse().unwrap()
+}
@@ -0,0 +1,99 @@
+// depth_ladder walks the loaded tick arrays level by level in both directions, its amounts only ever
+// grow as the price moves away and it ends where the loaded window runs out
+
+mod common;
+
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, tick_index_to_sqrt_price, try_get_amount_delta_a, try_get_amount_delta_b,
+    INVALID_TICK_ARRAY_SEQUENCE
+};
+
+use pool::structs::PoolState;
+use swap::depth::{
+    depth_ladder, DepthLadder, DepthLevel, DepthSide
+};
+
+use common::{
+    POOL_LIQUIDITY, TICK_ARRAY_WIDTH, pool_state_with, tick_arrays_with
+};
+
+const SQRT_PRICE_ONE: u128 = 1 << 64;
+
+/// Pool at tick 0 with two positions' ticks either side, liquidity halves twice moving away in
+/// either direction
+fn laddered_pool() -> PoolState {
+    let liquidity: i128 = POOL_LIQUIDITY as i128;
+    pool_state_with(POOL_LIQUIDITY, tick_arrays_with(&[
+        (-640, liquidity / 2), (-3_200, liquidity / 4), (1_280, -liquidity / 4), (6_400, -liquidity / 2),
+    ]))
+}
+
+/// Every level of a side in the order the price reaches it, ending at the edge of the window
+fn side_levels(side: &DepthSide) -> Vec<DepthLevel> {
+    side.levels.iter().chain([&side.exhausted_at]).copied().collect()
+}
+
+fn assert_monotonic(side: &DepthSide) {
+    let levels: Vec<DepthLevel> = side_levels(side);
+    for pair in levels.windows(2) {
+        let (before, after): (&DepthLevel, &DepthLevel) = (&pair[0], &pair[1]);
+        match side.a_to_b {
+            true => assert!(after.tick_index < before.tick_index && after.sqrt_price < before.sqrt_price),
+            false => assert!(after.tick_index > before.tick_index && after.sqrt_price > before.sqrt_price),
+        }
+        assert!(after.cumulative_in > before.cumulative_in);
+        assert!(after.cumulative_in_with_fee > before.cumulative_in_with_fee);
+        assert!(after.cumulative_out > before.cumulative_out);
+    }
+    assert!(levels.iter().all(|level| level.cumulative_in_with_fee > level.cumulative_in));
+}
+
+#[test]
+fn levels_sit_on_the_initialised_ticks_in_price_order() {
+    let ladder: DepthLadder = depth_ladder(&laddered_pool()).unwrap();
+    assert_eq!(ladder.tick_current_index, 0);
+    assert_eq!(ladder.mid_price, 1.0);
+
+    let ticks = |side: &DepthSide| -> Vec<i32> { side.levels.iter().map(|level| level.tick_index).collect() };
+    assert_eq!(ticks(&ladder.a_to_b), vec![-640, -3_200]);
+    assert_eq!(ticks(&ladder.b_to_a), vec![1_280, 6_400]);
+
+    // Liquidity after each crossing, selling A subtracts liquidity_net and selling B adds it
+    let liquidity_after = |side: &DepthSide| -> Vec<u128> { side.levels.iter().map(|level| level.liquidity_after).collect() };
+    assert_eq!(liquidity_after(&ladder.a_to_b), vec![POOL_LIQUIDITY / 2, POOL_LIQUIDITY / 4]);
+    assert_eq!(liquidity_after(&ladder.b_to_a), vec![POOL_LIQUIDITY * 3 / 4, POOL_LIQUIDITY / 4]);
+}
+
+#[test]
+fn cumulative_amounts_grow_monotonically() {
+    let ladder: DepthLadder = depth_ladder(&laddered_pool()).unwrap();
+    assert_monotonic(&ladder.a_to_b);
+    assert_monotonic(&ladder.b_to_a);
+
+    // The first level is one constant liquidity segment away, input rounds up and output down
+    let first: &DepthLevel = &ladder.a_to_b.levels[0];
+    let tick_sqrt_price: u128 = tick_index_to_sqrt_price(-640);
+    assert_eq!(first.sqrt_price, tick_sqrt_price);
+    assert_eq!(first.cumulative_in, try_get_amount_delta_a(SQRT_PRICE_ONE, tick_sqrt_price, POOL_LIQUIDITY, true).unwrap());
+    assert_eq!(first.cumulative_out, try_get_amount_delta_b(SQRT_PRICE_ONE, tick_sqrt_price, POOL_LIQUIDITY, false).unwrap());
+}
+
+#[test]
+fn each_side_ends_at_the_edge_of_the_loaded_window() {
+    let pool: PoolState = laddered_pool();
+    let ladder: DepthLadder = depth_ladder(&pool).unwrap();
+    // The window holds the tick arrays from offset -2 to 3, walking up stops on its last tick
+    assert_eq!(ladder.a_to_b.exhausted_at.tick_index, -2 * TICK_ARRAY_WIDTH);
+    assert_eq!(ladder.b_to_a.exhausted_at.tick_index, 4 * TICK_ARRAY_WIDTH - 1);
+    assert_eq!(ladder.a_to_b.exhausted_at.liquidity_net, 0);
+
+    // The core quote takes just under the exhausted size and fails just over it
+    for side in [&ladder.a_to_b, &ladder.b_to_a] {
+        let exhausted_in: u64 = side.exhausted_at.cumulative_in_with_fee;
+        let quote = |amount: u64| swap_quote_by_input_token(
+            amount, side.a_to_b, 0, pool.whirlpool, None, pool.current_tick_array.clone(), 0, None, None
+        );
+        assert!(quote(exhausted_in / 100 * 99).is_ok());
+        assert_eq!(quote(exhausted_in / 100 * 101).unwrap_err(), INVALID_TICK_ARRAY_SEQUENCE);
+    }
+}
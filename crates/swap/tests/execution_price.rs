@@ -0,0 +1,123 @@
+// Bid, ask and execution prices are the UI amounts of a quote divided by each other, so mints of
+// different decimals price in UI units rather than raw units
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+
+use mint::{
+    mint::ui_amount, structs::MintData
+};
+use pool::structs::PoolState;
+use swap::{
+    router::{quote_route, RouteHop, RouteQuote},
+    structs::{DirectedSwapQuote, SwapMode, SwapParams, SwapQuote}
+};
+
+use common::{
+    FEE_RATE, POOL_LIQUIDITY, mint_data, pool_between
+};
+
+const SLIPPAGE_BPS: u16 = 50;
+
+/// Whether value lies within tolerance of expected, relative to expected
+fn within(value: f64, expected: f64, tolerance: f64) -> bool {
+    (value - expected).abs() <= expected.abs() * tolerance
+}
+
+/// 9 decimal mint A and 6 decimal mint B at a raw price of 1.0, i.e. 1 000 B per A in UI units
+fn nine_to_six_decimal_pool() -> PoolState {
+    pool_between(&mint_data(9), &mint_data(6), POOL_LIQUIDITY)
+}
+
+#[test]
+fn bid_and_ask_prices_are_in_ui_units_of_the_quote_token() {
+    let pool_state: PoolState = nine_to_six_decimal_pool();
+    // 0.001 A, small against the pool's liquidity so the price barely moves
+    let quote: SwapQuote = SwapQuote::from_pool_state(&pool_state, &pool_state.mint_a_data.pubkey, 1_000_000, SLIPPAGE_BPS)
+        .unwrap();
+
+    assert_eq!(quote.amount_ui(), 0.001);
+    let bid_price: f64 = quote.bid_price().unwrap();
+    let ask_price: f64 = quote.ask_price().unwrap();
+    assert_eq!(bid_price, ui_amount(quote.bid.token_est_out, 6) / 0.001);
+    assert_eq!(ask_price, ui_amount(quote.ask.token_est_in, 6) / 0.001);
+
+    // Both sides pay the fee around the 1 000 mid price
+    let fee: f64 = FEE_RATE as f64 / 1_000_000.0;
+    assert!(within(quote.mid_price, 1_000.0, 1e-9));
+    assert!(within(bid_price, 1_000.0 * (1.0 - fee), 1e-3), "{bid_price}");
+    assert!(within(ask_price, 1_000.0 / (1.0 - fee), 1e-3), "{ask_price}");
+    assert!(bid_price < quote.mid_price && quote.mid_price < ask_price);
+}
+
+#[test]
+fn a_zero_amount_has_no_price() {
+    let pool_state: PoolState = nine_to_six_decimal_pool();
+    let mut quote: SwapQuote = SwapQuote::from_pool_state(&pool_state, &pool_state.mint_a_data.pubkey, 1_000_000, SLIPPAGE_BPS)
+        .unwrap();
+    quote.amount = 0;
+
+    assert_eq!(quote.bid_price(), None);
+    assert_eq!(quote.ask_price(), None);
+    assert_eq!(quote.spread_bps(), None);
+}
+
+#[test]
+fn execution_price_is_output_per_input_in_ui_units() {
+    let pool_state: PoolState = nine_to_six_decimal_pool();
+
+    for (input_mint, expected) in [(pool_state.mint_a_data.pubkey, 1_000.0), (pool_state.mint_b_data.pubkey, 0.001)] {
+        for mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+            let quote: DirectedSwapQuote = DirectedSwapQuote::try_from(SwapParams {
+                pool: &pool_state, amount: 1_000_000, slippage_bps: SLIPPAGE_BPS, input_mint, mode,
+            }).unwrap();
+            let execution_price: f64 = quote.execution_price().unwrap();
+
+            assert_eq!(
+                execution_price,
+                ui_amount(quote.token_out_est(), quote.output_decimals) / ui_amount(quote.token_in_est(), quote.input_decimals)
+            );
+            assert!(within(execution_price, expected, 1e-2), "{mode:?} from {input_mint}: {execution_price}");
+            assert!(execution_price < expected);
+        }
+    }
+}
+
+#[test]
+fn route_execution_price_spans_the_first_input_and_last_output() {
+    let mints: [MintData; 3] = [mint_data(9), mint_data(6), mint_data(8)];
+    let pool_states: Vec<(Pubkey, PoolState)> = vec![
+        (Pubkey::new_unique(), pool_between(&mints[0], &mints[1], POOL_LIQUIDITY)),
+        (Pubkey::new_unique(), pool_between(&mints[1], &mints[2], POOL_LIQUIDITY)),
+    ];
+    let path: Vec<RouteHop> = vec![
+        RouteHop { pool_index: 0, input_mint: mints[0].pubkey, output_mint: mints[1].pubkey },
+        RouteHop { pool_index: 1, input_mint: mints[1].pubkey, output_mint: mints[2].pubkey },
+    ];
+    let mut route: RouteQuote = quote_route(&pool_states, &path, SwapMode::ExactIn, 1_000_000, SLIPPAGE_BPS).unwrap();
+
+    // 1 A is 1 000 B is 10 C at raw prices of 1.0, less two fees
+    let execution_price: f64 = route.execution_price().unwrap();
+    assert_eq!(execution_price, ui_amount(route.token_out_est, 8) / ui_amount(route.token_in_est, 9));
+    assert!(within(execution_price, 10.0, 1e-2), "{execution_price}");
+
+    route.token_in_est = 0;
+    assert_eq!(route.execution_price(), None);
+    route.legs.clear();
+    assert_eq!(route.execution_price(), None);
+}
+
+#[test]
+fn quotes_of_mints_with_extreme_decimals_print() {
+    let pool_state: PoolState = pool_between(&mint_data(39), &mint_data(255), POOL_LIQUIDITY);
+    let quote: SwapQuote = SwapQuote::from_pool_state(&pool_state, &pool_state.mint_a_data.pubkey, 1_000_000, SLIPPAGE_BPS)
+        .unwrap();
+    let directed: DirectedSwapQuote = DirectedSwapQuote::try_from(SwapParams {
+        pool: &pool_state, amount: 1_000_000, slippage_bps: SLIPPAGE_BPS,
+        input_mint: pool_state.mint_b_data.pubkey, mode: SwapMode::ExactIn,
+    }).unwrap();
+
+    assert!(quote.to_string().contains(&format!("0.{}1", "0".repeat(32))));
+    assert!(!directed.to_string().is_empty());
+}
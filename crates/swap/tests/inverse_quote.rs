@@ -0,0 +1,113 @@
+// max_input_for_price_impact and max_input_for_sqrt_price size a trade to a target price in closed
+// form, swapping the computed input lands the pool on the target within the f64 precision of the
+// bps to sqrt price conversion
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::{
+    sqrt_price_to_price, tick_index_to_sqrt_price, try_get_amount_delta_a, try_get_amount_delta_b
+};
+
+use error::error::OrcaQuoterError;
+use pool::structs::PoolState;
+use swap::depth::{
+    depth_ladder, max_input_for_price_impact, max_input_for_sqrt_price, post_swap_sqrt_price, DepthLadder, InverseQuote
+};
+
+use common::{
+    FEE_RATE, POOL_LIQUIDITY, pool_state, pool_state_with, tick_arrays_with
+};
+
+const SQRT_PRICE_ONE: u128 = 1 << 64;
+// f64 carries about 1e-15 relative precision, checked with ample margin
+const TOLERANCE_BPS: f64 = 1e-6;
+
+/// Same ticks as the depth ladder tests, liquidity halves twice moving away in either direction
+fn laddered_pool() -> PoolState {
+    let liquidity: i128 = POOL_LIQUIDITY as i128;
+    pool_state_with(POOL_LIQUIDITY, tick_arrays_with(&[
+        (-640, liquidity / 2), (-3_200, liquidity / 4), (1_280, -liquidity / 4), (6_400, -liquidity / 2),
+    ]))
+}
+
+/// Price of token A in token B at a sqrt price, both mints have the same decimals
+fn price(sqrt_price: u128) -> f64 {
+    sqrt_price_to_price(sqrt_price, 6, 6)
+}
+
+/// Price impact in bps of swapping curve_amount_in into the pool from a price of 1.0
+fn realised_impact_bps(pool: &PoolState, a_to_b: bool, curve_amount_in: u64) -> f64 {
+    (price(post_swap_sqrt_price(pool, a_to_b, curve_amount_in).unwrap()) - 1.0).abs() * 10_000.0
+}
+
+#[test]
+fn a_target_within_one_tick_matches_the_closed_form() {
+    let pool: PoolState = pool_state();
+    let mint_a: Pubkey = pool.mint_a_data.pubkey;
+    let quote: InverseQuote = max_input_for_price_impact(&pool, &mint_a, 100).unwrap();
+
+    assert!(quote.a_to_b && !quote.limited_by_window);
+    assert!((quote.target_price - 0.99).abs() * 10_000.0 < TOLERANCE_BPS);
+    assert_eq!(quote.curve_amount_in, try_get_amount_delta_a(SQRT_PRICE_ONE, quote.target_sqrt_price, POOL_LIQUIDITY, true).unwrap());
+    assert_eq!(quote.amount_out_est, try_get_amount_delta_b(SQRT_PRICE_ONE, quote.target_sqrt_price, POOL_LIQUIDITY, false).unwrap());
+
+    // The static fee is grossed up on top, rounded up
+    let fee_denominator: u64 = 1_000_000;
+    assert_eq!(quote.max_amount_in, (quote.curve_amount_in * fee_denominator).div_ceil(fee_denominator - FEE_RATE as u64));
+}
+
+#[test]
+fn swapping_the_computed_input_hits_the_target_impact() {
+    let pool: PoolState = laddered_pool();
+    let (mint_a, mint_b): (Pubkey, Pubkey) = (pool.mint_a_data.pubkey, pool.mint_b_data.pubkey);
+    // 1_000 bps crosses tick -640 selling A, 2_000 bps crosses tick 1_280 selling B
+    for (input_mint, price_impact_bps) in [(mint_a, 10), (mint_a, 1_000), (mint_b, 10), (mint_b, 2_000)] {
+        let quote: InverseQuote = max_input_for_price_impact(&pool, &input_mint, price_impact_bps).unwrap();
+        assert!(!quote.limited_by_window);
+        let realised: f64 = realised_impact_bps(&pool, quote.a_to_b, quote.curve_amount_in);
+        assert!(
+            (realised - price_impact_bps as f64).abs() < TOLERANCE_BPS,
+            "{} bps targeted, {} bps realised", price_impact_bps, realised
+        );
+    }
+}
+
+#[test]
+fn an_exact_sqrt_price_target_stops_on_it() {
+    let pool: PoolState = laddered_pool();
+    let ladder: DepthLadder = depth_ladder(&pool).unwrap();
+    let target_sqrt_price: u128 = tick_index_to_sqrt_price(-3_200);
+    let quote: InverseQuote = max_input_for_sqrt_price(&pool, &pool.mint_a_data.pubkey, target_sqrt_price).unwrap();
+
+    // Stopping on an initialised tick takes exactly the ladder's cumulative input to reach it
+    assert_eq!(quote.target_sqrt_price, target_sqrt_price);
+    assert_eq!(quote.curve_amount_in, ladder.a_to_b.levels[1].cumulative_in);
+    assert_eq!(quote.amount_out_est, ladder.a_to_b.levels[1].cumulative_out);
+    assert_eq!(post_swap_sqrt_price(&pool, true, quote.curve_amount_in), Ok(target_sqrt_price));
+}
+
+#[test]
+fn targets_beyond_the_window_are_capped_at_its_edge() {
+    let pool: PoolState = laddered_pool();
+    let ladder: DepthLadder = depth_ladder(&pool).unwrap();
+    // A 90% drop is tick -23_028, past the window's lower edge at -11_264
+    let quote: InverseQuote = max_input_for_price_impact(&pool, &pool.mint_a_data.pubkey, 9_000).unwrap();
+    assert!(quote.limited_by_window);
+    assert_eq!(quote.curve_amount_in, ladder.a_to_b.exhausted_at.cumulative_in);
+}
+
+#[test]
+fn invalid_targets_are_rejected() {
+    let pool: PoolState = pool_state();
+    let (mint_a, mint_b): (Pubkey, Pubkey) = (pool.mint_a_data.pubkey, pool.mint_b_data.pubkey);
+    let invalid = |result: Result<InverseQuote, OrcaQuoterError>| matches!(result, Err(OrcaQuoterError::InvalidArgument(_)));
+
+    assert!(invalid(max_input_for_price_impact(&pool, &mint_a, 10_000)));
+    assert!(invalid(max_input_for_price_impact(&pool, &Pubkey::new_unique(), 100)));
+    // Selling A only moves the price down, selling B only up
+    assert!(invalid(max_input_for_sqrt_price(&pool, &mint_a, SQRT_PRICE_ONE + 1)));
+    assert!(invalid(max_input_for_sqrt_price(&pool, &mint_b, SQRT_PRICE_ONE - 1)));
+    assert!(max_input_for_price_impact(&pool, &mint_b, 10_000).is_ok());
+}
@@ -0,0 +1,97 @@
+// post_swap_sqrt_price lands where orca_whirlpools_core's quote leaves the pool, checked against sqrt
+// prices and outputs computed by hand from each constant liquidity segment of the swap
+
+mod common;
+
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, tick_index_to_sqrt_price, try_get_amount_delta_a, try_get_amount_delta_b,
+    try_get_next_sqrt_price_from_b, ExactInSwapQuote, INVALID_TICK_ARRAY_SEQUENCE
+};
+
+use error::error::OrcaQuoterError;
+use pool::structs::PoolState;
+use swap::depth::post_swap_sqrt_price;
+
+use common::{
+    FEE_RATE, POOL_LIQUIDITY, pool_state, pool_state_with, tick_arrays_with
+};
+
+const SQRT_PRICE_ONE: u128 = 1 << 64;
+const FEE_DENOMINATOR: u64 = 1_000_000;
+
+/// Core exact in quote of amount_in, a_to_b names the input token
+fn core_exact_in(pool: &PoolState, amount_in: u64, a_to_b: bool) -> Result<ExactInSwapQuote, &'static str> {
+    swap_quote_by_input_token(amount_in, a_to_b, 0, pool.whirlpool, None, pool.current_tick_array.clone(), 0, None, None)
+}
+
+#[test]
+fn a_swap_within_one_tick_matches_the_closed_form() {
+    let pool: PoolState = pool_state();
+    let amount_in: u64 = 1_000_000;
+    let quote: ExactInSwapQuote = core_exact_in(&pool, amount_in, true).unwrap();
+
+    // The fee comes off the input first, 0.3% of 1_000_000
+    let curve_amount_in: u64 = amount_in - amount_in * FEE_RATE as u64 / FEE_DENOMINATOR;
+    assert_eq!(quote.trade_fee, 3_000);
+    assert_eq!(amount_in - quote.trade_fee, curve_amount_in);
+
+    // From a sqrt price of 1.0, selling dx of token A leaves L / (L + dx), rounded up, and pays out
+    // L * (1.0 - that) of token B, rounded down
+    let expected_sqrt_price: u128 = (POOL_LIQUIDITY << 64).div_ceil(POOL_LIQUIDITY + curve_amount_in as u128);
+    let expected_out: u64 = ((POOL_LIQUIDITY * (SQRT_PRICE_ONE - expected_sqrt_price)) >> 64) as u64;
+    assert_eq!(quote.token_est_out, expected_out);
+    assert_eq!(post_swap_sqrt_price(&pool, true, curve_amount_in), Ok(expected_sqrt_price));
+    assert!(expected_sqrt_price > tick_index_to_sqrt_price(-64));
+}
+
+#[test]
+fn a_swap_crossing_a_tick_continues_with_the_crossed_liquidity() {
+    // Crossing tick 64 upwards adds half the pool's liquidity
+    let liquidity_net: u128 = POOL_LIQUIDITY / 2;
+    let pool: PoolState = pool_state_with(POOL_LIQUIDITY, tick_arrays_with(&[(64, liquidity_net as i128)]));
+    let amount_in: u64 = 10_000_000_000;
+    let quote: ExactInSwapQuote = core_exact_in(&pool, amount_in, false).unwrap();
+
+    // Segment 1: the whole way to tick 64 at POOL_LIQUIDITY, the fee grossed up on top
+    let tick_sqrt_price: u128 = tick_index_to_sqrt_price(64);
+    let segment_1_in: u64 = try_get_amount_delta_b(SQRT_PRICE_ONE, tick_sqrt_price, POOL_LIQUIDITY, true).unwrap();
+    let segment_1_fee: u64 = (segment_1_in * FEE_DENOMINATOR).div_ceil(FEE_DENOMINATOR - FEE_RATE as u64) - segment_1_in;
+    let segment_1_out: u64 = try_get_amount_delta_a(SQRT_PRICE_ONE, tick_sqrt_price, POOL_LIQUIDITY, false).unwrap();
+
+    // Segment 2: the rest, net of its fee, at the crossed liquidity
+    let crossed_liquidity: u128 = POOL_LIQUIDITY + liquidity_net;
+    let remaining: u64 = amount_in - segment_1_in - segment_1_fee;
+    let segment_2_curve_in: u64 = remaining - (remaining * FEE_RATE as u64).div_ceil(FEE_DENOMINATOR);
+    let expected_sqrt_price: u128 = try_get_next_sqrt_price_from_b(
+        tick_sqrt_price, crossed_liquidity, segment_2_curve_in, true
+    ).unwrap();
+    let segment_2_out: u64 = try_get_amount_delta_a(tick_sqrt_price, expected_sqrt_price, crossed_liquidity, false).unwrap();
+
+    assert!(expected_sqrt_price > tick_sqrt_price);
+    assert_eq!(quote.token_est_out, segment_1_out + segment_2_out);
+    assert_eq!(post_swap_sqrt_price(&pool, false, amount_in - quote.trade_fee), Ok(expected_sqrt_price));
+
+    // Without the tick the same curve input would have moved the price further
+    let uncrossed_sqrt_price: u128 = post_swap_sqrt_price(&pool_state(), false, amount_in - quote.trade_fee).unwrap();
+    assert!(uncrossed_sqrt_price > expected_sqrt_price);
+}
+
+#[test]
+fn running_past_the_loaded_tick_arrays_fails_like_the_core_quote() {
+    // The window ends at tick 22_528, a sqrt price of about 3.08, 2.08 * POOL_LIQUIDITY of token B away
+    let pool: PoolState = pool_state();
+    let amount_in: u64 = 10 * POOL_LIQUIDITY as u64;
+
+    assert_eq!(core_exact_in(&pool, amount_in, false).unwrap_err(), INVALID_TICK_ARRAY_SEQUENCE);
+    assert_eq!(
+        post_swap_sqrt_price(&pool, false, amount_in),
+        Err(OrcaQuoterError::Quote(INVALID_TICK_ARRAY_SEQUENCE))
+    );
+}
+
+#[test]
+fn no_input_leaves_the_price_unchanged() {
+    let pool: PoolState = pool_state();
+    assert_eq!(post_swap_sqrt_price(&pool, true, 0), Ok(SQRT_PRICE_ONE));
+    assert_eq!(post_swap_sqrt_price(&pool, false, 0), Ok(SQRT_PRICE_ONE));
+}
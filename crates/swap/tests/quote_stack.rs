@@ -0,0 +1,38 @@
+// Quoting must fit the default 2 MiB stack of spawned threads, e.g. tokio workers and rayon pools
+
+mod common;
+
+use std::thread;
+
+use pool::structs::PoolState;
+use swap::structs::{
+    DirectedSwapQuote, SwapMode, SwapParams, SwapQuote
+};
+
+use common::pool_state;
+
+const TWO_MIB: usize = 2 * 1024 * 1024;
+
+#[test]
+fn quotes_on_a_two_mib_thread() {
+    let handle = thread::Builder::new()
+        .stack_size(TWO_MIB)
+        .spawn(|| {
+            let pool_state: PoolState = pool_state();
+            for input_mint in [pool_state.mint_a_data.pubkey, pool_state.mint_b_data.pubkey] {
+                let quote: SwapQuote = SwapQuote::from_pool_state(&pool_state, &input_mint, 1_000_000, 50).unwrap();
+                assert!(quote.bid.token_est_out > 0);
+                assert!(quote.ask.token_est_in > 0);
+
+                for mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+                    let quote: DirectedSwapQuote = DirectedSwapQuote::try_from(SwapParams {
+                        pool: &pool_state, amount: 1_000_000, slippage_bps: 50, input_mint, mode,
+                    }).unwrap();
+                    assert_eq!(quote.a_to_b, input_mint == pool_state.mint_a_data.pubkey);
+                    assert!(quote.token_in_est() > 0 && quote.token_out_est() > 0);
+                }
+            }
+        })
+        .unwrap();
+    handle.join().unwrap();
+}
@@ -0,0 +1,153 @@
+// best_route_quote and quote_route chain per pool quotes into multi-hop routes, ExactOut routes are
+// quoted backwards from the output and slippage is applied once to the whole route
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, swap_quote_by_output_token, ExactInSwapQuote, ExactOutSwapQuote
+};
+
+use error::error::OrcaQuoterError;
+use mint::structs::MintData;
+use pool::structs::PoolState;
+use swap::{
+    router::{best_route_quote, quote_route, RouteHop, RouteQuote, TokenGraph, MAX_ROUTE_HOPS},
+    structs::SwapMode
+};
+
+use common::{
+    POOL_LIQUIDITY, mint_data, pool_between
+};
+
+const AMOUNT: u64 = 1_000_000_000;
+const SLIPPAGE_BPS: u16 = 100;
+
+/// Mints A, B and C and the pools A/B, B/C and a direct A/C pool with direct_liquidity
+fn triangle(direct_liquidity: u128) -> (Vec<(Pubkey, PoolState)>, [MintData; 3]) {
+    let mints: [MintData; 3] = [mint_data(6), mint_data(6), mint_data(6)];
+    let [a, b, c] = &mints;
+    let pool_states: Vec<(Pubkey, PoolState)> = vec![
+        (Pubkey::new_unique(), pool_between(a, b, POOL_LIQUIDITY)),
+        (Pubkey::new_unique(), pool_between(b, c, POOL_LIQUIDITY)),
+        (Pubkey::new_unique(), pool_between(a, c, direct_liquidity)),
+    ];
+    (pool_states, mints)
+}
+
+fn two_hop_path(mints: &[MintData; 3]) -> Vec<RouteHop> {
+    vec![
+        RouteHop { pool_index: 0, input_mint: mints[0].pubkey, output_mint: mints[1].pubkey },
+        RouteHop { pool_index: 1, input_mint: mints[1].pubkey, output_mint: mints[2].pubkey },
+    ]
+}
+
+/// Core exact in quote of a pool without slippage, a_to_b names the input token
+fn core_exact_in(pool: &PoolState, amount: u64, a_to_b: bool) -> ExactInSwapQuote {
+    swap_quote_by_input_token(amount, a_to_b, 0, pool.whirlpool, None, pool.current_tick_array.clone(), 0, None, None)
+        .unwrap()
+}
+
+/// Core exact out quote of a pool without slippage, b_out names token B as the output
+fn core_exact_out(pool: &PoolState, amount: u64, b_out: bool) -> ExactOutSwapQuote {
+    swap_quote_by_output_token(amount, !b_out, 0, pool.whirlpool, None, pool.current_tick_array.clone(), 0, None, None)
+        .unwrap()
+}
+
+#[test]
+fn token_graph_finds_every_simple_path_within_the_hop_limit() {
+    let (pool_states, mints): (Vec<(Pubkey, PoolState)>, [MintData; 3]) = triangle(POOL_LIQUIDITY);
+    let graph: TokenGraph = TokenGraph::from_pool_states(&pool_states);
+
+    let direct: Vec<Vec<RouteHop>> = graph.paths(&mints[0].pubkey, &mints[2].pubkey, 1);
+    assert_eq!(direct, vec![vec![RouteHop { pool_index: 2, input_mint: mints[0].pubkey, output_mint: mints[2].pubkey }]]);
+
+    let mut paths: Vec<Vec<RouteHop>> = graph.paths(&mints[0].pubkey, &mints[2].pubkey, MAX_ROUTE_HOPS);
+    paths.sort_by_key(|path| path.len());
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[1], two_hop_path(&mints));
+}
+
+#[test]
+fn pools_of_untradeable_mints_are_left_out_of_the_graph() {
+    let (mut pool_states, mints): (Vec<(Pubkey, PoolState)>, [MintData; 3]) = triangle(POOL_LIQUIDITY);
+    pool_states[2].1.mint_b_data.extensions.non_transferable = true;
+    let graph: TokenGraph = TokenGraph::from_pool_states(&pool_states);
+    assert_eq!(graph.paths(&mints[0].pubkey, &mints[2].pubkey, MAX_ROUTE_HOPS), vec![two_hop_path(&mints)]);
+}
+
+#[test]
+fn exact_in_routes_chain_estimated_outputs_and_apply_slippage_once() {
+    let (pool_states, mints): (Vec<(Pubkey, PoolState)>, [MintData; 3]) = triangle(POOL_LIQUIDITY);
+    let route: RouteQuote = quote_route(&pool_states, &two_hop_path(&mints), SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS).unwrap();
+
+    // Leg 2 sells exactly what leg 1 is estimated to pay out
+    let leg_1: ExactInSwapQuote = core_exact_in(&pool_states[0].1, AMOUNT, true);
+    let leg_2: ExactInSwapQuote = core_exact_in(&pool_states[1].1, leg_1.token_est_out, true);
+    assert_eq!(route.legs[0].pool, pool_states[0].0);
+    assert_eq!(route.legs[1].quote.token_in_est(), leg_1.token_est_out);
+    assert_eq!((route.token_in_est, route.token_out_est), (AMOUNT, leg_2.token_est_out));
+    assert_eq!((route.input_mint, route.output_mint), (mints[0].pubkey, mints[2].pubkey));
+
+    // 1% off the final estimate, rather than 1% off each leg
+    let expected_min_out: u64 = (leg_2.token_est_out as u128 * (10_000 - SLIPPAGE_BPS) as u128 / 10_000) as u64;
+    assert_eq!(route.other_amount_threshold, expected_min_out);
+    let compounded_min_out: u64 = (leg_2.token_est_out as u128 * 9_900 * 9_900 / 100_000_000) as u64;
+    assert!(route.other_amount_threshold > compounded_min_out);
+}
+
+#[test]
+fn exact_out_routes_are_quoted_backwards_from_the_output() {
+    let (pool_states, mints): (Vec<(Pubkey, PoolState)>, [MintData; 3]) = triangle(POOL_LIQUIDITY);
+    let route: RouteQuote = quote_route(&pool_states, &two_hop_path(&mints), SwapMode::ExactOut, AMOUNT, SLIPPAGE_BPS).unwrap();
+
+    // Leg 2 buys the requested output, leg 1 buys exactly what leg 2 is estimated to cost
+    let leg_2: ExactOutSwapQuote = core_exact_out(&pool_states[1].1, AMOUNT, true);
+    let leg_1: ExactOutSwapQuote = core_exact_out(&pool_states[0].1, leg_2.token_est_in, true);
+    assert_eq!(route.legs[1].quote.token_out_est(), AMOUNT);
+    assert_eq!(route.legs[0].quote.token_out_est(), leg_2.token_est_in);
+    assert_eq!((route.token_in_est, route.token_out_est), (leg_1.token_est_in, AMOUNT));
+
+    // 1% on top of the first leg's estimated input, rounded up
+    let expected_max_in: u64 = (leg_1.token_est_in as u128 * (10_000 + SLIPPAGE_BPS) as u128).div_ceil(10_000) as u64;
+    assert_eq!(route.other_amount_threshold, expected_max_in);
+}
+
+#[test]
+fn best_route_prefers_the_deeper_path() {
+    // A shallow direct pool loses to the two hop route, a deep one wins
+    let (shallow, mints): (Vec<(Pubkey, PoolState)>, [MintData; 3]) = triangle(POOL_LIQUIDITY / 1_000);
+    let (input_mint, output_mint): (Pubkey, Pubkey) = (mints[0].pubkey, mints[2].pubkey);
+    let two_hop: RouteQuote = quote_route(&shallow, &two_hop_path(&mints), SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS).unwrap();
+    for mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+        let best: RouteQuote = best_route_quote(&shallow, &input_mint, &output_mint, mode, AMOUNT, SLIPPAGE_BPS, MAX_ROUTE_HOPS).unwrap();
+        assert_eq!(best.legs.len(), 2, "{:?}", mode);
+    }
+    let best: RouteQuote = best_route_quote(&shallow, &input_mint, &output_mint, SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS, MAX_ROUTE_HOPS).unwrap();
+    assert_eq!(best.token_out_est, two_hop.token_out_est);
+
+    let mut deep: Vec<(Pubkey, PoolState)> = shallow;
+    deep[2].1.whirlpool.liquidity = POOL_LIQUIDITY * 1_000;
+    for mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+        let best: RouteQuote = best_route_quote(&deep, &input_mint, &output_mint, mode, AMOUNT, SLIPPAGE_BPS, MAX_ROUTE_HOPS).unwrap();
+        assert_eq!(best.legs.len(), 1, "{:?}", mode);
+        assert_eq!(best.legs[0].pool, deep[2].0);
+    }
+}
+
+#[test]
+fn invalid_routes_are_rejected() {
+    let (pool_states, mints): (Vec<(Pubkey, PoolState)>, [MintData; 3]) = triangle(POOL_LIQUIDITY);
+    let (a, c): (Pubkey, Pubkey) = (mints[0].pubkey, mints[2].pubkey);
+    let route = |input_mint: &Pubkey, output_mint: &Pubkey, max_hops: usize| -> Result<RouteQuote, OrcaQuoterError> {
+        best_route_quote(&pool_states, input_mint, output_mint, SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS, max_hops)
+    };
+    assert!(matches!(route(&a, &c, 0), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(route(&a, &c, MAX_ROUTE_HOPS + 1), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(route(&a, &a, MAX_ROUTE_HOPS), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(route(&a, &Pubkey::new_unique(), MAX_ROUTE_HOPS), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(
+        quote_route(&pool_states, &[], SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS), Err(OrcaQuoterError::InvalidArgument(_))
+    ));
+}
@@ -0,0 +1,102 @@
+// best_split_quote never does worse than the best single pool and allocates exactly the order's
+// input across the pools of a pair
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+
+use error::error::OrcaQuoterError;
+use mint::structs::MintData;
+use pool::structs::PoolState;
+use swap::{
+    router::{best_split_quote, SplitQuote, DEFAULT_SPLIT_STEPS},
+    structs::{DirectedSwapQuote, SwapMode}
+};
+
+use common::{
+    POOL_LIQUIDITY, mint_data, pool_between
+};
+
+const AMOUNT: u64 = 10_000_000_000;
+const SLIPPAGE_BPS: u16 = 50;
+
+/// Pools of one pair with the given liquidities, and the pair's mints
+fn pools_of_one_pair(liquidities: &[u128]) -> (Vec<(Pubkey, PoolState)>, MintData, MintData) {
+    let (mint_a, mint_b): (MintData, MintData) = (mint_data(6), mint_data(6));
+    let pool_states: Vec<(Pubkey, PoolState)> = liquidities.iter()
+        .map(|liquidity| (Pubkey::new_unique(), pool_between(&mint_a, &mint_b, *liquidity)))
+        .collect();
+    (pool_states, mint_a, mint_b)
+}
+
+/// Estimated output of the whole amount through one pool
+fn single_pool_out(pool: &PoolState, input_mint: &Pubkey, amount: u64) -> u64 {
+    DirectedSwapQuote::from_pool_state(pool, input_mint, SwapMode::ExactIn, amount, 0).unwrap().token_out_est()
+}
+
+#[test]
+fn a_split_beats_the_best_single_pool_and_allocates_the_whole_input() {
+    let (pool_states, mint_a, mint_b): (Vec<(Pubkey, PoolState)>, MintData, MintData) = pools_of_one_pair(
+        &[POOL_LIQUIDITY, POOL_LIQUIDITY / 2, POOL_LIQUIDITY / 4]
+    );
+    let split: SplitQuote = best_split_quote(
+        &pool_states, &mint_a.pubkey, &mint_b.pubkey, AMOUNT, SLIPPAGE_BPS, DEFAULT_SPLIT_STEPS
+    ).unwrap();
+
+    let best_single_pool_out: u64 = pool_states.iter()
+        .map(|(_, pool)| single_pool_out(pool, &mint_a.pubkey, AMOUNT))
+        .max()
+        .unwrap();
+    assert_eq!(split.best_single_pool_out, best_single_pool_out);
+    assert!(split.token_out_est > best_single_pool_out);
+    assert!(split.improvement_bps().unwrap() > 0.0);
+
+    // Every pool takes a share, the deepest the largest, and the shares add up to the order
+    assert_eq!(split.allocations.len(), 3);
+    assert_eq!(split.allocations[0].pool, pool_states[0].0);
+    assert!(split.allocations.windows(2).all(|pair| pair[0].amount_in >= pair[1].amount_in));
+    assert_eq!(split.allocations.iter().map(|allocation| allocation.amount_in).sum::<u64>(), AMOUNT);
+    assert!(split.allocations.iter().all(|allocation| allocation.quote.token_in_est() == allocation.amount_in));
+
+    // Each allocation keeps its own minimum, the aggregate is their sum
+    let token_min_out: u64 = split.allocations.iter().map(|allocation| allocation.quote.other_amount_threshold()).sum();
+    assert_eq!(split.token_min_out, token_min_out);
+    assert_eq!(split.token_out_est, split.allocations.iter().map(|allocation| allocation.quote.token_out_est()).sum::<u64>());
+}
+
+#[test]
+fn a_single_pool_takes_the_whole_order() {
+    let (pool_states, mint_a, mint_b): (Vec<(Pubkey, PoolState)>, MintData, MintData) = pools_of_one_pair(&[POOL_LIQUIDITY]);
+    let split: SplitQuote = best_split_quote(
+        &pool_states, &mint_b.pubkey, &mint_a.pubkey, AMOUNT, SLIPPAGE_BPS, DEFAULT_SPLIT_STEPS
+    ).unwrap();
+    assert_eq!(split.allocations.len(), 1);
+    assert_eq!(split.allocations[0].amount_in, AMOUNT);
+    assert_eq!(split.token_out_est, split.best_single_pool_out);
+    assert_eq!(split.improvement_bps(), Some(0.0));
+}
+
+#[test]
+fn orders_smaller_than_the_step_count_are_still_allocated_in_full() {
+    let (pool_states, mint_a, mint_b): (Vec<(Pubkey, PoolState)>, MintData, MintData) = pools_of_one_pair(
+        &[POOL_LIQUIDITY, POOL_LIQUIDITY]
+    );
+    for amount in [1, 7, DEFAULT_SPLIT_STEPS + 3] {
+        let split: SplitQuote = best_split_quote(
+            &pool_states, &mint_a.pubkey, &mint_b.pubkey, amount, SLIPPAGE_BPS, DEFAULT_SPLIT_STEPS
+        ).unwrap();
+        assert_eq!(split.allocations.iter().map(|allocation| allocation.amount_in).sum::<u64>(), amount);
+        assert!(split.token_out_est >= split.best_single_pool_out);
+    }
+}
+
+#[test]
+fn invalid_splits_are_rejected() {
+    let (pool_states, mint_a, mint_b): (Vec<(Pubkey, PoolState)>, MintData, MintData) = pools_of_one_pair(&[POOL_LIQUIDITY]);
+    let split = |amount: u64, steps: u64, output_mint: &Pubkey| -> Result<SplitQuote, OrcaQuoterError> {
+        best_split_quote(&pool_states, &mint_a.pubkey, output_mint, amount, SLIPPAGE_BPS, steps)
+    };
+    assert!(matches!(split(0, DEFAULT_SPLIT_STEPS, &mint_b.pubkey), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(split(AMOUNT, 0, &mint_b.pubkey), Err(OrcaQuoterError::InvalidArgument(_))));
+    assert!(matches!(split(AMOUNT, DEFAULT_SPLIT_STEPS, &Pubkey::new_unique()), Err(OrcaQuoterError::InvalidArgument(_))));
+}
@@ -0,0 +1,122 @@
+// DirectedSwapQuote quotes exactly the requested one of the four swap shapes, matching the
+// orca_whirlpools_core quote for that shape, and rejects mints that are not the pool's
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, swap_quote_by_output_token, ExactInSwapQuote, ExactOutSwapQuote
+};
+
+use error::error::OrcaQuoterError;
+use pool::structs::PoolState;
+use swap::structs::{
+    DirectedSwapQuote, SwapMode, SwapModeQuote, SwapParams
+};
+
+use common::pool_state;
+
+const AMOUNT: u64 = 1_000_000;
+const SLIPPAGE_BPS: u16 = 50;
+
+/// DirectedSwapQuote through SwapParams
+fn directed_quote(pool: &PoolState, input_mint: Pubkey, mode: SwapMode) -> Result<DirectedSwapQuote, OrcaQuoterError> {
+    DirectedSwapQuote::try_from(SwapParams { pool, amount: AMOUNT, slippage_bps: SLIPPAGE_BPS, input_mint, mode })
+}
+
+/// Core exact in quote, specified_token_a names the input token
+fn core_exact_in(pool: &PoolState, specified_token_a: bool) -> ExactInSwapQuote {
+    swap_quote_by_input_token(
+        AMOUNT, specified_token_a, SLIPPAGE_BPS, pool.whirlpool, None, pool.current_tick_array.clone(), 0, None, None
+    ).unwrap()
+}
+
+/// Core exact out quote, specified_token_a names the output token
+fn core_exact_out(pool: &PoolState, specified_token_a: bool) -> ExactOutSwapQuote {
+    swap_quote_by_output_token(
+        AMOUNT, specified_token_a, SLIPPAGE_BPS, pool.whirlpool, None, pool.current_tick_array.clone(), 0, None, None
+    ).unwrap()
+}
+
+fn assert_exact_in(quote: &DirectedSwapQuote, expected: ExactInSwapQuote) {
+    let SwapModeQuote::ExactIn(core) = quote.quote else { panic!("expected an exact in quote, got {:?}", quote.quote) };
+    assert_eq!((core.token_in, core.token_est_out, core.token_min_out), (expected.token_in, expected.token_est_out, expected.token_min_out));
+    assert_eq!(core.trade_fee, expected.trade_fee);
+    assert_eq!(quote.token_in_est(), AMOUNT);
+    assert_eq!(quote.other_amount_threshold(), expected.token_min_out);
+}
+
+fn assert_exact_out(quote: &DirectedSwapQuote, expected: ExactOutSwapQuote) {
+    let SwapModeQuote::ExactOut(core) = quote.quote else { panic!("expected an exact out quote, got {:?}", quote.quote) };
+    assert_eq!((core.token_out, core.token_est_in, core.token_max_in), (expected.token_out, expected.token_est_in, expected.token_max_in));
+    assert_eq!(core.trade_fee, expected.trade_fee);
+    assert_eq!(quote.token_out_est(), AMOUNT);
+    assert_eq!(quote.other_amount_threshold(), expected.token_max_in);
+}
+
+#[test]
+fn exact_in_selling_a() {
+    let pool: PoolState = pool_state();
+    let quote: DirectedSwapQuote = directed_quote(&pool, pool.mint_a_data.pubkey, SwapMode::ExactIn).unwrap();
+    assert!(quote.a_to_b);
+    assert_eq!((quote.input_mint, quote.output_mint), (pool.mint_a_data.pubkey, pool.mint_b_data.pubkey));
+    assert_exact_in(&quote, core_exact_in(&pool, true));
+    // At a price of 1.0 the fee and the price impact both cost the seller
+    assert!(quote.token_out_est() < AMOUNT);
+}
+
+#[test]
+fn exact_in_selling_b() {
+    let pool: PoolState = pool_state();
+    let quote: DirectedSwapQuote = directed_quote(&pool, pool.mint_b_data.pubkey, SwapMode::ExactIn).unwrap();
+    assert!(!quote.a_to_b);
+    assert_eq!((quote.input_mint, quote.output_mint), (pool.mint_b_data.pubkey, pool.mint_a_data.pubkey));
+    assert_exact_in(&quote, core_exact_in(&pool, false));
+    assert!(quote.token_out_est() < AMOUNT);
+}
+
+#[test]
+fn exact_out_buying_b() {
+    // Paying in A buys B, so the exact output is token B
+    let pool: PoolState = pool_state();
+    let quote: DirectedSwapQuote = directed_quote(&pool, pool.mint_a_data.pubkey, SwapMode::ExactOut).unwrap();
+    assert!(quote.a_to_b);
+    assert_eq!(quote.output_mint, pool.mint_b_data.pubkey);
+    assert_exact_out(&quote, core_exact_out(&pool, false));
+    assert!(quote.token_in_est() > AMOUNT);
+}
+
+#[test]
+fn exact_out_buying_a() {
+    let pool: PoolState = pool_state();
+    let quote: DirectedSwapQuote = directed_quote(&pool, pool.mint_b_data.pubkey, SwapMode::ExactOut).unwrap();
+    assert!(!quote.a_to_b);
+    assert_eq!(quote.output_mint, pool.mint_a_data.pubkey);
+    assert_exact_out(&quote, core_exact_out(&pool, true));
+    assert!(quote.token_in_est() > AMOUNT);
+}
+
+#[test]
+fn the_four_shapes_are_distinct_quotes() {
+    let pool: PoolState = pool_state();
+    let mut quotes: Vec<(u64, u64)> = Vec::new();
+    for input_mint in [pool.mint_a_data.pubkey, pool.mint_b_data.pubkey] {
+        for mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+            let quote: DirectedSwapQuote = directed_quote(&pool, input_mint, mode).unwrap();
+            assert_eq!(quote.mode, mode);
+            quotes.push((quote.token_in_est(), quote.token_out_est()));
+        }
+    }
+    // Selling a fixed input never yields as much as buying that output costs
+    assert!(quotes[0].1 < quotes[1].0 && quotes[2].1 < quotes[3].0);
+}
+
+#[test]
+fn mints_outside_the_pool_are_rejected() {
+    let pool: PoolState = pool_state();
+    for mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+        let result: Result<DirectedSwapQuote, OrcaQuoterError> = directed_quote(&pool, Pubkey::new_unique(), mode);
+        assert!(matches!(result, Err(OrcaQuoterError::InvalidArgument(_))), "{:?}", result);
+    }
+}
@@ -0,0 +1,71 @@
+// Token-2022 transfer fees of either mint are passed into the core quotes, so the input mint's fee is
+// taken before the swap and the output mint's fee after it
+
+mod common;
+
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, swap_quote_by_output_token, ExactInSwapQuote, ExactOutSwapQuote, TransferFee
+};
+
+use mint::structs::TransferFeeData;
+use pool::structs::PoolState;
+use swap::structs::{
+    DirectedSwapQuote, SwapMode, SwapModeQuote
+};
+
+use common::pool_state;
+
+const AMOUNT: u64 = 1_000_000;
+const SLIPPAGE_BPS: u16 = 50;
+const FEE_A: TransferFeeData = TransferFeeData { epoch: 0, maximum_fee: u64::MAX, transfer_fee_basis_points: 100 };
+const FEE_B: TransferFeeData = TransferFeeData { epoch: 0, maximum_fee: 2_000, transfer_fee_basis_points: 300 };
+
+/// The test pool with a transfer fee on both mints
+fn pool_with_transfer_fees() -> PoolState {
+    let mut pool: PoolState = pool_state();
+    pool.mint_a_data.transfer_fee = Some(FEE_A);
+    pool.mint_b_data.transfer_fee = Some(FEE_B);
+    pool
+}
+
+fn core_fee(fee: TransferFeeData) -> Option<TransferFee> {
+    Some(TransferFee::new_with_max(fee.transfer_fee_basis_points, fee.maximum_fee))
+}
+
+#[test]
+fn exact_in_quotes_pay_both_transfer_fees() {
+    let pool: PoolState = pool_with_transfer_fees();
+    let quote: DirectedSwapQuote = DirectedSwapQuote::from_pool_state(
+        &pool, &pool.mint_a_data.pubkey, SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS
+    ).unwrap();
+    let expected: ExactInSwapQuote = swap_quote_by_input_token(
+        AMOUNT, true, SLIPPAGE_BPS, pool.whirlpool, None, pool.current_tick_array.clone(), 0,
+        core_fee(FEE_A), core_fee(FEE_B)
+    ).unwrap();
+    let SwapModeQuote::ExactIn(core) = quote.quote else { panic!("expected an exact in quote") };
+    assert_eq!((core.token_est_out, core.token_min_out), (expected.token_est_out, expected.token_min_out));
+
+    // The fees cost the seller against the same pool without them
+    let mut without_fees: PoolState = pool.clone();
+    without_fees.mint_a_data.transfer_fee = None;
+    without_fees.mint_b_data.transfer_fee = None;
+    let without_fees: DirectedSwapQuote = DirectedSwapQuote::from_pool_state(
+        &without_fees, &pool.mint_a_data.pubkey, SwapMode::ExactIn, AMOUNT, SLIPPAGE_BPS
+    ).unwrap();
+    assert!(quote.token_out_est() < without_fees.token_out_est());
+}
+
+#[test]
+fn exact_out_quotes_gross_up_for_both_transfer_fees() {
+    let pool: PoolState = pool_with_transfer_fees();
+    let quote: DirectedSwapQuote = DirectedSwapQuote::from_pool_state(
+        &pool, &pool.mint_b_data.pubkey, SwapMode::ExactOut, AMOUNT, SLIPPAGE_BPS
+    ).unwrap();
+    // Paying in B buys an exact amount of A
+    let expected: ExactOutSwapQuote = swap_quote_by_output_token(
+        AMOUNT, true, SLIPPAGE_BPS, pool.whirlpool, None, pool.current_tick_array.clone(), 0,
+        core_fee(FEE_A), core_fee(FEE_B)
+    ).unwrap();
+    let SwapModeQuote::ExactOut(core) = quote.quote else { panic!("expected an exact out quote") };
+    assert_eq!((core.token_est_in, core.token_max_in), (expected.token_est_in, expected.token_max_in));
+}